@@ -0,0 +1,82 @@
+use super::{AnchorTable, AnchorIndex};
+
+/**
+Anchor Checker: Check if the pattern in the match of the extension is an anchor
+  - Needed only when the bytes can be matched to the equivalent (not the same) bytes.
+    Then, the pattern fully in the consecutive matches can be not located (not an anchor).
+  - The positions are the offset from the end of the extended anchor
+    (left: reversed offset from the start, right: offset from the end).
+*/
+pub struct AnchorChecker<'a> {
+    anchor_table: &'a AnchorTable,
+    pattern_size: u32,
+    left_pattern_index: u32,
+    left_target_position: u32,
+    right_pattern_index: u32,
+    right_target_position: u32,
+}
+
+impl<'a> AnchorChecker<'a> {
+    pub fn new(
+        anchor_table: &'a AnchorTable,
+        anchor_index: AnchorIndex,
+        pattern_size: u32,
+    ) -> Self {
+        let anchor = &anchor_table.0[anchor_index.0 as usize][anchor_index.1 as usize];
+        Self {
+            anchor_table,
+            pattern_size,
+            left_pattern_index: anchor_index.0,
+            left_target_position: anchor.target_position,
+            right_pattern_index: anchor_index.0 + anchor.pattern_count,
+            right_target_position: anchor.target_position + anchor.pattern_count * pattern_size,
+        }
+    }
+    // The pattern ends at the offsets on the left
+    //  - `query_offset` is the multiple of pattern size
+    //  - `max_walk_back`: count of the patterns on the left of this pattern, that are in the same matches
+    #[inline]
+    pub fn is_anchor_on_left(
+        &self,
+        query_offset: u32,
+        target_offset: u32,
+        max_walk_back: u32,
+    ) -> bool {
+        let pattern_index = self.left_pattern_index - query_offset / self.pattern_size - 1;
+        let target_position = self.left_target_position - target_offset - self.pattern_size;
+        self.is_covered_by_anchor(pattern_index, target_position, max_walk_back)
+    }
+    // The pattern starts at the offsets on the right
+    //  - `max_walk_back`: count of the patterns on the left of this pattern, that are in the same matches
+    #[inline]
+    pub fn is_anchor_on_right(
+        &self,
+        addt_pattern_index: u32,
+        target_offset: u32,
+        max_walk_back: u32,
+    ) -> bool {
+        let pattern_index = self.right_pattern_index + addt_pattern_index;
+        let target_position = self.right_target_position + target_offset;
+        self.is_covered_by_anchor(pattern_index, target_position, max_walk_back)
+    }
+    // The merged anchor is registered at the leftmost pattern
+    fn is_covered_by_anchor(
+        &self,
+        mut pattern_index: u32,
+        mut target_position: u32,
+        max_walk_back: u32,
+    ) -> bool {
+        for walk_back in 0..=max_walk_back {
+            let anchors = &self.anchor_table.0[pattern_index as usize];
+            if let Ok(index) = anchors.binary_search_by_key(&target_position, |anchor| anchor.target_position) {
+                return anchors[index].pattern_count > walk_back
+            }
+            if pattern_index == 0 || target_position < self.pattern_size {
+                break
+            }
+            pattern_index -= 1;
+            target_position -= self.pattern_size;
+        }
+        false
+    }
+}
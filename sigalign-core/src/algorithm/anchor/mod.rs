@@ -1,6 +1,15 @@
-use crate::core::BufferedPatternLocator;
+use crate::core::{BufferedPatternLocator, PatternLocation, regulators::{Penalty, SubstitutionMatrix}};
 use ahash::AHashMap;
 
+mod checker;
+pub use checker::AnchorChecker;
+
+// Maximum number of the patterns searched instead of a pattern with the equivalent bytes
+const MAX_EXPANDED_PATTERN_COUNT: usize = 64;
+// Minimum length of the halves of the pattern located instead of the over-expanded pattern
+//  - The shorter halves occur almost everywhere, so the pattern is not used as a seed.
+const MIN_SUB_PATTERN_SIZE: usize = 8;
+
 /**
 Anchor Table: Sorted target positions by pattern
   - 1st Vec: Pattern index
@@ -33,6 +42,7 @@ impl AnchorTable {
         query: &[u8],
         sorted_target_indices: &[u32],
         pattern_size: u32,
+        penalties: &Penalty,
     ) -> AHashMap<u32, Self> {
         let qry_len = query.len();
         let pattern_count = qry_len / pattern_size as usize;
@@ -43,7 +53,7 @@ impl AnchorTable {
             let qry_pos = pattern_index * pattern_size as usize;
             let pattern = &query[qry_pos..qry_pos+pattern_size as usize];
             
-            let pattern_locations = pattern_locater.locate(pattern, sorted_target_indices);
+            let pattern_locations = Self::locate_pattern(pattern_locater, pattern, sorted_target_indices, penalties);
 
             pattern_locations.into_iter().for_each(|pattern_location| {
                 match anchor_table_by_target_index.get_mut(&pattern_location.target_index) {
@@ -71,6 +81,127 @@ impl AnchorTable {
 
         anchor_table_by_target_index
     }
+    // Locate the pattern
+    //  - If the bytes of the pattern have the equivalent bytes, the patterns of the equivalent bytes
+    //    occurring in the reference are also searched.
+    //  - If the count of the patterns exceeds `MAX_EXPANDED_PATTERN_COUNT`, the halves of the pattern
+    //    are located instead, and the positions where both halves are adjacent are kept.
+    //  - If the halves are shorter than `MIN_SUB_PATTERN_SIZE` (e.g., the run of `N`),
+    //    the pattern is not seedable, and no location is returned.
+    #[inline]
+    fn locate_pattern<L: BufferedPatternLocator>(
+        pattern_locater: &L,
+        pattern: &[u8],
+        sorted_target_indices: &[u32],
+        penalties: &Penalty,
+    ) -> Vec<PatternLocation> {
+        match penalties.get_matrix_with_equivalent_bytes() {
+            Some(substitution_matrix) => Self::locate_expanded_pattern(
+                pattern_locater,
+                pattern,
+                sorted_target_indices,
+                substitution_matrix,
+            ),
+            None => pattern_locater.locate(pattern, sorted_target_indices),
+        }
+    }
+    fn locate_expanded_pattern<L: BufferedPatternLocator>(
+        pattern_locater: &L,
+        pattern: &[u8],
+        sorted_target_indices: &[u32],
+        substitution_matrix: &SubstitutionMatrix,
+    ) -> Vec<PatternLocation> {
+        if pattern.iter().all(|&base| substitution_matrix.get_seed_bases(base).is_empty()) {
+            return pattern_locater.locate(pattern, sorted_target_indices)
+        }
+        let Some(expanded_patterns) = Self::expand_pattern(pattern_locater, pattern, substitution_matrix) else {
+            let half_size = pattern.len() / 2;
+            if half_size < MIN_SUB_PATTERN_SIZE {
+                return Vec::new()
+            }
+            let left_locations = Self::locate_expanded_pattern(
+                pattern_locater, &pattern[..half_size], sorted_target_indices, substitution_matrix,
+            );
+            if left_locations.is_empty() {
+                return left_locations
+            }
+            let right_locations = Self::locate_expanded_pattern(
+                pattern_locater, &pattern[half_size..], sorted_target_indices, substitution_matrix,
+            );
+            return Self::join_adjacent_locations(left_locations, right_locations, half_size as u32)
+        };
+
+        let mut positions_by_target_index: AHashMap<u32, Vec<u32>> = AHashMap::new();
+        expanded_patterns.iter().for_each(|expanded_pattern| {
+            pattern_locater.locate(expanded_pattern, sorted_target_indices).into_iter().for_each(|pattern_location| {
+                positions_by_target_index
+                    .entry(pattern_location.target_index)
+                    .or_default()
+                    .extend(pattern_location.sorted_positions);
+            });
+        });
+
+        positions_by_target_index.into_iter().map(|(target_index, mut positions)| {
+            positions.sort_unstable();
+            positions.dedup();
+            PatternLocation {
+                target_index,
+                sorted_positions: positions,
+            }
+        }).collect()
+    }
+    // Patterns of the equivalent bytes occurring in the reference
+    //  - Extended from the last byte, dropping the suffixes not in the reference.
+    //  - None, if the count exceeds `MAX_EXPANDED_PATTERN_COUNT` (except a single byte).
+    fn expand_pattern<L: BufferedPatternLocator>(
+        pattern_locater: &L,
+        pattern: &[u8],
+        substitution_matrix: &SubstitutionMatrix,
+    ) -> Option<Vec<Vec<u8>>> {
+        let mut suffixes: Vec<Vec<u8>> = vec![Vec::new()];
+        for &base in pattern.iter().rev() {
+            let mut extended_suffixes = Vec::new();
+            for suffix in suffixes.iter() {
+                for &seed_base in std::iter::once(&base).chain(substitution_matrix.get_seed_bases(base)) {
+                    let mut extended_suffix = Vec::with_capacity(suffix.len() + 1);
+                    extended_suffix.push(seed_base);
+                    extended_suffix.extend_from_slice(suffix);
+                    if pattern_locater.get_occurrence_count(&extended_suffix) != 0 {
+                        extended_suffixes.push(extended_suffix);
+                    }
+                }
+            }
+            if extended_suffixes.len() > MAX_EXPANDED_PATTERN_COUNT && pattern.len() > 1 {
+                return None
+            }
+            suffixes = extended_suffixes;
+        }
+        Some(suffixes)
+    }
+    // Positions of the left locations followed by the right locations
+    fn join_adjacent_locations(
+        left_locations: Vec<PatternLocation>,
+        right_locations: Vec<PatternLocation>,
+        left_size: u32,
+    ) -> Vec<PatternLocation> {
+        let right_positions_by_target_index: AHashMap<u32, Vec<u32>> = right_locations.into_iter().map(|pattern_location| {
+            (pattern_location.target_index, pattern_location.sorted_positions)
+        }).collect();
+        left_locations.into_iter().filter_map(|pattern_location| {
+            let right_positions = right_positions_by_target_index.get(&pattern_location.target_index)?;
+            let sorted_positions: Vec<u32> = pattern_location.sorted_positions.into_iter().filter(|position| {
+                right_positions.binary_search(&(position + left_size)).is_ok()
+            }).collect();
+            if sorted_positions.is_empty() {
+                None
+            } else {
+                Some(PatternLocation {
+                    target_index: pattern_location.target_index,
+                    sorted_positions,
+                })
+            }
+        }).collect()
+    }
     fn add_new_positions(
         &mut self,
         pattern_index: usize,
@@ -0,0 +1,137 @@
+use crate::{
+    core::{
+        BufferedPatternLocator, SequenceBuffer,
+        regulators::{
+            Penalty, Cutoff,
+        }
+    },
+    results::{
+        QueryAlignment, TargetAlignment, Alignment,
+        AlignmentOperations,
+    },
+};
+use super::{
+    AnchorTable,
+    WaveFront, TraversedAnchor,
+    SparePenaltyCalculator,
+    semi_global::extend_anchor,
+};
+
+// Regulators and buffers shared by the targets
+pub struct GlobalAlignmentContext<'a> {
+    pub pattern_size: u32,
+    pub penalties: &'a Penalty,
+    pub cutoff: &'a Cutoff,
+    pub spare_penalty_calculator: &'a mut SparePenaltyCalculator,
+    // Buffers
+    pub wave_front: &'a mut WaveFront,
+    pub traversed_anchors_buffer: &'a mut Vec<TraversedAnchor>,
+    pub operations_buffer: &'a mut Vec<AlignmentOperations>,
+}
+
+// Find the global alignment with the least penalty for each target
+//  - The extension is the same as the semi-global, except that
+//    both the target and the query have to be consumed at each end.
+#[inline]
+pub fn global_alignment_algorithm<L: BufferedPatternLocator>(
+    pattern_locater: &L,
+    sequence_buffer: &mut L::Buffer,
+    query: &[u8],
+    sorted_target_indices: &[u32],
+    context: &mut GlobalAlignmentContext,
+) -> QueryAlignment {
+    let mut anchor_table_map = AnchorTable::new_by_target_index(pattern_locater, query, sorted_target_indices, context.pattern_size, context.penalties);
+    let target_alignment_results: Vec<TargetAlignment> = anchor_table_map.iter_mut().filter_map(|(target_index, anchor_table)| {
+        pattern_locater.fill_buffer(*target_index, sequence_buffer);
+        let target = sequence_buffer.buffered_sequence();
+        let optional_alignment = global_alignment_query_to_target(
+            anchor_table,
+            target,
+            query,
+            context,
+        );
+
+        optional_alignment.map(|alignment| {
+            TargetAlignment {
+                index: *target_index,
+                alignments: vec![alignment],
+            }
+        })
+    }).collect();
+
+    QueryAlignment(target_alignment_results)
+}
+
+fn global_alignment_query_to_target(
+    anchor_table: &mut AnchorTable,
+    target: &[u8],
+    query: &[u8],
+    context: &mut GlobalAlignmentContext,
+) -> Option<Alignment> {
+    let GlobalAlignmentContext {
+        pattern_size,
+        penalties,
+        cutoff,
+        spare_penalty_calculator,
+        wave_front,
+        traversed_anchors_buffer,
+        operations_buffer,
+    } = context;
+    // Initialize
+    //   - (1) Clear the buffers
+    operations_buffer.clear();
+    //   - (2) Change the last pattern index
+    spare_penalty_calculator.change_last_pattern_index(
+        anchor_table.0.len() as u32 - 1
+    );
+    //   - (3) The alignment with the least penalty
+    let mut optional_best_alignment: Option<Alignment> = None;
+
+    for pattern_index in 0..anchor_table.0.len() {
+        for anchor_index_in_pattern in 0..anchor_table.0[pattern_index].len() {
+            let skipped = {
+                let anchor = &anchor_table.0[pattern_index][anchor_index_in_pattern];
+                anchor.to_skip
+            };
+            if !skipped {
+                // (1) Extend the anchor if not skipped
+                let optional_extension = extend_anchor(
+                    anchor_table,
+                    (pattern_index as u32, anchor_index_in_pattern as u32),
+                    pattern_size,
+                    spare_penalty_calculator,
+                    target,
+                    query,
+                    penalties,
+                    cutoff,
+                    wave_front,
+                    operations_buffer,
+                    traversed_anchors_buffer,
+                    true,
+                );
+                // (2) Mark skipped anchors:
+                //     As the semi-global, the right traversed anchors are always checked.
+                traversed_anchors_buffer.iter().for_each(|tv| {
+                    if tv.to_skip {
+                        anchor_table.0[
+                            tv.addt_pattern_index as usize
+                        ][
+                            tv.addt_target_position as usize
+                        ].to_skip = true;
+                    }
+                });
+                // (3) Keep the alignment if the penalty is less than the previous one
+                if let Some(extension) = optional_extension {
+                    let is_better = match &optional_best_alignment {
+                        Some(best_alignment) => extension.penalty < best_alignment.penalty,
+                        None => true,
+                    };
+                    if is_better {
+                        optional_best_alignment = Some(extension.parse_anchor_alignment_result(operations_buffer));
+                    }
+                }
+            }
+        }
+    }
+    optional_best_alignment
+}
@@ -6,7 +6,7 @@ use crate::{
     }
 };
 use super::{
-    AnchorTable, AnchorIndex,
+    AnchorTable, AnchorIndex, AnchorChecker,
     WaveFront, WaveFrontScore, BackTraceMarker, TraversedAnchor,
     Extension, SparePenaltyCalculator,
     transform_right_additive_positions_to_traversed_anchor_index,
@@ -48,6 +48,10 @@ pub fn extend_anchor(
 
     let left_query_end_index = anchor_index.0 * pattern_size;
     let right_query_start_index = left_query_end_index + anchor_size;
+    // 1.2. Checker of the anchors in the matches (only if the bytes can be matched to the equivalent bytes)
+    let anchor_checker = penalties.get_matrix_with_equivalent_bytes().map(|_| {
+        AnchorChecker::new(anchor_table, anchor_index, *pattern_size)
+    });
 
     // 2. Extend to the right
     // 2.1. Get slices to extend
@@ -132,6 +136,7 @@ pub fn extend_anchor(
         *pattern_size,
         left_optimal_vpc.component_index,
         penalties,
+        anchor_checker.as_ref(),
         operations_buffer,
     )?;
     // 4.4. Backtrace from right
@@ -142,6 +147,7 @@ pub fn extend_anchor(
         pattern_count,
         right_optimal_vpc.component_index,
         penalties,
+        anchor_checker.as_ref(),
         operations_buffer,
         traversed_anchors_buffer,
     );
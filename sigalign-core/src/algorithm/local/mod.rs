@@ -11,7 +11,7 @@ use crate::{
     },
 };
 use super::{
-    AnchorTable, AnchorIndex,
+    AnchorTable, AnchorIndex, AnchorChecker,
     WaveFront, WaveFrontScore, BackTraceMarker, TraversedAnchor,
     Extension, SparePenaltyCalculator,
     transform_right_additive_positions_to_traversed_anchor_index,
@@ -39,7 +39,7 @@ pub fn local_alignment_algorithm<L: BufferedPatternLocator>(
     traversed_anchors_buffer: &mut Vec<TraversedAnchor>,
     operations_buffer: &mut Vec<AlignmentOperations>,
 ) -> QueryAlignment {
-    let mut anchor_table_map = AnchorTable::new_by_target_index(pattern_locater, query, sorted_target_indices, pattern_size);
+    let mut anchor_table_map = AnchorTable::new_by_target_index(pattern_locater, query, sorted_target_indices, pattern_size, penalties);
 
     let target_alignment_results: Vec<TargetAlignment> = anchor_table_map.iter_mut().filter_map(|(target_index, anchor_table)| {
         pattern_locater.fill_buffer(*target_index, sequence_buffer);
@@ -168,7 +168,7 @@ pub fn local_alignment_algorithm_with_limit<L: BufferedPatternLocator>(
     // Limit of the number of alignments
     mut limit: u32,
 ) -> QueryAlignment {
-    let mut anchor_table_map = AnchorTable::new_by_target_index(pattern_locater, query, sorted_target_indices, pattern_size);
+    let mut anchor_table_map = AnchorTable::new_by_target_index(pattern_locater, query, sorted_target_indices, pattern_size, penalties);
 
     let mut target_alignment_results: Vec<TargetAlignment> = Vec::new();
     for (target_index, anchor_table) in anchor_table_map.iter_mut() {
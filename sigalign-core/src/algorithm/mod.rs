@@ -3,6 +3,7 @@ mod anchor;
 use anchor::{
     Anchor,
     AnchorTable,
+    AnchorChecker,
 };
 pub use anchor::AnchorIndex;
 
@@ -10,6 +11,7 @@ mod wave_front;
 use wave_front::{
     WaveFrontScore,
     BackTraceMarker,
+    first_anchor_in_right_matches,
 };
 pub use wave_front::{WaveFront, TraversedAnchor};
 
@@ -35,3 +37,6 @@ pub use semi_global::{
     semi_global_alignment_algorithm,
     semi_global_alignment_algorithm_with_limit,
 };
+
+mod global;
+pub use global::{global_alignment_algorithm, GlobalAlignmentContext};
@@ -1,6 +1,7 @@
 use crate::core::regulators::{Penalty, PREC_SCALE};
 use super::{
     WaveFront, BackTraceMarker, TraversedAnchor,
+    AnchorChecker, first_anchor_in_right_matches,
 };
 use num::integer::div_rem;
 
@@ -21,6 +22,7 @@ impl WaveFront {
         pattern_count_of_anchor: u32,
         component_index: u32,
         penalties: &Penalty,
+        anchor_checker: Option<&AnchorChecker>,
         traversed_anchors_buffer: &mut Vec<TraversedAnchor>,
     ) {
         traversed_anchors_buffer.clear();
@@ -45,7 +47,8 @@ impl WaveFront {
                     match component.bt {
                         BackTraceMarker::FromM => {
                             // (1) Next penalty
-                            penalty -= penalties.x;
+                            let substitution_penalty = component.substitution_penalty(penalties);
+                            penalty -= substitution_penalty;
                             // (2) Next k
                             // not change
                             // (3) Next WFS
@@ -70,13 +73,15 @@ impl WaveFront {
                             
                             let (quotient, remainder) = div_rem(length_to_two_base_before_match_block, pattern_size as i32);
                             let match_count_of_assumed_anchor = match_count + remainder + 1 - pattern_size as i32;
-                            if match_count_of_assumed_anchor >= pattern_size as i32 {
+                            if let Some((addt_pattern_index, addt_target_position)) = first_anchor_in_right_matches(
+                                anchor_checker, quotient, match_count_of_assumed_anchor, fr, pattern_size,
+                            ) {
                                 // Traversed Anchor Exists
                                 let pd_to_this_tv_matches = (
                                     scaled_maximum_penalty_per_length
                                     * (next_fr + component.insertion_count as i32 + 1) // Length
                                 ) - (
-                                    (penalty + penalties.x) * PREC_SCALE // Penalty
+                                    (penalty + substitution_penalty) * PREC_SCALE // Penalty
                                 ) as i32;
                                 let pd_between_tv_matches = pd_to_previous_tv_matches - pd_to_this_tv_matches;
                                 traversed_anchors_buffer.iter_mut().for_each(|tv| {
@@ -87,8 +92,8 @@ impl WaveFront {
                                 });
                                 pd_to_previous_tv_matches = pd_to_this_tv_matches;
                                 let traversed_anchor = TraversedAnchor {
-                                    addt_pattern_index: (quotient + 1) as u32 + pattern_count_of_anchor,
-                                    addt_target_position: (fr - match_count_of_assumed_anchor) as u32 + anchor_size,
+                                    addt_pattern_index: addt_pattern_index + pattern_count_of_anchor,
+                                    addt_target_position: addt_target_position + anchor_size,
                                     cum_penalty_delta: 0,
                                     to_skip: false
                                 };
@@ -127,7 +132,9 @@ impl WaveFront {
 
                             let (quotient, remainder) = div_rem(length_to_two_base_before_match_block, pattern_size as i32);
                             let match_count_of_assumed_anchor = match_count + remainder + 1 - pattern_size as i32;
-                            if match_count_of_assumed_anchor >= pattern_size as i32 {
+                            if let Some((addt_pattern_index, addt_target_position)) = first_anchor_in_right_matches(
+                                anchor_checker, quotient, match_count_of_assumed_anchor, fr, pattern_size,
+                            ) {
                                 // Traversed Anchor Exists
                                 let pd_to_this_tv_matches = 
                                     scaled_maximum_penalty_per_length
@@ -143,8 +150,8 @@ impl WaveFront {
                                 });
                                 pd_to_previous_tv_matches = pd_to_this_tv_matches;
                                 let traversed_anchor = TraversedAnchor {
-                                    addt_pattern_index: (quotient + 1) as u32 + pattern_count_of_anchor,
-                                    addt_target_position: (fr - match_count_of_assumed_anchor) as u32 + anchor_size,
+                                    addt_pattern_index: addt_pattern_index + pattern_count_of_anchor,
+                                    addt_target_position: addt_target_position + anchor_size,
                                     cum_penalty_delta: 0,
                                     to_skip: false
                                 };
@@ -181,7 +188,9 @@ impl WaveFront {
 
                             let (quotient, remainder) = div_rem(length_to_two_base_before_match_block, pattern_size as i32);
                             let match_count_of_assumed_anchor = match_count + remainder + 1 - pattern_size as i32;
-                            if match_count_of_assumed_anchor >= pattern_size as i32 {
+                            if let Some((addt_pattern_index, addt_target_position)) = first_anchor_in_right_matches(
+                                anchor_checker, quotient, match_count_of_assumed_anchor, fr, pattern_size,
+                            ) {
                                 // Traversed Anchor Exists
                                 let pd_to_this_tv_matches = 
                                     scaled_maximum_penalty_per_length
@@ -197,8 +206,8 @@ impl WaveFront {
                                 });
                                 pd_to_previous_tv_matches = pd_to_this_tv_matches;
                                 let traversed_anchor = TraversedAnchor {
-                                    addt_pattern_index: (quotient + 1) as u32 + pattern_count_of_anchor,
-                                    addt_target_position: (fr - match_count_of_assumed_anchor) as u32 + anchor_size,
+                                    addt_pattern_index: addt_pattern_index + pattern_count_of_anchor,
+                                    addt_target_position: addt_target_position + anchor_size,
                                     cum_penalty_delta: 0,
                                     to_skip: false
                                 };
@@ -7,8 +7,8 @@ use crate::{
     },
 };
 use super::{
-    AnchorTable, AnchorIndex,
-    WaveFront, BackTraceMarker, TraversedAnchor,
+    AnchorTable, AnchorIndex, AnchorChecker,
+    WaveFront, BackTraceMarker, TraversedAnchor, first_anchor_in_right_matches,
     Extension,
     SparePenaltyCalculator,
     transform_right_additive_positions_to_traversed_anchor_index,
@@ -19,7 +19,7 @@ mod backtrace;
 // Return the optional extension of anchor
 //  - None if this anchor is
 //     - invalid
-//        - not meet sequences' end
+//        - not meet sequences' end (both ends of the sequences if `to_both_ends`)
 //        - not satisfy the cutoff
 //     - or not leftmost (= having traversed anchor on the left)
 #[inline]
@@ -36,6 +36,8 @@ pub fn extend_anchor(
     wave_front: &mut WaveFront,
     operations_buffer: &mut Vec<AlignmentOperations>,
     traversed_anchors_buffer: &mut Vec<TraversedAnchor>,
+    // Extend to the end of both the target and the query (global alignment)
+    to_both_ends: bool,
 ) -> Option<Extension> { // None if already used position or not reached to the end
     // 1. Init
     let anchor = &anchor_table.0[anchor_index.0 as usize][anchor_index.1 as usize];
@@ -48,6 +50,10 @@ pub fn extend_anchor(
 
     let left_query_end_index = anchor_index.0 * pattern_size;
     let right_query_start_index = left_query_end_index + anchor_size;
+    // 1.2. Checker of the anchors in the matches (only if the bytes can be matched to the equivalent bytes)
+    let anchor_checker = penalties.get_matrix_with_equivalent_bytes().map(|_| {
+        AnchorChecker::new(anchor_table, anchor_index, *pattern_size)
+    });
 
     // 2. Extend to the right
     // 2.1. Get slices to extend
@@ -56,12 +62,21 @@ pub fn extend_anchor(
     // 2.2. Calculate the left spare penalty
     let right_spare_penalty = spare_penalty_calculator.get_right_spare_penalty(anchor_index.0);
     // 2.3. Extend the side with wave front
-    wave_front.align_right_to_end_point(
-        right_target_slice,
-        right_query_slice,
-        penalties,
-        right_spare_penalty,
-    );
+    if to_both_ends {
+        wave_front.align_right_to_both_ends(
+            right_target_slice,
+            right_query_slice,
+            penalties,
+            right_spare_penalty,
+        );
+    } else {
+        wave_front.align_right_to_end_point(
+            right_target_slice,
+            right_query_slice,
+            penalties,
+            right_spare_penalty,
+        );
+    }
     // 2.4. Check if invalid
     //   - confirm invalid: early drop here
     let right_end_point = match wave_front.get_optional_end_point() {
@@ -75,6 +90,7 @@ pub fn extend_anchor(
                 pattern_count,
                 component_index,
                 penalties,
+                anchor_checker.as_ref(),
                 traversed_anchors_buffer,
             );
             transform_right_additive_positions_to_traversed_anchor_index(
@@ -99,6 +115,7 @@ pub fn extend_anchor(
         pattern_count,
         right_end_point.1,
         penalties,
+        anchor_checker.as_ref(),
         operations_buffer,
         traversed_anchors_buffer,
     );
@@ -126,12 +143,21 @@ pub fn extend_anchor(
         )
     };
     // 3.3. Extend the side with wave front
-    wave_front.align_left_to_end_point(
-        left_target_slice,
-        left_query_slice,
-        penalties,
-        left_spare_penalty,
-    );
+    if to_both_ends {
+        wave_front.align_left_to_both_ends(
+            left_target_slice,
+            left_query_slice,
+            penalties,
+            left_spare_penalty,
+        );
+    } else {
+        wave_front.align_left_to_end_point(
+            left_target_slice,
+            left_query_slice,
+            penalties,
+            left_spare_penalty,
+        );
+    }
     // 3.4. Check if invalid
     //   - confirm invalid: early drop here
     let left_end_point = match wave_front.get_optional_end_point() {
@@ -160,6 +186,7 @@ pub fn extend_anchor(
         *pattern_size,
         left_end_point.1,
         penalties,
+        anchor_checker.as_ref(),
         operations_buffer,
     )?;
 
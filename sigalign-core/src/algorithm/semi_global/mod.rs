@@ -11,14 +11,14 @@ use crate::{
     },
 };
 use super::{
-    AnchorTable, AnchorIndex,
-    WaveFront, BackTraceMarker, TraversedAnchor,
+    AnchorTable, AnchorIndex, AnchorChecker,
+    WaveFront, BackTraceMarker, TraversedAnchor, first_anchor_in_right_matches,
     Extension, SparePenaltyCalculator,
     transform_right_additive_positions_to_traversed_anchor_index,
 };
 
 mod extend;
-use extend::extend_anchor;
+pub(super) use extend::extend_anchor;
 
 // Find all semi-global alignments
 #[inline]
@@ -36,7 +36,7 @@ pub fn semi_global_alignment_algorithm<L: BufferedPatternLocator>(
     traversed_anchors_buffer: &mut Vec<TraversedAnchor>,
     operations_buffer: &mut Vec<AlignmentOperations>,
 ) -> QueryAlignment {
-    let mut anchor_table_map = AnchorTable::new_by_target_index(pattern_locater, query, sorted_target_indices, pattern_size);
+    let mut anchor_table_map = AnchorTable::new_by_target_index(pattern_locater, query, sorted_target_indices, pattern_size, penalties);
     let target_alignment_results: Vec<TargetAlignment> = anchor_table_map.iter_mut().filter_map(|(target_index, anchor_table)| {
         pattern_locater.fill_buffer(*target_index, sequence_buffer);
         let target = sequence_buffer.buffered_sequence();
@@ -109,6 +109,7 @@ fn semi_global_alignment_query_to_target(
                     wave_front,
                     operations_buffer,
                     traversed_anchors_buffer,
+                    false,
                 );
                 // After extension, "traversed_anchors_buffer" is filled with right traversed anchors
 
@@ -155,7 +156,7 @@ pub fn semi_global_alignment_algorithm_with_limit<L: BufferedPatternLocator>(
     // Limit of the number of alignments
     mut limit: u32,
 ) -> QueryAlignment {
-    let mut anchor_table_map = AnchorTable::new_by_target_index(pattern_locater, query, sorted_target_indices, pattern_size);
+    let mut anchor_table_map = AnchorTable::new_by_target_index(pattern_locater, query, sorted_target_indices, pattern_size, penalties);
     let mut target_alignment_results: Vec<TargetAlignment> = Vec::new();
 
     for (target_index, anchor_table) in anchor_table_map.iter_mut() {
@@ -236,6 +237,7 @@ fn semi_global_alignment_query_to_target_with_limit(
                     wave_front,
                     operations_buffer,
                     traversed_anchors_buffer,
+                    false,
                 );
                 // After extension, "traversed_anchors_buffer" is filled with right traversed anchors
                 // (2) If extension exists
@@ -15,7 +15,7 @@ Failure to follow these usage rules could lead to unexpected behavior.
 pub struct SparePenaltyCalculator {
     precalculated_right_spare_penalty: Vec<u32>,
     last_pattern_index: u32,
-    coefficient_for_right: (u32, u32, u32),
+    coefficient_for_right: (u32, i64, u32),
     coefficient_for_left: (u32, u32, u32, u32),
     min_penalty: u32,
 }
@@ -68,9 +68,10 @@ impl SparePenaltyCalculator {
         //   - f(x) = (a * x + b) / c
         //   - x: reversed pattern index (= last pattern index - pattern index)
         //   - all coefficient is scaled
+        //   - b can be negative with the small pattern size (e.g., protein)
         let a = maximum_scaled_penalty_per_length * penalties.e * pattern_size;
-        let b = maximum_scaled_penalty_per_length * (
-            penalties.e * (3 * pattern_size - 2) - penalties.o
+        let b = maximum_scaled_penalty_per_length as i64 * (
+            (penalties.e * (3 * pattern_size - 2)) as i64 - penalties.o as i64
         );
         let c = penalties.e * PREC_SCALE - maximum_scaled_penalty_per_length;
 
@@ -101,10 +102,10 @@ impl SparePenaltyCalculator {
         let calculated_pattern_count = self.precalculated_right_spare_penalty.len() as u32;
         let ce = &self.coefficient_for_right;
         for reversed_pattern_index in calculated_pattern_count..max_pattern_count {
-            let v = u32::max(
-                (ce.0 * reversed_pattern_index + ce.1) / ce.2,
-                self.min_penalty,
-            );
+            let v = i64::max(
+                ((ce.0 * reversed_pattern_index) as i64 + ce.1) / ce.2 as i64,
+                self.min_penalty as i64,
+            ) as u32;
             self.precalculated_right_spare_penalty.push(v);
         }
     }
@@ -6,6 +6,7 @@ use crate::{
 };
 use super::{
     WaveFront, BackTraceMarker,
+    super::AnchorChecker,
 };
 use num::integer::div_rem;
 
@@ -48,10 +49,13 @@ impl WaveFront {
         pattern_size: u32,
         component_index: u32,
         penalties: &Penalty,
+        anchor_checker: Option<&AnchorChecker>,
         operations_buffer: &mut Vec<AlignmentOperations>,
     ) -> Option<(u32, u32)> { // Return leftmost anchor index if it is not used as result
+        // Separator from the previous operations
+        //   - Match is never merged with the next operation (a side can start with a gap in the global alignment)
         operations_buffer.push(AlignmentOperations {
-            operation: AlignmentOperation::Deletion,
+            operation: AlignmentOperation::Match,
             count: 0,
         });
         let operation_start_index = operations_buffer.len() as u32;
@@ -73,7 +77,8 @@ impl WaveFront {
                     match component.bt {
                         BackTraceMarker::FromM => {
                             // (1) Next penalty
-                            penalty -= penalties.x;
+                            let substitution_penalty = component.substitution_penalty(penalties);
+                            penalty -= substitution_penalty;
                             // (2) Next k
                             // not change
                             // (3) Next WFS
@@ -87,9 +92,7 @@ impl WaveFront {
                             // (7) Check traversed
                             let match_count = fr - next_fr - 1;
 
-                            let remainder = (fr - k) % pattern_size as i32; // fr - k = query_index_of_first_match
-                            let match_count_of_next_pattern = match_count - remainder;
-                            if match_count_of_next_pattern >= pattern_size as i32 {
+                            if left_matches_have_anchor(anchor_checker, fr - k, match_count, k, pattern_size) {
                                 return None
                             }
                             
@@ -142,9 +145,7 @@ impl WaveFront {
                             // (7) Check traversed
                             let match_count = fr - next_fr;
 
-                            let remainder = (fr - k) % pattern_size as i32;
-                            let match_count_of_next_pattern = match_count - remainder;
-                            if match_count_of_next_pattern >= pattern_size as i32 {
+                            if left_matches_have_anchor(anchor_checker, fr - k, match_count, k, pattern_size) {
                                 return None
                             }
 
@@ -175,9 +176,7 @@ impl WaveFront {
                             let next_fr = component.fr;
                             // (7) Check traversed
                             let match_count = fr-next_fr;
-                            let remainder = (fr - k) % pattern_size as i32;
-                            let match_count_of_next_pattern = match_count - remainder;
-                            if match_count_of_next_pattern >= pattern_size as i32 {
+                            if left_matches_have_anchor(anchor_checker, fr - k, match_count, k, pattern_size) {
                                 return None
                             }
                             // (8) Add operation
@@ -193,6 +192,11 @@ impl WaveFront {
                             fr = next_fr;
                         },
                         _ => { // START_POINT
+                            // Check traversed
+                            //  - Without the checker, the matches from the start point can not have an anchor.
+                            if anchor_checker.is_some() && left_matches_have_anchor(anchor_checker, fr - k, fr, k, pattern_size) {
+                                return None
+                            }
                             // Add operation
                             if fr != 0 {
                                 operations_buffer.push(
@@ -356,13 +360,16 @@ impl WaveFront {
         pattern_count_of_anchor: u32,
         component_index: u32,
         penalties: &Penalty,
+        anchor_checker: Option<&AnchorChecker>,
         operations_buffer: &mut Vec<AlignmentOperations>,
         traversed_anchors_buffer: &mut Vec<TraversedAnchor>,
     ) -> (u32, u32) { // Return operation range in buffer
         traversed_anchors_buffer.clear();
 
+        // Separator from the previous operations
+        //   - Match is never merged with the next operation (a side can start with a gap in the global alignment)
         operations_buffer.push(AlignmentOperations {
-            operation: AlignmentOperation::Deletion,
+            operation: AlignmentOperation::Match,
             count: 0,
         });
         let operation_start_index = operations_buffer.len() as u32;
@@ -388,7 +395,8 @@ impl WaveFront {
                     match component.bt {
                         BackTraceMarker::FromM => {
                             // (1) Next penalty
-                            penalty -= penalties.x;
+                            let substitution_penalty = component.substitution_penalty(penalties);
+                            penalty -= substitution_penalty;
                             // (2) Next k
                             // not change
                             // (3) Next WFS
@@ -413,13 +421,15 @@ impl WaveFront {
                             
                             let (quotient, remainder) = div_rem(length_to_two_base_before_match_block, pattern_size as i32);
                             let match_count_of_assumed_anchor = match_count + remainder + 1 - pattern_size as i32;
-                            if match_count_of_assumed_anchor >= pattern_size as i32 {
+                            if let Some((addt_pattern_index, addt_target_position)) = first_anchor_in_right_matches(
+                                anchor_checker, quotient, match_count_of_assumed_anchor, fr, pattern_size,
+                            ) {
                                 // Traversed Anchor Exists
                                 let pd_to_this_tv_matches = (
                                     scaled_maximum_penalty_per_length
                                     * (next_fr + component.insertion_count as i32 + 1) // Length
                                 ) - (
-                                    (penalty + penalties.x) * PREC_SCALE // Penalty
+                                    (penalty + substitution_penalty) * PREC_SCALE // Penalty
                                 ) as i32;
                                 let pd_between_tv_matches = pd_to_previous_tv_matches - pd_to_this_tv_matches;
                                 traversed_anchors_buffer.iter_mut().for_each(|tv| {
@@ -430,8 +440,8 @@ impl WaveFront {
                                 });
                                 pd_to_previous_tv_matches = pd_to_this_tv_matches;
                                 let traversed_anchor = TraversedAnchor {
-                                    addt_pattern_index: (quotient + 1) as u32 + pattern_count_of_anchor,
-                                    addt_target_position: (fr - match_count_of_assumed_anchor) as u32 + anchor_size,
+                                    addt_pattern_index: addt_pattern_index + pattern_count_of_anchor,
+                                    addt_target_position: addt_target_position + anchor_size,
                                     cum_penalty_delta: 0,
                                     to_skip: false
                                 };
@@ -498,7 +508,9 @@ impl WaveFront {
 
                             let (quotient, remainder) = div_rem(length_to_two_base_before_match_block, pattern_size as i32);
                             let match_count_of_assumed_anchor = match_count + remainder + 1 - pattern_size as i32;
-                            if match_count_of_assumed_anchor >= pattern_size as i32 {
+                            if let Some((addt_pattern_index, addt_target_position)) = first_anchor_in_right_matches(
+                                anchor_checker, quotient, match_count_of_assumed_anchor, fr, pattern_size,
+                            ) {
                                 // Traversed Anchor Exists
                                 let pd_to_this_tv_matches = 
                                     scaled_maximum_penalty_per_length
@@ -514,8 +526,8 @@ impl WaveFront {
                                 });
                                 pd_to_previous_tv_matches = pd_to_this_tv_matches;
                                 let traversed_anchor = TraversedAnchor {
-                                    addt_pattern_index: (quotient + 1) as u32 + pattern_count_of_anchor,
-                                    addt_target_position: (fr - match_count_of_assumed_anchor) as u32 + anchor_size,
+                                    addt_pattern_index: addt_pattern_index + pattern_count_of_anchor,
+                                    addt_target_position: addt_target_position + anchor_size,
                                     cum_penalty_delta: 0,
                                     to_skip: false
                                 };
@@ -559,7 +571,9 @@ impl WaveFront {
 
                             let (quotient, remainder) = div_rem(length_to_two_base_before_match_block, pattern_size as i32);
                             let match_count_of_assumed_anchor = match_count + remainder + 1 - pattern_size as i32;
-                            if match_count_of_assumed_anchor >= pattern_size as i32 {
+                            if let Some((addt_pattern_index, addt_target_position)) = first_anchor_in_right_matches(
+                                anchor_checker, quotient, match_count_of_assumed_anchor, fr, pattern_size,
+                            ) {
                                 // Traversed Anchor Exists
                                 let pd_to_this_tv_matches = 
                                     scaled_maximum_penalty_per_length
@@ -575,8 +589,8 @@ impl WaveFront {
                                 });
                                 pd_to_previous_tv_matches = pd_to_this_tv_matches;
                                 let traversed_anchor = TraversedAnchor {
-                                    addt_pattern_index: (quotient + 1) as u32 + pattern_count_of_anchor,
-                                    addt_target_position: (fr - match_count_of_assumed_anchor) as u32 + anchor_size,
+                                    addt_pattern_index: addt_pattern_index + pattern_count_of_anchor,
+                                    addt_target_position: addt_target_position + anchor_size,
                                     cum_penalty_delta: 0,
                                     to_skip: false
                                 };
@@ -760,3 +774,65 @@ impl WaveFront {
         }
     }
 }
+
+// Check if the consecutive matches on the left have an anchor
+//  - `query_end`: the (reversed) query offset at the end of the matches
+//  - Without `anchor_checker`, any pattern fully in the matches is an anchor.
+#[inline(always)]
+fn left_matches_have_anchor(
+    anchor_checker: Option<&AnchorChecker>,
+    query_end: i32,
+    match_count: i32,
+    k: i32,
+    pattern_size: u32,
+) -> bool {
+    let pattern_size = pattern_size as i32;
+    let Some(anchor_checker) = anchor_checker else {
+        let remainder = query_end % pattern_size;
+        let match_count_of_next_pattern = match_count - remainder;
+        return match_count_of_next_pattern >= pattern_size
+    };
+    let query_start = query_end - match_count;
+    let mut query_offset = (query_start + pattern_size - 1) / pattern_size * pattern_size;
+    while query_offset + pattern_size <= query_end {
+        let max_walk_back = (query_end - query_offset) / pattern_size - 1;
+        if anchor_checker.is_anchor_on_left(query_offset as u32, (query_offset + k) as u32, max_walk_back as u32) {
+            return true
+        }
+        query_offset += pattern_size;
+    }
+    false
+}
+
+// The first anchor in the consecutive matches on the right
+//  - Return (additive pattern index, additive target position) from the end of the anchor.
+//  - Without `anchor_checker`, the first pattern fully in the matches is an anchor.
+#[inline(always)]
+pub fn first_anchor_in_right_matches(
+    anchor_checker: Option<&AnchorChecker>,
+    quotient: i32,
+    match_count_of_assumed_anchor: i32,
+    fr: i32,
+    pattern_size: u32,
+) -> Option<(u32, u32)> {
+    let pattern_size = pattern_size as i32;
+    let mut addt_pattern_index = quotient + 1;
+    let mut addt_target_position = fr - match_count_of_assumed_anchor;
+    let Some(anchor_checker) = anchor_checker else {
+        if match_count_of_assumed_anchor >= pattern_size {
+            return Some((addt_pattern_index as u32, addt_target_position as u32))
+        } else {
+            return None
+        }
+    };
+    let mut walk_back = 0;
+    while addt_target_position + pattern_size <= fr {
+        if anchor_checker.is_anchor_on_right(addt_pattern_index as u32, addt_target_position as u32, walk_back) {
+            return Some((addt_pattern_index as u32, addt_target_position as u32))
+        }
+        addt_pattern_index += 1;
+        addt_target_position += pattern_size;
+        walk_back += 1;
+    }
+    None
+}
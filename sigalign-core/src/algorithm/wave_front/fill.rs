@@ -13,7 +13,7 @@ impl WaveFront {
         penalties: &Penalty,
         spare_penalty: u32,
     ) {
-        self.align_to_end_point::<ForwardMatchCounter>(tgt_seq, qry_seq, penalties, spare_penalty)
+        self.align_to_end_point::<ForwardMatchCounter>(tgt_seq, qry_seq, penalties, spare_penalty, false)
     }
     #[inline]
     pub fn align_left_to_end_point(
@@ -23,7 +23,28 @@ impl WaveFront {
         penalties: &Penalty,
         spare_penalty: u32,
     ) {
-        self.align_to_end_point::<ReverseMatchCounter>(tgt_seq, qry_seq, penalties, spare_penalty)
+        self.align_to_end_point::<ReverseMatchCounter>(tgt_seq, qry_seq, penalties, spare_penalty, false)
+    }
+    // The end point is where both the target and the query are consumed
+    #[inline]
+    pub fn align_right_to_both_ends(
+        &mut self,
+        tgt_seq: &[u8],
+        qry_seq: &[u8],
+        penalties: &Penalty,
+        spare_penalty: u32,
+    ) {
+        self.align_to_end_point::<ForwardMatchCounter>(tgt_seq, qry_seq, penalties, spare_penalty, true)
+    }
+    #[inline]
+    pub fn align_left_to_both_ends(
+        &mut self,
+        tgt_seq: &[u8],
+        qry_seq: &[u8],
+        penalties: &Penalty,
+        spare_penalty: u32,
+    ) {
+        self.align_to_end_point::<ReverseMatchCounter>(tgt_seq, qry_seq, penalties, spare_penalty, true)
     }
     #[inline]
     fn align_to_end_point<C: MatchCounter>(
@@ -32,16 +53,22 @@ impl WaveFront {
         qry_seq: &[u8],
         penalties: &Penalty,
         spare_penalty: u32,
+        to_both_ends: bool,
     ) {
         let tgt_len = tgt_seq.len();
         let qry_len = qry_seq.len();
 
         // (1) Initialize the first wave front score
-        let first_match_count = C::count_consecutive_match(qry_seq, tgt_seq, 0, 0);
+        let first_match_count = C::count_consecutive_match_with_penalties(qry_seq, tgt_seq, 0, 0, penalties);
         self.wave_front_scores[0].add_first_components(first_match_count);
 
         // (2) Check if the end point is already reached
-        if first_match_count as usize == tgt_len || first_match_count as usize == qry_len {
+        let is_end_point = if to_both_ends {
+            first_match_count as usize == tgt_len && first_match_count as usize == qry_len
+        } else {
+            first_match_count as usize == tgt_len || first_match_count as usize == qry_len
+        };
+        if is_end_point {
             let end_point = WaveEndPoint { penalty: 0, k: Some(0) };
             self.end_point = end_point;
         } else {
@@ -51,6 +78,7 @@ impl WaveFront {
                 qry_seq,
                 spare_penalty,
                 penalties,
+                to_both_ends,
             );
             self.end_point = end_point;
         }
@@ -62,6 +90,7 @@ impl WaveFront {
         qry_seq: &[u8],
         mut spare_penalty: u32,
         penalties: &Penalty,
+        to_both_ends: bool,
     ) -> WaveEndPoint {
         // This step is needed to occasionally over-estimate the spare penalty
         //   - WFS length is more accurate than spare penalty function
@@ -69,9 +98,14 @@ impl WaveFront {
             spare_penalty = (self.wave_front_scores.len() - 1) as u32;
         }
         for penalty in 1..=spare_penalty {
-            self.update_components_of_next_wave_front_score(penalty, penalties);
+            self.update_components_of_next_wave_front_score::<C>(tgt_seq, qry_seq, penalty, penalties, to_both_ends);
 
-            let optional_last_k = self.wave_front_scores[penalty as usize].extend_m_components_to_the_end::<C>(tgt_seq, qry_seq);
+            let wave_front_score = &mut self.wave_front_scores[penalty as usize];
+            let optional_last_k = if to_both_ends {
+                wave_front_score.extend_m_components_to_both_ends::<C>(tgt_seq, qry_seq, penalties)
+            } else {
+                wave_front_score.extend_m_components_to_the_end::<C>(tgt_seq, qry_seq, penalties)
+            };
 
             if let Some(last_k) = optional_last_k {
                 return WaveEndPoint { penalty: penalty as usize, k: Some(last_k) };
@@ -81,10 +115,13 @@ impl WaveFront {
         WaveEndPoint { penalty: spare_penalty as usize, k: None }
     }
     #[inline]
-    fn update_components_of_next_wave_front_score(
+    fn update_components_of_next_wave_front_score<C: MatchCounter>(
         &mut self,
+        tgt_seq: &[u8],
+        qry_seq: &[u8],
         penalty: u32,
         penalties: &Penalty,
+        to_both_ends: bool,
     ) {
         let mismatch_penalty = &penalties.x;
         let gap_open_penalty = &penalties.o;
@@ -135,6 +172,7 @@ impl WaveFront {
                                 fr: pre_m_component.fr + 1,
                                 insertion_count: pre_m_component.insertion_count,
                                 bt: BackTraceMarker::FromM,
+                                subst_penalty: 0,
                             };
                         }
                     }
@@ -149,6 +187,7 @@ impl WaveFront {
                                 fr: pre_m_component.fr,
                                 insertion_count: pre_m_component.insertion_count + 1,
                                 bt: BackTraceMarker::FromM,
+                                subst_penalty: 0,
                             };
                         }
                     }
@@ -173,6 +212,7 @@ impl WaveFront {
                                     fr: pre_d_component.fr + 1,
                                     insertion_count: pre_d_component.insertion_count,
                                     bt: BackTraceMarker::FromD,
+                                    subst_penalty: 0,
                                 };
                             }
                         };
@@ -188,6 +228,7 @@ impl WaveFront {
                                     fr: pre_i_component.fr,
                                     insertion_count: pre_i_component.insertion_count + 1,
                                     bt: BackTraceMarker::FromI,
+                                    subst_penalty: 0,
                                 };
                             };
                         }
@@ -197,7 +238,48 @@ impl WaveFront {
         }
         // (3) From score: s-x
         // Substitution
-        if let Some(pre_score) = penalty.checked_sub(*mismatch_penalty) {
+        if let Some(substitution_matrix) = &penalties.substitution_matrix {
+            // With the substitution matrix: from score s-p,
+            // if the penalty of the bases next to the previous M is p
+            for &substitution_penalty in substitution_matrix.get_distinct_penalties() {
+                let Some(pre_score) = penalty.checked_sub(substitution_penalty as u32) else {
+                    break
+                };
+                let pre_wave_front_score = &self.wave_front_scores[pre_score as usize];
+                for index_of_k in 0..num_components {
+                    let k = index_of_k as i32 - max_k;
+                    let new_components_of_k = unsafe { new_components_ptr.add(index_of_k) };
+                    // 1. Update M from previous M
+                    if let Some(pre_components) = pre_wave_front_score.components_of_k_checked(k) {
+                        let pre_m_component = &pre_components.m;
+                        if pre_m_component.bt == BackTraceMarker::Empty {
+                            continue
+                        }
+                        let Some((qry_base, tgt_base)) = C::get_bases(
+                            qry_seq,
+                            tgt_seq,
+                            (pre_m_component.fr - k) as usize,
+                            pre_m_component.fr as usize,
+                        ) else {
+                            continue
+                        };
+                        if substitution_matrix.get_penalty(qry_base, tgt_base) != substitution_penalty as u32 {
+                            continue
+                        }
+                        unsafe {
+                            if (*new_components_of_k).m.bt == BackTraceMarker::Empty || (*new_components_of_k).m.fr < pre_m_component.fr + 1 {
+                                (*new_components_of_k).m = Component {
+                                    fr: pre_m_component.fr + 1,
+                                    insertion_count: pre_m_component.insertion_count,
+                                    bt: BackTraceMarker::FromM,
+                                    subst_penalty: substitution_penalty,
+                                };
+                            }
+                        }
+                    }
+                }
+            }
+        } else if let Some(pre_score) = penalty.checked_sub(*mismatch_penalty) {
             let pre_wave_front_score = &self.wave_front_scores[pre_score as usize];
             for index_of_k in 0..num_components {
                 let k = index_of_k as i32 - max_k;
@@ -207,17 +289,24 @@ impl WaveFront {
 
                 if let Some(pre_components) = pre_wave_front_score.components_by_k.get(pre_component_index) {
                     let pre_m_component = &pre_components.m;
+                    if pre_m_component.bt == BackTraceMarker::Empty {
+                        continue
+                    }
                     // Update M
                     unsafe {
                         (*new_components_of_k).m = Component {
                             fr: pre_m_component.fr + 1,
                             insertion_count: pre_m_component.insertion_count,
                             bt: BackTraceMarker::FromM,
+                            subst_penalty: 0,
                         };
                     }
                 }
             }
         }
+        if to_both_ends {
+            self.wave_front_scores[penalty as usize].remove_components_out_of_sequences(tgt_seq, qry_seq);
+        }
         // TODO: Optimization
         for index_of_k in 0..num_components {
             let new_components_of_k = unsafe { new_components_ptr.add(index_of_k) };
@@ -231,6 +320,7 @@ impl WaveFront {
                         fr: (*new_components_of_k).d.fr,
                         insertion_count: (*new_components_of_k).d.insertion_count,
                         bt: BackTraceMarker::FromD,
+                        subst_penalty: 0,
                     };
                 }
                 // 3. Update M from current I
@@ -242,6 +332,7 @@ impl WaveFront {
                         fr: (*new_components_of_k).i.fr,
                         insertion_count: (*new_components_of_k).i.insertion_count,
                         bt: BackTraceMarker::FromI,
+                        subst_penalty: 0,
                     };
                 }
             }
@@ -259,6 +350,7 @@ impl WaveFrontScore {
         &mut self,
         tgt_seq: &[u8],
         qry_seq: &[u8],
+        penalties: &Penalty,
     ) -> Option<i32> {
         for (components, k) in self.components_by_k.iter_mut().zip(-self.max_k..=self.max_k) {
             let m_component = &mut components.m;
@@ -267,7 +359,7 @@ impl WaveFrontScore {
                 // Extend & update
                 let mut v = (m_component.fr - k) as usize; // query length to this component
                 let mut h = m_component.fr as usize; // target length to this component
-                let match_count = C::count_consecutive_match(qry_seq, tgt_seq, v, h);
+                let match_count = C::count_consecutive_match_with_penalties(qry_seq, tgt_seq, v, h, penalties);
                 m_component.fr += match_count;
                 // Check exit condition
                 v += match_count as usize;
@@ -279,6 +371,50 @@ impl WaveFrontScore {
         }
         None
     }
+    // Components over the end of the sequences are not in the alignment consuming both ends
+    #[inline]
+    fn remove_components_out_of_sequences(
+        &mut self,
+        tgt_seq: &[u8],
+        qry_seq: &[u8],
+    ) {
+        let (tgt_len, qry_len) = (tgt_seq.len() as i32, qry_seq.len() as i32);
+        for (components, k) in self.components_by_k.iter_mut().zip(-self.max_k..=self.max_k) {
+            for component in [&mut components.m, &mut components.d, &mut components.i] {
+                if component.bt != BackTraceMarker::Empty && (
+                    component.fr > tgt_len || component.fr - k > qry_len
+                ) {
+                    *component = Component::empty();
+                }
+            }
+        }
+    }
+    #[inline]
+    fn extend_m_components_to_both_ends<C: MatchCounter>(
+        &mut self,
+        tgt_seq: &[u8],
+        qry_seq: &[u8],
+        penalties: &Penalty,
+    ) -> Option<i32> {
+        for (components, k) in self.components_by_k.iter_mut().zip(-self.max_k..=self.max_k) {
+            let m_component = &mut components.m;
+
+            if m_component.bt != BackTraceMarker::Empty {
+                // Extend & update
+                let mut v = (m_component.fr - k) as usize; // query length to this component
+                let mut h = m_component.fr as usize; // target length to this component
+                let match_count = C::count_consecutive_match_with_penalties(qry_seq, tgt_seq, v, h, penalties);
+                m_component.fr += match_count;
+                // Check exit condition
+                v += match_count as usize;
+                h += match_count as usize;
+                if h == tgt_seq.len() && v == qry_seq.len() {
+                    return Some(k);
+                }
+            };
+        }
+        None
+    }
 }
 
 #[cfg(test)]
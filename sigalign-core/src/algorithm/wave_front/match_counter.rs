@@ -1,3 +1,5 @@
+use crate::core::regulators::{Penalty, SubstitutionMatrix};
+
 // TODO: apply SIMD
 pub trait MatchCounter {
     fn count_consecutive_match(
@@ -6,6 +8,38 @@ pub trait MatchCounter {
         qry_start_index: usize,
         tgt_start_index: usize,
     ) -> i32;
+    // Count the consecutive matches including the equivalent bytes of the matrix
+    fn count_consecutive_equivalent(
+        qry_seq: &[u8],
+        tgt_seq: &[u8],
+        qry_start_index: usize,
+        tgt_start_index: usize,
+        substitution_matrix: &SubstitutionMatrix,
+    ) -> i32;
+    #[inline(always)]
+    fn count_consecutive_match_with_penalties(
+        qry_seq: &[u8],
+        tgt_seq: &[u8],
+        qry_start_index: usize,
+        tgt_start_index: usize,
+        penalties: &Penalty,
+    ) -> i32 {
+        match penalties.get_matrix_with_equivalent_bytes() {
+            Some(substitution_matrix) => Self::count_consecutive_equivalent(
+                qry_seq, tgt_seq, qry_start_index, tgt_start_index, substitution_matrix,
+            ),
+            None => Self::count_consecutive_match(
+                qry_seq, tgt_seq, qry_start_index, tgt_start_index,
+            ),
+        }
+    }
+    // Bases (query, target) at the indices in the direction of counting
+    fn get_bases(
+        qry_seq: &[u8],
+        tgt_seq: &[u8],
+        qry_index: usize,
+        tgt_index: usize,
+    ) -> Option<(u8, u8)>;
 }
 
 pub struct ForwardMatchCounter;
@@ -27,6 +61,27 @@ impl MatchCounter for ForwardMatchCounter {
         }
         match_count
     }
+    #[inline(always)]
+    fn count_consecutive_equivalent(
+        qry_seq: &[u8],
+        tgt_seq: &[u8],
+        qry_start_index: usize,
+        tgt_start_index: usize,
+        substitution_matrix: &SubstitutionMatrix,
+    ) -> i32 {
+        qry_seq[qry_start_index..].iter().zip(tgt_seq[tgt_start_index..].iter()).take_while(|(v1, v2)| {
+            substitution_matrix.get_penalty(**v1, **v2) == 0
+        }).count() as i32
+    }
+    #[inline(always)]
+    fn get_bases(
+        qry_seq: &[u8],
+        tgt_seq: &[u8],
+        qry_index: usize,
+        tgt_index: usize,
+    ) -> Option<(u8, u8)> {
+        Some((*qry_seq.get(qry_index)?, *tgt_seq.get(tgt_index)?))
+    }
 }
 pub struct ReverseMatchCounter;
 impl MatchCounter for ReverseMatchCounter {
@@ -47,4 +102,27 @@ impl MatchCounter for ReverseMatchCounter {
         }
         match_count
     }
+    #[inline(always)]
+    fn count_consecutive_equivalent(
+        qry_seq: &[u8],
+        tgt_seq: &[u8],
+        qry_start_index: usize,
+        tgt_start_index: usize,
+        substitution_matrix: &SubstitutionMatrix,
+    ) -> i32 {
+        qry_seq[..qry_seq.len()-qry_start_index].iter().rev().zip(tgt_seq[..tgt_seq.len()-tgt_start_index].iter().rev()).take_while(|(v1, v2)| {
+            substitution_matrix.get_penalty(**v1, **v2) == 0
+        }).count() as i32
+    }
+    #[inline(always)]
+    fn get_bases(
+        qry_seq: &[u8],
+        tgt_seq: &[u8],
+        qry_index: usize,
+        tgt_index: usize,
+    ) -> Option<(u8, u8)> {
+        let qry_index = qry_seq.len().checked_sub(qry_index + 1)?;
+        let tgt_index = tgt_seq.len().checked_sub(tgt_index + 1)?;
+        Some((qry_seq[qry_index], tgt_seq[tgt_index]))
+    }
 }
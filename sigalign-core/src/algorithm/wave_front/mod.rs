@@ -5,7 +5,7 @@ mod match_counter;
 use match_counter::{MatchCounter, ForwardMatchCounter, ReverseMatchCounter};
 mod fill;
 mod backtrace;
-pub use backtrace::TraversedAnchor;
+pub use backtrace::{TraversedAnchor, first_anchor_in_right_matches};
 
 // Wave Front
 #[derive(Debug, Clone)]
@@ -128,6 +128,8 @@ pub struct Component {
     pub fr: i32,
     pub insertion_count: u16,
     pub bt: BackTraceMarker,
+    // Penalty of the substitution, when `bt` is `FromM` with the substitution matrix
+    pub subst_penalty: u8,
 }
 // FIXME: Check if Pod is needed
 unsafe impl Pod for Component {}
@@ -167,6 +169,7 @@ impl Component {
             fr: 0,
             insertion_count: 0,
             bt: BackTraceMarker::Empty,
+            subst_penalty: 0,
         }
     }
     #[inline(always)]
@@ -175,6 +178,15 @@ impl Component {
             fr: first_fr,
             insertion_count: 0,
             bt: BackTraceMarker::Start,
+            subst_penalty: 0,
+        }
+    }
+    /// Penalty of the substitution of the M component from the previous M component.
+    #[inline(always)]
+    pub fn substitution_penalty(&self, penalties: &Penalty) -> u32 {
+        match penalties.substitution_matrix {
+            Some(_) => self.subst_penalty as u32,
+            None => penalties.x,
         }
     }
 }
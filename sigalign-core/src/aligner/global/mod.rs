@@ -0,0 +1,67 @@
+use crate::results::QueryAlignment;
+use crate::reference::{
+    Reference, PatternIndex, SequenceStorage,
+};
+use crate::algorithm::{global_alignment_algorithm, GlobalAlignmentContext};
+use super::{
+    regulator::AlignmentRegulator,
+    semi_global::SemiGlobalWorkspace,
+};
+
+/// Aligner for the global alignment, consuming both the query and the target from end to end.
+///  - Only the alignment with the least penalty is returned for each target.
+#[derive(Clone)]
+pub struct GlobalAligner {
+    regulator: AlignmentRegulator,
+    workspace: SemiGlobalWorkspace,
+}
+
+impl GlobalAligner {
+    /// Create a new Aligner
+    pub fn new(regulator: AlignmentRegulator) -> Self {
+        let workspace = SemiGlobalWorkspace::init(&regulator);
+        Self {
+            regulator,
+            workspace,
+        }
+    }
+    /// Low-level alignment function
+    #[inline]
+    pub fn align<I: PatternIndex, S: SequenceStorage> (
+        &mut self,
+        // Query
+        query: &[u8],
+        // Targets
+        reference: &Reference<I, S>,
+        sequence_buffer: &mut S::Buffer,
+        sorted_target_indices: &[u32],
+    ) -> QueryAlignment {
+        // Initialization
+        self.workspace.allocate_more_space_if_needed(
+            query.len() as u32,
+            &self.regulator,
+        );
+        
+        // Perform alignment
+        let mut result = global_alignment_algorithm(
+            reference,
+            sequence_buffer,
+            query,
+            sorted_target_indices,
+            &mut GlobalAlignmentContext {
+                pattern_size: self.regulator.pattern_size,
+                penalties: &self.regulator.penalties,
+                cutoff: &self.regulator.cutoff,
+                spare_penalty_calculator: &mut self.workspace.spare_penalty_calculator,
+                wave_front: self.workspace.wave_front_buffer.as_mut(),
+                traversed_anchors_buffer: &mut self.workspace.traversed_anchors_buffer,
+                operations_buffer: &mut self.workspace.operations_buffer,
+            },
+        );
+        self.regulator.decompress_result_with_gcd(&mut result);
+        result
+    }
+    pub fn regulator(&self) -> &AlignmentRegulator {
+        &self.regulator
+    }
+}
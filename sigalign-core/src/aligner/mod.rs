@@ -27,8 +27,11 @@ mod workspace;
 //  - To define input parameters
 mod regulator;
 pub use regulator::{AlignmentRegulator, RegulatorError};
+pub use crate::core::regulators::{SubstitutionMatrix, SubstitutionMatrixError, MAX_SUBSTITUTION_PENALTY};
 
 /// Executing "local" alignment algorithm.
 pub mod local;
 // Executing "semi-global" alignment algorithm.
 pub mod semi_global;
+/// Executing "global" alignment algorithm.
+pub mod global;
@@ -1,5 +1,6 @@
 use crate::core::regulators::{
     Penalty, PREC_SCALE, Cutoff, MinPenaltyForPattern,
+    SubstitutionMatrix,
     calculate_max_pattern_size,
 };
 use crate::results::{
@@ -48,6 +49,27 @@ impl AlignmentRegulator {
         
         Ok(aligner)
     }
+    /// Generate new aligner with the penalties of substitutions defined by `SubstitutionMatrix`.
+    ///  - The minimum penalty of the matrix is used as the mismatch penalty to calculate the pattern size.
+    pub fn new_with_substitution_matrix(
+        substitution_matrix: SubstitutionMatrix,
+        gap_open_penalty: u32,
+        gap_extend_penalty: u32,
+        minimum_alignment_length: u32,
+        maximum_penalty_per_alignment_length: f32,
+    ) -> Result<Self, RegulatorError> {
+        if gap_extend_penalty == 0 {
+            return Err(RegulatorError::InvalidGapExtendPenalty);
+        } else if maximum_penalty_per_alignment_length <= 0.0 {
+            return Err(RegulatorError::InvalidMaxPenaltyPerLength);
+        }
+
+        let penalties = Penalty::new_with_substitution_matrix(substitution_matrix, gap_open_penalty, gap_extend_penalty);
+        let cutoff = Cutoff::new(minimum_alignment_length, maximum_penalty_per_alignment_length);
+        let aligner = Self::new_with_gcd_compressed_from_penalties_and_cutoff(penalties, cutoff);
+
+        Ok(aligner)
+    }
     fn new_with_gcd_compressed_from_penalties_and_cutoff(mut penalties: Penalty, mut cutoff: Cutoff) -> Self {
         let gcd = penalties.gcd_of_penalties();
         penalties.divide_by_gcd(gcd);
@@ -74,9 +96,18 @@ impl AlignmentRegulator {
         }
     }
     /// Get mismatch penalty
+    ///  - The minimum penalty of the substitutions, if the substitution matrix is used.
     pub fn get_mismatch_penalty(&self) -> u32 {
         self.penalties.x * self.gcd_for_compression
     }
+    /// Get substitution matrix
+    pub fn get_substitution_matrix(&self) -> Option<SubstitutionMatrix> {
+        self.penalties.substitution_matrix.as_ref().map(|substitution_matrix| {
+            let mut substitution_matrix = substitution_matrix.clone();
+            substitution_matrix.multiply_gcd(self.gcd_for_compression);
+            substitution_matrix
+        })
+    }
     /// Get gap-open penalty
     pub fn get_gap_open_penalty(&self) -> u32 {
         self.penalties.o * self.gcd_for_compression
@@ -129,15 +160,33 @@ impl Penalty {
             x: mismatch,
             o: gap_open,
             e: gap_extend,
+            substitution_matrix: None,
+        }
+    }
+    fn new_with_substitution_matrix(substitution_matrix: SubstitutionMatrix, gap_open: u32, gap_extend: u32) -> Self {
+        Self {
+            x: substitution_matrix.get_min_penalty(),
+            o: gap_open,
+            e: gap_extend,
+            substitution_matrix: Some(substitution_matrix),
         }
     }
     fn gcd_of_penalties(&self) -> u32 {
-        gcd(gcd(self.x, self.o), self.e)
+        let gcd_of_substitutions = match &self.substitution_matrix {
+            Some(substitution_matrix) => substitution_matrix.get_distinct_penalties().iter().fold(
+                self.x, |acc, &penalty| gcd(acc, penalty as u32),
+            ),
+            None => self.x,
+        };
+        gcd(gcd(gcd_of_substitutions, self.o), self.e)
     }
     fn divide_by_gcd(&mut self, gcd: u32) {
         self.x /= gcd;
         self.o /= gcd;
         self.e /= gcd;
+        if let Some(substitution_matrix) = &mut self.substitution_matrix {
+            substitution_matrix.divide_by_gcd(gcd);
+        }
     }
 }
 
@@ -174,6 +223,21 @@ mod tests {
         assert_eq!(gcd, 1);
         penalties.divide_by_gcd(gcd);
         assert_eq!(penalties, Penalty::new(4, 5, 3));
+
+        let substitution_matrix = SubstitutionMatrix::dna_transition_transversion(2, 6).unwrap();
+        let mut penalties = Penalty::new_with_substitution_matrix(substitution_matrix, 4, 2);
+        let gcd = penalties.gcd_of_penalties();
+        assert_eq!(gcd, 2);
+        penalties.divide_by_gcd(gcd);
+        assert_eq!(penalties.x, 1);
+        let substitution_matrix = penalties.substitution_matrix.unwrap();
+        assert_eq!(substitution_matrix.get_penalty(b'A', b'G'), 1);
+        assert_eq!(substitution_matrix.get_penalty(b'A', b'C'), 3);
+        assert_eq!(substitution_matrix, SubstitutionMatrix::dna_transition_transversion(1, 3).unwrap());
+
+        let substitution_matrix = SubstitutionMatrix::dna_transition_transversion(2, 3).unwrap();
+        let penalties = Penalty::new_with_substitution_matrix(substitution_matrix, 4, 2);
+        assert_eq!(penalties.gcd_of_penalties(), 1);
     }
 
     #[allow(dead_code)]
@@ -1,7 +1,7 @@
 use super::regulator::AlignmentRegulator;
 
 mod workspace;
-use workspace::SemiGlobalWorkspace;
+pub(super) use workspace::SemiGlobalWorkspace;
 
 mod semi_global_unlimited;
 pub use semi_global_unlimited::SemiGlobalAligner;
@@ -9,6 +9,10 @@ pub trait BufferedPatternLocator {
     type Buffer: SequenceBuffer;
 
     fn locate(&self, pattern: &[u8], sorted_target_indices: &[u32]) -> Vec<PatternLocation>;
+    /// Number of occurrences of the pattern in all targets.
+    ///  - Used to skip the patterns not in the reference, so it can be overcounted
+    ///    (e.g., the occurrences across the boundaries of targets).
+    fn get_occurrence_count(&self, pattern: &[u8]) -> u64;
     fn fill_buffer(&self, target_index: u32, buffer: &mut Self::Buffer);
 }
 
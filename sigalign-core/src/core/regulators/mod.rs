@@ -1,14 +1,26 @@
 //! Alignment regulators
 pub mod pattern_size;
 pub use pattern_size::calculate_max_pattern_size;
+pub mod substitution_matrix;
+pub use substitution_matrix::{SubstitutionMatrix, SubstitutionMatrixError, MAX_SUBSTITUTION_PENALTY};
 
 pub const PREC_SCALE: u32 = 100_000; // Ensuring accuracy to the fourth decimal place.
 
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
 pub struct Penalty {
+    // Minimum penalty of the substitutions, if the substitution matrix is used.
     pub x: u32,
     pub o: u32,
     pub e: u32,
+    pub substitution_matrix: Option<SubstitutionMatrix>,
+}
+
+impl Penalty {
+    /// The substitution matrix, if it has the equivalent bytes (matched, but not the same).
+    #[inline(always)]
+    pub fn get_matrix_with_equivalent_bytes(&self) -> Option<&SubstitutionMatrix> {
+        self.substitution_matrix.as_ref().filter(|matrix| matrix.has_equivalent_bytes())
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
@@ -24,6 +36,8 @@ pub struct MinPenaltyForPattern {
 }
 
 impl MinPenaltyForPattern {
+    // With the substitution matrix, `x` is the minimum penalty of the substitutions,
+    // so the penalties are still the lower bounds.
     pub fn new(penalties: &Penalty) -> Self {
         let odd: u32;
         let even: u32;
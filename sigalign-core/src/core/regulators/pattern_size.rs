@@ -3,6 +3,9 @@ use num::integer::{div_ceil, div_floor};
 use super::{Penalty, Cutoff, MinPenaltyForPattern, PREC_SCALE};
 
 // For pattern size calculation
+//  - With the substitution matrix, `penalty.x` is the minimum penalty of the substitutions.
+//    Every alignment has the penalty not less than the one with the uniform `x`,
+//    so the pattern size is still guaranteed not to miss any alignment.
 pub fn calculate_max_pattern_size(
     penalty: &Penalty,
     cutoff: &Cutoff,
@@ -273,7 +276,7 @@ fn calculate_max_pattern_size_without_panic() {
             for &pe in pe.iter() {
                 for &minl in minl.iter() {
                     for &maxp in maxp.iter() {
-                        let penalties = Penalty { x: px, o: po, e: pe };
+                        let penalties = Penalty { x: px, o: po, e: pe, substitution_matrix: None };
                         let min_penalty_for_pattern = MinPenaltyForPattern::new(&penalties);
                         let cutoff = Cutoff { minimum_length: minl, maximum_scaled_penalty_per_length: (maxp * PREC_SCALE as f32) as u32 };
                         let _ = calculate_max_pattern_size(
@@ -0,0 +1,379 @@
+use thiserror::Error;
+
+/// Maximum penalty of a substitution in `SubstitutionMatrix`.
+pub const MAX_SUBSTITUTION_PENALTY: u32 = u8::MAX as u32;
+
+const NUM_BYTES: usize = 256;
+
+/// Penalties of substitutions for each pair of (query, target) bytes.
+///
+/// - The penalty of the same bytes is always zero (match).
+/// - The penalty of different bytes is at most `MAX_SUBSTITUTION_PENALTY`.
+///   - Zero penalty makes the bytes equivalent: they are matched to each other (e.g., IUPAC ambiguity codes).
+/// - Bytes are compared as they are (case-sensitive).
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct SubstitutionMatrix {
+    // Indexed by `query byte * 256 + target byte`
+    penalties: Vec<u8>,
+    // Number of pairs of different bytes by the penalty
+    count_by_penalty: Vec<u32>,
+    // Positive penalties sorted in ascending order
+    distinct_penalties: Vec<u8>,
+    // Bases matched to the byte (zero penalty), searched with the byte in the patterns
+    seed_bases: Vec<Vec<u8>>,
+    // Number of bytes in the alphabet of the similarity scores
+    alphabet_size: Option<u32>,
+}
+
+/// Error to define the `SubstitutionMatrix`.
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum SubstitutionMatrixError {
+    #[error("Mismatch penalty only allow positive integer.")]
+    ZeroPenalty,
+    #[error("Substitution penalty is over the maximum {max}: {0}", max = MAX_SUBSTITUTION_PENALTY)]
+    PenaltyOverMaximum(u32),
+    #[error("Penalty of the same bytes cannot be set.")]
+    SameBytes,
+    #[error("Invalid scores: {0}")]
+    InvalidScores(String),
+}
+
+impl SubstitutionMatrix {
+    /// All substitutions have the same penalty.
+    pub fn new(mismatch_penalty: u32) -> Result<Self, SubstitutionMatrixError> {
+        let mismatch_penalty = Self::checked_penalty(mismatch_penalty)?;
+        let mut penalties = vec![mismatch_penalty; NUM_BYTES * NUM_BYTES];
+        (0..NUM_BYTES).for_each(|byte| penalties[byte * NUM_BYTES + byte] = 0);
+        let mut count_by_penalty = vec![0; NUM_BYTES];
+        count_by_penalty[mismatch_penalty as usize] = (NUM_BYTES * (NUM_BYTES - 1)) as u32;
+        Ok(Self {
+            penalties,
+            count_by_penalty,
+            distinct_penalties: vec![mismatch_penalty],
+            seed_bases: vec![Vec::new(); NUM_BYTES],
+            alphabet_size: None,
+        })
+    }
+    /// Transitions (`A`<->`G`, `C`<->`T`) and transversions have different penalties.
+    ///  - For uppercase nucleotides. The other substitutions have the transversion penalty.
+    pub fn dna_transition_transversion(
+        transition_penalty: u32,
+        transversion_penalty: u32,
+    ) -> Result<Self, SubstitutionMatrixError> {
+        let mut matrix = Self::new(transversion_penalty)?;
+        for (base1, base2) in [(b'A', b'G'), (b'C', b'T')] {
+            matrix.set_symmetric_penalty(base1, base2, transition_penalty)?;
+        }
+        Ok(matrix)
+    }
+    /// IUPAC nucleotide codes (uppercase) are matched to the codes sharing any base.
+    ///  - e.g., `R` (`A` or `G`) to `A`, `G`, `S` (`C` or `G`), and `N`.
+    ///  - The shared codes have the `ambiguity_penalty`. If zero, they are regarded as matches,
+    ///    and the patterns are also searched with the shared codes (in both the query and the reference).
+    ///  - The other substitutions have the `mismatch_penalty`.
+    pub fn iupac(
+        mismatch_penalty: u32,
+        ambiguity_penalty: u32,
+    ) -> Result<Self, SubstitutionMatrixError> {
+        let mut matrix = Self::new(mismatch_penalty)?;
+        for &(code1, bases1) in IUPAC_NUCLEOTIDE_CODES {
+            for &(code2, bases2) in IUPAC_NUCLEOTIDE_CODES {
+                if code1 != code2 && bases1.iter().any(|base| bases2.contains(base)) {
+                    matrix.set_penalty(code1, code2, ambiguity_penalty)?;
+                }
+            }
+        }
+        Ok(matrix)
+    }
+    /// BLOSUM62 matrix converted by `from_similarity_scores`.
+    pub fn blosum62() -> Self {
+        Self::from_similarity_scores(AMINO_ACID_ALPHABET, &BLOSUM62_SCORES).unwrap()
+    }
+    /// PAM250 matrix converted by `from_similarity_scores`.
+    pub fn pam250() -> Self {
+        Self::from_similarity_scores(AMINO_ACID_ALPHABET, &PAM250_SCORES).unwrap()
+    }
+    /// Convert the similarity scores (e.g., BLOSUM, PAM) into the penalties.
+    ///  - `scores` is the row-major square matrix of the `alphabet`.
+    ///  - The penalty of `a` and `b` is `max(score(a, a), score(b, b)) - score(a, b)` (at least one),
+    ///    so the matches always have the lowest penalty (zero).
+    ///  - The substitutions with bytes not in `alphabet` have the maximum penalty of the converted.
+    pub fn from_similarity_scores(alphabet: &[u8], scores: &[i32]) -> Result<Self, SubstitutionMatrixError> {
+        let size = alphabet.len();
+        if scores.len() != size * size {
+            return Err(SubstitutionMatrixError::InvalidScores(format!(
+                "{} scores for the alphabet of {} bytes", scores.len(), size,
+            )))
+        }
+        let score_of = |index1: usize, index2: usize| scores[index1 * size + index2];
+        let mut converted = Vec::with_capacity(size * size);
+        for index1 in 0..size {
+            for index2 in 0..size {
+                if alphabet[index1] == alphabet[index2] {
+                    continue
+                }
+                let best_score = score_of(index1, index1).max(score_of(index2, index2));
+                let penalty = (best_score - score_of(index1, index2)).max(1) as u32;
+                converted.push((alphabet[index1], alphabet[index2], penalty));
+            }
+        }
+        let max_penalty = converted.iter().map(|(_, _, penalty)| *penalty).max().unwrap_or(1);
+        let mut matrix = Self::new(max_penalty)?;
+        for (query_base, target_base, penalty) in converted {
+            matrix.set_penalty(query_base, target_base, penalty)?;
+        }
+        matrix.alphabet_size = Some(size as u32);
+        Ok(matrix)
+    }
+    /// Set the penalty of substituting `query_base` with `target_base`.
+    ///  - Zero penalty makes `query_base` to be matched to `target_base`.
+    pub fn set_penalty(
+        &mut self,
+        query_base: u8,
+        target_base: u8,
+        penalty: u32,
+    ) -> Result<(), SubstitutionMatrixError> {
+        if query_base == target_base {
+            return Err(SubstitutionMatrixError::SameBytes)
+        }
+        if penalty > MAX_SUBSTITUTION_PENALTY {
+            return Err(SubstitutionMatrixError::PenaltyOverMaximum(penalty))
+        }
+        let penalty = penalty as u8;
+        let index = query_base as usize * NUM_BYTES + target_base as usize;
+        self.count_by_penalty[self.penalties[index] as usize] -= 1;
+        self.count_by_penalty[penalty as usize] += 1;
+        self.penalties[index] = penalty;
+        self.distinct_penalties = (1..NUM_BYTES).filter(|&penalty| {
+            self.count_by_penalty[penalty] != 0
+        }).map(|penalty| penalty as u8).collect();
+        let seed_bases = &mut self.seed_bases[query_base as usize];
+        if penalty != 0 {
+            seed_bases.retain(|&base| base != target_base);
+        } else if !seed_bases.contains(&target_base) {
+            seed_bases.push(target_base);
+        }
+        Ok(())
+    }
+    /// Set the penalty of both directions.
+    pub fn set_symmetric_penalty(
+        &mut self,
+        base1: u8,
+        base2: u8,
+        penalty: u32,
+    ) -> Result<(), SubstitutionMatrixError> {
+        self.set_penalty(base1, base2, penalty)?;
+        self.set_penalty(base2, base1, penalty)
+    }
+    /// Get the penalty of substituting `query_base` with `target_base`.
+    #[inline(always)]
+    pub fn get_penalty(&self, query_base: u8, target_base: u8) -> u32 {
+        self.penalties[query_base as usize * NUM_BYTES + target_base as usize] as u32
+    }
+    /// True if any different bytes are matched (have zero penalty).
+    #[inline(always)]
+    pub fn has_equivalent_bytes(&self) -> bool {
+        self.count_by_penalty[0] != 0
+    }
+    /// Get the minimum positive penalty of substitutions.
+    pub fn get_min_penalty(&self) -> u32 {
+        self.distinct_penalties[0] as u32
+    }
+    /// Get the maximum penalty of substitutions.
+    pub fn get_max_penalty(&self) -> u32 {
+        *self.distinct_penalties.last().unwrap() as u32
+    }
+    /// Get the number of bytes in the alphabet, if the matrix is converted from the similarity scores.
+    pub fn get_alphabet_size(&self) -> Option<u32> {
+        self.alphabet_size
+    }
+    #[inline(always)]
+    pub(crate) fn get_distinct_penalties(&self) -> &[u8] {
+        &self.distinct_penalties
+    }
+    // Bases to search instead of `base` in the patterns
+    #[inline(always)]
+    pub(crate) fn get_seed_bases(&self, base: u8) -> &[u8] {
+        &self.seed_bases[base as usize]
+    }
+    pub(crate) fn divide_by_gcd(&mut self, gcd: u32) {
+        self.scale_penalties(|penalty| penalty / gcd);
+    }
+    pub(crate) fn multiply_gcd(&mut self, gcd: u32) {
+        self.scale_penalties(|penalty| penalty * gcd);
+    }
+
+    fn scale_penalties<F: Fn(u32) -> u32>(&mut self, scale: F) {
+        let mut count_by_penalty = vec![0; NUM_BYTES];
+        self.penalties.iter_mut().for_each(|penalty| {
+            *penalty = scale(*penalty as u32) as u8;
+            count_by_penalty[*penalty as usize] += 1;
+        });
+        // Exclude the same bytes
+        count_by_penalty[0] -= NUM_BYTES as u32;
+        self.count_by_penalty = count_by_penalty;
+        self.distinct_penalties.iter_mut().for_each(|penalty| {
+            *penalty = scale(*penalty as u32) as u8;
+        });
+    }
+    fn checked_penalty(penalty: u32) -> Result<u8, SubstitutionMatrixError> {
+        if penalty == 0 {
+            Err(SubstitutionMatrixError::ZeroPenalty)
+        } else if penalty > MAX_SUBSTITUTION_PENALTY {
+            Err(SubstitutionMatrixError::PenaltyOverMaximum(penalty))
+        } else {
+            Ok(penalty as u8)
+        }
+    }
+}
+
+// IUPAC nucleotide codes and the bases of each code
+const IUPAC_NUCLEOTIDE_CODES: &[(u8, &[u8])] = &[
+    (b'A', b"A"), (b'C', b"C"), (b'G', b"G"), (b'T', b"T"),
+    (b'R', b"AG"), (b'Y', b"CT"), (b'S', b"CG"), (b'W', b"AT"), (b'K', b"GT"), (b'M', b"AC"),
+    (b'B', b"CGT"), (b'D', b"AGT"), (b'H', b"ACT"), (b'V', b"ACG"),
+    (b'N', b"ACGT"),
+];
+
+// Amino acids (with ambiguous and non-standard codes) and the stop codon, in the order of the scores below
+const AMINO_ACID_ALPHABET: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWYZX*";
+
+// From SeqAn (include/seqan/score/score_matrix_data.h)
+#[rustfmt::skip]
+const BLOSUM62_SCORES: [i32; 27 * 27] = [
+     4, -2,  0, -2, -1, -2,  0, -2, -1, -1, -1, -1, -1, -2,  0, -1, -1, -1,  1,  0,  0,  0, -3, -2, -1,  0, -4,
+    -2,  4, -3,  4,  1, -3, -1,  0, -3, -4,  0, -4, -3,  3, -1, -2,  0, -1,  0, -1, -1, -3, -4, -3,  1, -1, -4,
+     0, -3,  9, -3, -4, -2, -3, -3, -1, -1, -3, -1, -1, -3, -2, -3, -3, -3, -1, -1, -2, -1, -2, -2, -3, -2, -4,
+    -2,  4, -3,  6,  2, -3, -1, -1, -3, -4, -1, -4, -3,  1, -1, -1,  0, -2,  0, -1, -1, -3, -4, -3,  1, -1, -4,
+    -1,  1, -4,  2,  5, -3, -2,  0, -3, -3,  1, -3, -2,  0, -1, -1,  2,  0,  0, -1, -1, -2, -3, -2,  4, -1, -4,
+    -2, -3, -2, -3, -3,  6, -3, -1,  0,  0, -3,  0,  0, -3, -1, -4, -3, -3, -2, -2, -1, -1,  1,  3, -3, -1, -4,
+     0, -1, -3, -1, -2, -3,  6, -2, -4, -4, -2, -4, -3,  0, -1, -2, -2, -2,  0, -2, -1, -3, -2, -3, -2, -1, -4,
+    -2,  0, -3, -1,  0, -1, -2,  8, -3, -3, -1, -3, -2,  1, -1, -2,  0,  0, -1, -2, -1, -3, -2,  2,  0, -1, -4,
+    -1, -3, -1, -3, -3,  0, -4, -3,  4,  3, -3,  2,  1, -3, -1, -3, -3, -3, -2, -1, -1,  3, -3, -1, -3, -1, -4,
+    -1, -4, -1, -4, -3,  0, -4, -3,  3,  3, -3,  3,  2, -3, -1, -3, -3, -3, -2, -1, -1,  2, -3, -1, -3, -1, -4,
+    -1,  0, -3, -1,  1, -3, -2, -1, -3, -3,  5, -2, -1,  0, -1, -1,  1,  2,  0, -1, -1, -2, -3, -2,  1, -1, -4,
+    -1, -4, -1, -4, -3,  0, -4, -3,  2,  3, -2,  4,  2, -3, -1, -3, -2, -2, -2, -1, -1,  1, -2, -1, -3, -1, -4,
+    -1, -3, -1, -3, -2,  0, -3, -2,  1,  2, -1,  2,  5, -2, -1, -2,  0, -1, -1, -1, -1,  1, -1, -1, -1, -1, -4,
+    -2,  3, -3,  1,  0, -3,  0,  1, -3, -3,  0, -3, -2,  6, -1, -2,  0,  0,  1,  0, -1, -3, -4, -2,  0, -1, -4,
+     0, -1, -2, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -2, -1, -1,  0,  0, -1, -1, -2, -1, -1, -1, -4,
+    -1, -2, -3, -1, -1, -4, -2, -2, -3, -3, -1, -3, -2, -2, -2,  7, -1, -2, -1, -1, -2, -2, -4, -3, -1, -2, -4,
+    -1,  0, -3,  0,  2, -3, -2,  0, -3, -3,  1, -2,  0,  0, -1, -1,  5,  1,  0, -1, -1, -2, -2, -1,  3, -1, -4,
+    -1, -1, -3, -2,  0, -3, -2,  0, -3, -3,  2, -2, -1,  0, -1, -2,  1,  5, -1, -1, -1, -3, -3, -2,  0, -1, -4,
+     1,  0, -1,  0,  0, -2,  0, -1, -2, -2,  0, -2, -1,  1,  0, -1,  0, -1,  4,  1,  0, -2, -3, -2,  0,  0, -4,
+     0, -1, -1, -1, -1, -2, -2, -2, -1, -1, -1, -1, -1,  0,  0, -1, -1, -1,  1,  5,  0,  0, -2, -2, -1,  0, -4,
+     0, -1, -2, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -2, -1, -1,  0,  0, -1, -1, -2, -1, -1, -1, -4,
+     0, -3, -1, -3, -2, -1, -3, -3,  3,  2, -2,  1,  1, -3, -1, -2, -2, -3, -2,  0, -1,  4, -3, -1, -2, -1, -4,
+    -3, -4, -2, -4, -3,  1, -2, -2, -3, -3, -3, -2, -1, -4, -2, -4, -2, -3, -3, -2, -2, -3, 11,  2, -3, -2, -4,
+    -2, -3, -2, -3, -2,  3, -3,  2, -1, -1, -2, -1, -1, -2, -1, -3, -1, -2, -2, -2, -1, -1,  2,  7, -2, -1, -4,
+    -1,  1, -3,  1,  4, -3, -2,  0, -3, -3,  1, -3, -1,  0, -1, -1,  3,  0,  0, -1, -1, -2, -3, -2,  4, -1, -4,
+     0, -1, -2, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -2, -1, -1,  0,  0, -1, -1, -2, -1, -1, -1, -4,
+    -4, -4, -4, -4, -4, -4, -4, -4, -4, -4, -4, -4, -4, -4, -4, -4, -4, -4, -4, -4, -4, -4, -4, -4, -4, -4,  1,
+];
+
+#[rustfmt::skip]
+const PAM250_SCORES: [i32; 27 * 27] = [
+     2,  0, -2,  0,  0, -3,  1, -1, -1, -2, -1, -2, -1,  0,  0,  1,  0, -2,  1,  1,  0,  0, -6, -3,  0,  0, -8,
+     0,  3, -4,  3,  3, -4,  0,  1, -2, -3,  1, -3, -2,  2, -1, -1,  1, -1,  0,  0, -1, -2, -5, -3,  2, -1, -8,
+    -2, -4, 12, -5, -5, -4, -3, -3, -2, -4, -5, -6, -5, -4, -3, -3, -5, -4,  0, -2, -3, -2, -8,  0, -5, -3, -8,
+     0,  3, -5,  4,  3, -6,  1,  1, -2, -3,  0, -4, -3,  2, -1, -1,  2, -1,  0,  0, -1, -2, -7, -4,  3, -1, -8,
+     0,  3, -5,  3,  4, -5,  0,  1, -2, -3,  0, -3, -2,  1, -1, -1,  2, -1,  0,  0, -1, -2, -7, -4,  3, -1, -8,
+    -3, -4, -4, -6, -5,  9, -5, -2,  1,  2, -5,  2,  0, -3, -2, -5, -5, -4, -3, -3, -2, -1,  0,  7, -5, -2, -8,
+     1,  0, -3,  1,  0, -5,  5, -2, -3, -4, -2, -4, -3,  0, -1,  0, -1, -3,  1,  0, -1, -1, -7, -5,  0, -1, -8,
+    -1,  1, -3,  1,  1, -2, -2,  6, -2, -2,  0, -2, -2,  2, -1,  0,  3,  2, -1, -1, -1, -2, -3,  0,  2, -1, -8,
+    -1, -2, -2, -2, -2,  1, -3, -2,  5,  4, -2,  2,  2, -2, -1, -2, -2, -2, -1,  0, -1,  4, -5, -1, -2, -1, -8,
+    -2, -3, -4, -3, -3,  2, -4, -2,  4,  4, -3,  4,  3, -3, -1, -3, -2, -3, -2, -1, -1,  3, -4, -1, -3, -1, -8,
+    -1,  1, -5,  0,  0, -5, -2,  0, -2, -3,  5, -3,  0,  1, -1, -1,  1,  3,  0,  0, -1, -2, -3, -4,  0, -1, -8,
+    -2, -3, -6, -4, -3,  2, -4, -2,  2,  4, -3,  6,  4, -3, -1, -3, -2, -3, -3, -2, -1,  2, -2, -1, -3, -1, -8,
+    -1, -2, -5, -3, -2,  0, -3, -2,  2,  3,  0,  4,  6, -2, -1, -2, -1,  0, -2, -1, -1,  2, -4, -2, -2, -1, -8,
+     0,  2, -4,  2,  1, -3,  0,  2, -2, -3,  1, -3, -2,  2,  0,  0,  1,  0,  1,  0,  0, -2, -4, -2,  1,  0, -8,
+     0, -1, -3, -1, -1, -2, -1, -1, -1, -1, -1, -1, -1,  0, -1, -1, -1, -1,  0,  0, -1, -1, -4, -2, -1, -1, -8,
+     1, -1, -3, -1, -1, -5,  0,  0, -2, -3, -1, -3, -2,  0, -1,  6,  0,  0,  1,  0, -1, -1, -6, -5,  0, -1, -8,
+     0,  1, -5,  2,  2, -5, -1,  3, -2, -2,  1, -2, -1,  1, -1,  0,  4,  1, -1, -1, -1, -2, -5, -4,  3, -1, -8,
+    -2, -1, -4, -1, -1, -4, -3,  2, -2, -3,  3, -3,  0,  0, -1,  0,  1,  6,  0, -1, -1, -2,  2, -4,  0, -1, -8,
+     1,  0,  0,  0,  0, -3,  1, -1, -1, -2,  0, -3, -2,  1,  0,  1, -1,  0,  2,  1,  0, -1, -2, -3,  0,  0, -8,
+     1,  0, -2,  0,  0, -3,  0, -1,  0, -1,  0, -2, -1,  0,  0,  0, -1, -1,  1,  3,  0,  0, -5, -3, -1,  0, -8,
+     0, -1, -3, -1, -1, -2, -1, -1, -1, -1, -1, -1, -1,  0, -1, -1, -1, -1,  0,  0, -1, -1, -4, -2, -1, -1, -8,
+     0, -2, -2, -2, -2, -1, -1, -2,  4,  3, -2,  2,  2, -2, -1, -1, -2, -2, -1,  0, -1,  4, -6, -2, -2, -1, -8,
+    -6, -5, -8, -7, -7,  0, -7, -3, -5, -4, -3, -2, -4, -4, -4, -6, -5,  2, -2, -5, -4, -6, 17,  0, -6, -4, -8,
+    -3, -3,  0, -4, -4,  7, -5,  0, -1, -1, -4, -1, -2, -2, -2, -5, -4, -4, -3, -3, -2, -2,  0, 10, -4, -2, -8,
+     0,  2, -5,  3,  3, -5,  0,  2, -2, -3,  0, -3, -2,  1, -1,  0,  3,  0,  0, -1, -1, -2, -6, -4,  3, -1, -8,
+     0, -1, -3, -1, -1, -2, -1, -1, -1, -1, -1, -1, -1,  0, -1, -1, -1, -1,  0,  0, -1, -1, -4, -2, -1, -1, -8,
+    -8, -8, -8, -8, -8, -8, -8, -8, -8, -8, -8, -8, -8, -8, -8, -8, -8, -8, -8, -8, -8, -8, -8, -8, -8, -8,  1,
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_penalties_of_substitution_matrix() {
+        let mut matrix = SubstitutionMatrix::dna_transition_transversion(2, 5).unwrap();
+        assert_eq!(matrix.get_penalty(b'A', b'A'), 0);
+        assert_eq!(matrix.get_penalty(b'G', b'A'), 2);
+        assert_eq!(matrix.get_penalty(b'T', b'C'), 2);
+        assert_eq!(matrix.get_penalty(b'A', b'T'), 5);
+        assert_eq!(matrix.get_distinct_penalties(), &[2, 5]);
+        matrix.set_penalty(b'A', b'N', 1).unwrap();
+        assert_eq!((matrix.get_min_penalty(), matrix.get_max_penalty()), (1, 5));
+        assert_eq!(matrix.get_penalty(b'N', b'A'), 5);
+        assert_eq!(matrix.set_penalty(b'A', b'A', 1), Err(SubstitutionMatrixError::SameBytes));
+        assert!(!matrix.has_equivalent_bytes());
+        matrix.set_penalty(b'A', b'C', 0).unwrap();
+        assert!(matrix.has_equivalent_bytes());
+        assert_eq!(matrix.get_distinct_penalties(), &[1, 2, 5]);
+        assert_eq!(SubstitutionMatrix::new(0), Err(SubstitutionMatrixError::ZeroPenalty));
+        assert_eq!(matrix.set_penalty(b'A', b'C', 256), Err(SubstitutionMatrixError::PenaltyOverMaximum(256)));
+
+        let mut scaled = SubstitutionMatrix::dna_transition_transversion(4, 10).unwrap();
+        scaled.divide_by_gcd(2);
+        assert_eq!(scaled, SubstitutionMatrix::dna_transition_transversion(2, 5).unwrap());
+        scaled.multiply_gcd(2);
+        assert_eq!(scaled, SubstitutionMatrix::dna_transition_transversion(4, 10).unwrap());
+    }
+    #[test]
+    fn test_iupac_codes_are_matched() {
+        let matrix = SubstitutionMatrix::iupac(4, 0).unwrap();
+        assert!(matrix.has_equivalent_bytes());
+        assert_eq!(matrix.get_penalty(b'A', b'R'), 0);
+        assert_eq!(matrix.get_penalty(b'R', b'S'), 0);
+        assert_eq!(matrix.get_penalty(b'N', b'T'), 0);
+        assert_eq!(matrix.get_penalty(b'R', b'Y'), 4);
+        assert_eq!(matrix.get_penalty(b'A', b'C'), 4);
+        assert_eq!(matrix.get_distinct_penalties(), &[4]);
+        assert_eq!(matrix.get_seed_bases(b'R'), b"AGSWKMBDHVN");
+        assert_eq!(matrix.get_seed_bases(b'A'), b"RWMDHVN");
+
+        let mut matrix = SubstitutionMatrix::iupac(4, 2).unwrap();
+        assert!(!matrix.has_equivalent_bytes());
+        assert_eq!(matrix.get_penalty(b'A', b'R'), 2);
+        assert_eq!(matrix.get_seed_bases(b'R'), b"");
+        matrix.divide_by_gcd(2);
+        assert_eq!(matrix, SubstitutionMatrix::iupac(2, 1).unwrap());
+
+        let mut matrix = SubstitutionMatrix::iupac(4, 0).unwrap();
+        matrix.set_penalty(b'R', b'A', 1).unwrap();
+        assert_eq!(matrix.get_seed_bases(b'R'), b"GSWKMBDHVN");
+    }
+    #[test]
+    fn test_penalties_converted_from_similarity_scores() {
+        for matrix in [SubstitutionMatrix::blosum62(), SubstitutionMatrix::pam250()] {
+            for &base1 in AMINO_ACID_ALPHABET {
+                assert_eq!(matrix.get_penalty(base1, base1), 0);
+                for &base2 in AMINO_ACID_ALPHABET {
+                    assert_eq!(matrix.get_penalty(base1, base2), matrix.get_penalty(base2, base1));
+                }
+            }
+        }
+        let blosum62 = SubstitutionMatrix::blosum62();
+        // A-A: 4, W-W: 11, A-W: -3
+        assert_eq!(blosum62.get_penalty(b'A', b'W'), 14);
+        // I-I: 4, V-V: 4, I-V: 3
+        assert_eq!(blosum62.get_penalty(b'I', b'V'), 1);
+        // Out of the alphabet
+        assert_eq!(blosum62.get_penalty(b'A', b'a'), blosum62.get_max_penalty());
+        assert_eq!(blosum62.get_alphabet_size(), Some(AMINO_ACID_ALPHABET.len() as u32));
+        assert_eq!(SubstitutionMatrix::new(4).unwrap().get_alphabet_size(), None);
+
+        assert!(SubstitutionMatrix::from_similarity_scores(b"AC", &[1, 0, 0]).is_err());
+    }
+}
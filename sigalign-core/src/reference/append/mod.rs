@@ -0,0 +1,79 @@
+use super::{
+    Reference,
+    AppendedTargets,
+    PatternIndex,
+    PatternPosition,
+    SequenceStorage,
+    SequenceBuffer,
+};
+
+impl<I, S> Reference<I, S> where
+    I: PatternIndex,
+    S: SequenceStorage,
+{
+    /// Append targets to the `Reference` without rebuilding the main pattern index.
+    ///  - `merge_targets` adds the targets of `targets` to the end of the `SequenceStorage`.
+    ///  - The appended targets are indexed by a separate pattern index built with `pattern_index_option`.
+    ///    This index is rebuilt with all appended targets at each call,
+    ///    so use `merge_appended_targets` when it grows large.
+    ///  - The indices of existing targets are not changed.
+    ///  - If building the index fails, the `Reference` is not changed.
+    pub fn append_targets<T, F>(
+        &mut self,
+        targets: T,
+        merge_targets: F,
+        pattern_index_option: I::Option,
+    ) -> Result<(), I::BuildError> where
+        T: SequenceStorage,
+        F: FnOnce(&mut S, T),
+    {
+        let num_new_targets = targets.num_targets();
+        if num_new_targets == 0 {
+            return Ok(())
+        }
+        let first_target_index = self.num_targets_in_main_index();
+        let num_targets = self.sequence_storage.num_targets();
+
+        let mut concatenated_sequence = Vec::new();
+        let mut boundaries = Vec::with_capacity((num_targets - first_target_index + num_new_targets) as usize + 1);
+        boundaries.push(0);
+        let mut buffer = self.sequence_storage.get_buffer();
+        for target_index in first_target_index..num_targets {
+            self.sequence_storage.fill_buffer(target_index, &mut buffer);
+            concatenated_sequence.extend_from_slice(buffer.buffered_sequence());
+            boundaries.push(concatenated_sequence.len() as u64);
+        }
+        let mut buffer = targets.get_buffer();
+        for target_index in 0..num_new_targets {
+            targets.fill_buffer(target_index, &mut buffer);
+            concatenated_sequence.extend_from_slice(buffer.buffered_sequence());
+            boundaries.push(concatenated_sequence.len() as u64);
+        }
+        let pattern_index = I::new(concatenated_sequence, pattern_index_option)?;
+        merge_targets(&mut self.sequence_storage, targets);
+        self.appended_targets = Some(AppendedTargets {
+            target_boundaries: boundaries.into_iter().map(I::Position::from_u64).collect(),
+            pattern_index,
+        });
+        Ok(())
+    }
+    /// Rebuild the main pattern index with all targets, including the appended ones.
+    pub fn merge_appended_targets(
+        &mut self,
+        pattern_index_option: I::Option,
+    ) -> Result<(), I::BuildError> {
+        let (concatenated_sequence, target_boundaries) = self.sequence_storage.get_concatenated_sequence_with_boundaries_of_targets();
+        self.pattern_index = I::new(concatenated_sequence, pattern_index_option)?;
+        self.target_boundaries = target_boundaries.into_iter().map(I::Position::from_u64).collect();
+        self.appended_targets = None;
+        Ok(())
+    }
+    /// Get the number of targets appended after the main pattern index was built.
+    pub fn num_appended_targets(&self) -> u32 {
+        self.sequence_storage.num_targets() - self.num_targets_in_main_index()
+    }
+    #[inline]
+    pub(super) fn num_targets_in_main_index(&self) -> u32 {
+        self.target_boundaries.len() as u32 - 1
+    }
+}
@@ -13,6 +13,7 @@ impl<I, S> Clone for Reference<I, S> where
             target_boundaries: self.target_boundaries.clone(),
             pattern_index: self.pattern_index.clone(),
             sequence_storage: self.sequence_storage.clone(),
+            appended_targets: self.appended_targets.clone(),
         }
     }
 }
@@ -3,7 +3,9 @@ use super::{
     PatternIndex,
     SequenceStorage,
 };
-use std::io::{Write, Read, Error};
+use crate::reference::AppendedTargets;
+use std::io::{Write, Read, Error, ErrorKind, Cursor};
+use std::sync::Arc;
 
 /// Save and load the structure
 pub trait Serialize {
@@ -15,6 +17,11 @@ pub trait Serialize {
 }
 
 use capwriter::{Save, Load};
+// A flag (one byte) is written after the sequence storage: 1 if the appended targets follow, 0 if not.
+// The layout before the appended targets (without this flag) is loaded by `load_legacy_from` and `load_legacy_in_place`.
+const WITHOUT_APPENDED_TARGETS: u8 = 0;
+const WITH_APPENDED_TARGETS: u8 = 1;
+
 impl<I, S> Serialize for Reference<I, S> where
     I: PatternIndex + Serialize,
     S: SequenceStorage + Serialize,
@@ -25,12 +32,41 @@ impl<I, S> Serialize for Reference<I, S> where
         self.target_boundaries.save_to(&mut writer)?;
         self.pattern_index.save_to(&mut writer)?;
         self.sequence_storage.save_to(&mut writer)?;
+        match &self.appended_targets {
+            Some(appended_targets) => {
+                writer.write_all(&[WITH_APPENDED_TARGETS])?;
+                appended_targets.target_boundaries.save_to(&mut writer)?;
+                appended_targets.pattern_index.save_to(&mut writer)?;
+            },
+            None => writer.write_all(&[WITHOUT_APPENDED_TARGETS])?,
+        }
         Ok(())
     }
     fn load_from<R>(mut reader: R) -> Result<Self, Error> where
         R: Read,
         Self: Sized
     {
+        let mut reference = Self::load_legacy_from(&mut reader)?;
+        let mut flag = [0; 1];
+        reader.read_exact(&mut flag)?;
+        reference.appended_targets = match flag[0] {
+            WITHOUT_APPENDED_TARGETS => None,
+            WITH_APPENDED_TARGETS => Some(AppendedTargets {
+                target_boundaries: Vec::load_from(&mut reader)?,
+                pattern_index: I::load_from(&mut reader)?,
+            }),
+            _ => return Err(ErrorKind::InvalidData.into()),
+        };
+        Ok(reference)
+    }
+}
+
+impl<I, S> Reference<I, S> where
+    I: PatternIndex + Serialize,
+    S: SequenceStorage + Serialize,
+{
+    /// Load the reference saved in the layout before the appended targets (without the flag).
+    pub fn load_legacy_from<R: Read>(mut reader: R) -> Result<Self, Error> {
         let target_boundaries = Vec::load_from(&mut reader)?;
         let pattern_index = I::load_from(&mut reader)?;
         let sequence_storage = S::load_from(&mut reader)?;
@@ -38,10 +74,80 @@ impl<I, S> Serialize for Reference<I, S> where
             target_boundaries,
             pattern_index,
             sequence_storage,
+            appended_targets: None,
+        })
+    }
+}
+
+/// Bytes shared by the structures loaded from them (e.g., memory-mapped file).
+pub type SharedBytes = Arc<dyn AsRef<[u8]> + Send + Sync>;
+
+/// Load the structure from the `SharedBytes` in place.
+///  - The layout of bytes is the same as the one written by `Serialize::save_to`.
+///  - The parts that can be used as they are may refer to the `SharedBytes` without copy.
+pub trait LoadInPlace: Serialize {
+    /// `offset` is moved to the end of the structure.
+    fn load_in_place(bytes: &SharedBytes, offset: &mut usize) -> Result<Self, Error> where
+        Self: Sized;
+}
+
+/// Load the structure by copying, for the `LoadInPlace` implementation of the structure that cannot refer to the bytes.
+pub fn load_by_copy<T: Serialize>(bytes: &SharedBytes, offset: &mut usize) -> Result<T, Error> {
+    let bytes: &[u8] = (**bytes).as_ref();
+    let mut cursor = Cursor::new(&bytes[(*offset).min(bytes.len())..]);
+    let loaded = T::load_from(&mut cursor)?;
+    *offset += cursor.position() as usize;
+    Ok(loaded)
+}
+
+impl<I, S> LoadInPlace for Reference<I, S> where
+    I: PatternIndex + LoadInPlace,
+    S: SequenceStorage + LoadInPlace,
+{
+    fn load_in_place(bytes: &SharedBytes, offset: &mut usize) -> Result<Self, Error> where
+        Self: Sized
+    {
+        let mut reference = Self::load_legacy_in_place(bytes, offset)?;
+        let flag = (**bytes).as_ref().get(*offset).copied().ok_or(ErrorKind::UnexpectedEof)?;
+        *offset += 1;
+        reference.appended_targets = match flag {
+            WITHOUT_APPENDED_TARGETS => None,
+            WITH_APPENDED_TARGETS => Some(AppendedTargets {
+                target_boundaries: load_target_boundaries(bytes, offset)?,
+                pattern_index: I::load_in_place(bytes, offset)?,
+            }),
+            _ => return Err(ErrorKind::InvalidData.into()),
+        };
+        Ok(reference)
+    }
+}
+
+impl<I, S> Reference<I, S> where
+    I: PatternIndex + LoadInPlace,
+    S: SequenceStorage + LoadInPlace,
+{
+    /// Same as `load_legacy_from`, but from the `SharedBytes` in place.
+    pub fn load_legacy_in_place(bytes: &SharedBytes, offset: &mut usize) -> Result<Self, Error> {
+        let target_boundaries = load_target_boundaries(bytes, offset)?;
+        let pattern_index = I::load_in_place(bytes, offset)?;
+        let sequence_storage = S::load_in_place(bytes, offset)?;
+        Ok(Self {
+            target_boundaries,
+            pattern_index,
+            sequence_storage,
+            appended_targets: None,
         })
     }
 }
 
+fn load_target_boundaries<P: bytemuck::Pod>(bytes: &SharedBytes, offset: &mut usize) -> Result<Vec<P>, Error> {
+    let bytes: &[u8] = (**bytes).as_ref();
+    let mut cursor = Cursor::new(&bytes[(*offset).min(bytes.len())..]);
+    let target_boundaries = Vec::load_from(&mut cursor)?;
+    *offset += cursor.position() as usize;
+    Ok(target_boundaries)
+}
+
 /// Provides an estimate of the size of the object when saved.
 pub trait EstimateSize {
     fn serialized_size(&self) -> usize;
@@ -52,8 +158,13 @@ impl<I, S> EstimateSize for Reference<I, S> where
     S: SequenceStorage + EstimateSize,
 {
     fn serialized_size(&self) -> usize {
-        (self.target_boundaries.len() * std::mem::size_of::<u32>())
+        (self.target_boundaries.len() * std::mem::size_of::<I::Position>())
         + self.sequence_storage.serialized_size()
         + self.pattern_index.serialized_size()
+        + 1 // Flag of the appended targets
+        + self.appended_targets.as_ref().map(|appended_targets| {
+            appended_targets.target_boundaries.to_be_saved_size()
+            + appended_targets.pattern_index.serialized_size()
+        }).unwrap_or(0)
     }
 }
@@ -13,6 +13,9 @@ mod io;
 pub use io::{
     Serialize,
     EstimateSize,
+    LoadInPlace,
+    SharedBytes,
+    load_by_copy,
 };
 mod label;
 pub use label::LabelStorage;
@@ -24,11 +24,12 @@ mod pattern_index;
 mod sequence_storage;
 // Implementations
 mod pattern_locate; // Implements the `BufferedPatternLocater` trait.
+mod append; // Appends targets with a separate pattern index.
 mod debug;
 // Extensions for additional features for `Reference`.
 pub mod extensions;
 
-pub use pattern_index::PatternIndex;
+pub use pattern_index::{PatternIndex, PatternPosition};
 pub use sequence_storage::SequenceStorage;
 pub use crate::core::{PatternLocation, SequenceBuffer};
 
@@ -38,9 +39,19 @@ pub struct Reference<I, S> where
     I: PatternIndex,
     S: SequenceStorage,
 {
-    target_boundaries: Vec<u32>,
+    target_boundaries: Vec<I::Position>,
     pattern_index: I,
     sequence_storage: S,
+    appended_targets: Option<AppendedTargets<I>>,
+}
+
+/// Targets appended after the `Reference` was built, indexed by their own pattern index.
+///  - The first appended target follows the targets in the main pattern index.
+#[derive(Debug, Clone)]
+struct AppendedTargets<I: PatternIndex> {
+    // Boundaries in the concatenated sequence of the appended targets.
+    target_boundaries: Vec<I::Position>,
+    pattern_index: I,
 }
 
 impl<I, S> Reference<I, S> where
@@ -53,11 +64,14 @@ impl<I, S> Reference<I, S> where
     ) -> Result<Self, I::BuildError> {
         let (concatenated_sequence, target_boundaries) = sequence_storage.get_concatenated_sequence_with_boundaries_of_targets();
         let pattern_index = I::new(concatenated_sequence, pattern_index_option)?;
+        // The total length is already checked by the `PatternIndex`.
+        let target_boundaries = target_boundaries.into_iter().map(I::Position::from_u64).collect();
 
         Ok(Self {
             target_boundaries,
             pattern_index,
             sequence_storage,
+            appended_targets: None,
         })
     }
     pub fn get_sequence_storage(&self) -> &S {
@@ -4,9 +4,57 @@ Provides the `PatternIndex` and its basic implementations.
 pub trait PatternIndex: Sized {
     type Option;
     type BuildError: std::error::Error;
+    /// Type of the position in the concatenated sequence.
+    type Position: PatternPosition;
 
     /// Create a new `PatternIndex` instance with the given concatenated sequence.
+    ///  - Must return error if the length of the sequence is over `Self::Position::MAX`.
     fn new(concatenated_sequence : Vec<u8>, option: Self::Option) -> Result<Self, Self::BuildError>;
     /// Get sorted positions of the given pattern in concatenated sequence.
-    fn get_sorted_positions(&self, pattern: &[u8]) -> Vec<u32>;
+    fn get_sorted_positions(&self, pattern: &[u8]) -> Vec<Self::Position>;
+    /// Get the number of occurrences of the given pattern in concatenated sequence.
+    ///  - Override if it can be counted without locating.
+    fn get_occurrence_count(&self, pattern: &[u8]) -> u64 {
+        self.get_sorted_positions(pattern).len() as u64
+    }
+}
+
+/// Position in the concatenated sequence of all targets.
+///  - `u32`: For the reference shorter than `u32::MAX` (faster and smaller).
+///  - `u64`: For the larger reference.
+///
+/// The length of each target is always limited to `u32::MAX`.
+pub trait PatternPosition:
+    Copy + Ord + std::fmt::Debug + bytemuck::Pod + Send + Sync
+{
+    const MAX: u64;
+
+    fn as_u64(self) -> u64;
+    /// The value must not be over `Self::MAX`.
+    fn from_u64(value: u64) -> Self;
+}
+
+impl PatternPosition for u32 {
+    const MAX: u64 = u32::MAX as u64;
+
+    #[inline(always)]
+    fn as_u64(self) -> u64 {
+        self as u64
+    }
+    #[inline(always)]
+    fn from_u64(value: u64) -> Self {
+        value as u32
+    }
+}
+impl PatternPosition for u64 {
+    const MAX: u64 = u64::MAX;
+
+    #[inline(always)]
+    fn as_u64(self) -> u64 {
+        self
+    }
+    #[inline(always)]
+    fn from_u64(value: u64) -> Self {
+        value
+    }
 }
@@ -2,7 +2,7 @@ use ahash::AHashMap;
 
 use crate::core::{BufferedPatternLocator, PatternLocation};
 use super::Reference;
-use super::pattern_index::PatternIndex;
+use super::pattern_index::{PatternIndex, PatternPosition as _};
 use super::sequence_storage::SequenceStorage;
 
 impl<I, S> BufferedPatternLocator for Reference<I, S> where
@@ -13,52 +13,41 @@ impl<I, S> BufferedPatternLocator for Reference<I, S> where
 
     #[inline]
     fn locate(&self, pattern: &[u8], sorted_target_indices: &[u32]) -> Vec<PatternLocation> {
-        let sorted_positions = self.pattern_index.get_sorted_positions(pattern);
         // TODO: Applying cap is valuable?
         let mut positions_by_target: AHashMap<u32, Vec<u32>> = AHashMap::new();
 
-        let search_range_count = sorted_target_indices.len();
-
-        let mut size;
-        let mut left;
-        let mut right;
-        let mut mid = 0;
-        let mut index;
-
-        for position in sorted_positions {
-            // reset
-            right = search_range_count;
-            left = mid;
-            size = right - left;
-
-            while left < right {
-                mid = left + size / 2;
-                index = sorted_target_indices[mid];
-                
-                let start = self.target_boundaries[index as usize];
-                let end = self.target_boundaries[(index + 1) as usize];
-
-                if position >= end {
-                    left = mid + 1;
-                } else if start > position {
-                    right = mid;
-                } else if position + pattern.len() as u32 <= end {
-                    let ref_pos = position - start;
-                    match positions_by_target.get_mut(&index) {
-                        Some(v) => {
-                            v.push(ref_pos);
-                        },
-                        None => {
-                            positions_by_target.insert(index, vec![ref_pos]);
-                        },
-                    }
-                    break;
-                } else {
-                    break;
-                }
-                
-                size = right - left;
-            }
+        match &self.appended_targets {
+            None => {
+                locate_in_targets(
+                    &self.pattern_index,
+                    &self.target_boundaries,
+                    0,
+                    pattern,
+                    sorted_target_indices,
+                    &mut positions_by_target,
+                );
+            },
+            Some(appended_targets) => {
+                let first_appended_target_index = self.num_targets_in_main_index();
+                let split_index = sorted_target_indices.partition_point(|&index| index < first_appended_target_index);
+                let (main_target_indices, appended_target_indices) = sorted_target_indices.split_at(split_index);
+                locate_in_targets(
+                    &self.pattern_index,
+                    &self.target_boundaries,
+                    0,
+                    pattern,
+                    main_target_indices,
+                    &mut positions_by_target,
+                );
+                locate_in_targets(
+                    &appended_targets.pattern_index,
+                    &appended_targets.target_boundaries,
+                    first_appended_target_index,
+                    pattern,
+                    appended_target_indices,
+                    &mut positions_by_target,
+                );
+            },
         }
 
         positions_by_target.into_iter().map(|(target_index, positions)| {
@@ -68,11 +57,83 @@ impl<I, S> BufferedPatternLocator for Reference<I, S> where
             }
         }).collect()
     }
+    fn get_occurrence_count(&self, pattern: &[u8]) -> u64 {
+        let count = self.pattern_index.get_occurrence_count(pattern);
+        match &self.appended_targets {
+            None => count,
+            Some(appended_targets) => count + appended_targets.pattern_index.get_occurrence_count(pattern),
+        }
+    }
     fn fill_buffer(&self, target_index: u32, buffer: &mut Self::Buffer) {
         self.sequence_storage.fill_buffer(target_index, buffer)
     }
 }
 
+/// Locate the pattern in the targets of one pattern index.
+///  - `target_boundaries` start from the target of `first_target_index`.
+///  - `sorted_target_indices` must be in the range of `target_boundaries`.
+#[inline]
+fn locate_in_targets<I: PatternIndex>(
+    pattern_index: &I,
+    target_boundaries: &[I::Position],
+    first_target_index: u32,
+    pattern: &[u8],
+    sorted_target_indices: &[u32],
+    positions_by_target: &mut AHashMap<u32, Vec<u32>>,
+) {
+    if sorted_target_indices.is_empty() {
+        return
+    }
+    let sorted_positions = pattern_index.get_sorted_positions(pattern);
+
+    let search_range_count = sorted_target_indices.len();
+
+    let mut size;
+    let mut left;
+    let mut right;
+    let mut mid = 0;
+    let mut index;
+
+    for position in sorted_positions {
+        let position = position.as_u64();
+        // reset
+        right = search_range_count;
+        left = mid;
+        size = right - left;
+
+        while left < right {
+            mid = left + size / 2;
+            index = sorted_target_indices[mid];
+            let boundary_index = (index - first_target_index) as usize;
+
+            let start = target_boundaries[boundary_index].as_u64();
+            let end = target_boundaries[boundary_index + 1].as_u64();
+
+            if position >= end {
+                left = mid + 1;
+            } else if start > position {
+                right = mid;
+            } else if position + pattern.len() as u64 <= end {
+                // Position in a target is always in the range of `u32`.
+                let ref_pos = (position - start) as u32;
+                match positions_by_target.get_mut(&index) {
+                    Some(v) => {
+                        v.push(ref_pos);
+                    },
+                    None => {
+                        positions_by_target.insert(index, vec![ref_pos]);
+                    },
+                }
+                break;
+            } else {
+                break;
+            }
+
+            size = right - left;
+        }
+    }
+}
+
 impl<I, S> Reference<I, S> where
     I: PatternIndex,
     S: SequenceStorage,
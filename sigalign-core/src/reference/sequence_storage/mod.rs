@@ -10,9 +10,10 @@ pub trait SequenceStorage {
     fn fill_buffer(&self, target_index: u32, buffer: &mut Self::Buffer);
     fn num_targets(&self) -> u32;
 
+    /// Boundaries are `u64` to be used for the reference longer than `u32::MAX`.
     fn get_concatenated_sequence_with_boundaries_of_targets(&self) -> (
         Vec<u8>,
-        Vec<u32>,
+        Vec<u64>,
     ) {
         let num_targets = self.num_targets();
         let mut boundaries = Vec::with_capacity(num_targets as usize + 1);
@@ -24,7 +25,7 @@ pub trait SequenceStorage {
         for target_index in 0..num_targets {
             self.fill_buffer(target_index, &mut buffer);
             let target_sequence = buffer.buffered_sequence();
-            accumulated_length += target_sequence.len() as u32;
+            accumulated_length += target_sequence.len() as u64;
             boundaries.push(accumulated_length);
             concatenated_sequence.extend_from_slice(target_sequence)
         }
@@ -95,6 +95,7 @@ pub struct AlignmentOperations {
 #[derive(Debug, PartialEq, Eq, Hash, Clone)]
 #[derive(Serialize, Deserialize)]
 pub enum AlignmentOperation {
+    /// The same bases, or the equivalent bases of the substitution matrix (e.g., IUPAC codes).
     #[cfg_attr(feature = "short_key", serde(rename = "M"))]
     Match,
     #[cfg_attr(feature = "short_key", serde(rename = "S"))]
@@ -110,3 +111,5 @@ mod to_json;
 // Features
 mod count_alignments;
 mod deduplicate;
+mod pairwise_text;
+pub use pairwise_text::PairwiseTextOption;
@@ -0,0 +1,182 @@
+use super::{
+    Alignment,
+    AlignmentOperation,
+};
+
+/// Option for rendering `Alignment` as pairwise text.
+///
+/// - `line_width`: The number of alignment columns in one block (default: 60).
+/// - `lowercase_mismatches`: If true, matched bases are written in uppercase,
+///   and the bases of substitutions and gaps are written in lowercase (default: false).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PairwiseTextOption {
+    pub line_width: usize,
+    pub lowercase_mismatches: bool,
+}
+
+impl Default for PairwiseTextOption {
+    fn default() -> Self {
+        Self {
+            line_width: 60,
+            lowercase_mismatches: false,
+        }
+    }
+}
+
+const QUERY_NAME: &str = "Query";
+const TARGET_NAME: &str = "Target";
+const NAME_WIDTH: usize = 6;
+
+impl Alignment {
+    /// Render as the three-line pairwise text ("query / match bar / target").
+    ///
+    /// - `query` and `target` are the whole sequences used for the alignment.
+    /// - None if the alignment is not in range of `query` and `target`,
+    ///   or its operations do not fit its positions.
+    /// - In match bar, `|` is match, `.` is substitution, and ` ` is gap.
+    /// - Each line is annotated with the 1-based positions of the first and last bases.
+    ///
+    /// ```text
+    /// Penalty: 12, Length: 9, Identity: 7/9 (77.8%), Gaps: 1/9 (11.1%)
+    ///
+    /// Query  1  ACGT-ACGT  8
+    ///           |||| |.||
+    /// Target 11 ACGTAAAGT  19
+    /// ```
+    pub fn to_pairwise_text(
+        &self,
+        query: &[u8],
+        target: &[u8],
+        option: &PairwiseTextOption,
+    ) -> Option<String> {
+        if !self.fits_in(query.len(), target.len()) {
+            return None
+        }
+        let line_width = option.line_width.max(1);
+
+        // (1) Make rows
+        let mut query_row = Vec::with_capacity(self.length as usize);
+        let mut match_bar = Vec::with_capacity(self.length as usize);
+        let mut target_row = Vec::with_capacity(self.length as usize);
+        let mut query_index = self.position.query.0 as usize;
+        let mut target_index = self.position.target.0 as usize;
+        let (mut identity, mut gaps, mut columns) = (0, 0, 0);
+        for operations in &self.operations {
+            let count = operations.count as usize;
+            columns += count;
+            match operations.operation {
+                AlignmentOperation::Match => {
+                    identity += count;
+                    for _ in 0..count {
+                        query_row.push(as_matched(query[query_index], option));
+                        match_bar.push(b'|');
+                        target_row.push(as_matched(target[target_index], option));
+                        query_index += 1;
+                        target_index += 1;
+                    }
+                },
+                AlignmentOperation::Subst => {
+                    for _ in 0..count {
+                        query_row.push(as_mismatched(query[query_index], option));
+                        match_bar.push(b'.');
+                        target_row.push(as_mismatched(target[target_index], option));
+                        query_index += 1;
+                        target_index += 1;
+                    }
+                },
+                AlignmentOperation::Insertion => {
+                    gaps += count;
+                    for _ in 0..count {
+                        query_row.push(as_mismatched(query[query_index], option));
+                        match_bar.push(b' ');
+                        target_row.push(b'-');
+                        query_index += 1;
+                    }
+                },
+                AlignmentOperation::Deletion => {
+                    gaps += count;
+                    for _ in 0..count {
+                        query_row.push(b'-');
+                        match_bar.push(b' ');
+                        target_row.push(as_mismatched(target[target_index], option));
+                        target_index += 1;
+                    }
+                },
+            }
+        }
+
+        // (2) Write blocks
+        let mut text = format!(
+            "Penalty: {}, Length: {}, Identity: {}/{} ({:.1}%), Gaps: {}/{} ({:.1}%)\n",
+            self.penalty, self.length,
+            identity, columns, percent(identity, columns),
+            gaps, columns, percent(gaps, columns),
+        );
+        let number_width = self.position.query.1.max(self.position.target.1).to_string().len();
+        let mut query_position = self.position.query.0 as usize;
+        let mut target_position = self.position.target.0 as usize;
+        for ((query_chunk, bar_chunk), target_chunk) in query_row.chunks(line_width)
+            .zip(match_bar.chunks(line_width))
+            .zip(target_row.chunks(line_width))
+        {
+            text.push('\n');
+            push_sequence_line(&mut text, QUERY_NAME, query_chunk, &mut query_position, number_width);
+            text.push_str(&format!(
+                "{:name_width$} {:number_width$} {}\n",
+                "", "", String::from_utf8_lossy(bar_chunk),
+                name_width = NAME_WIDTH, number_width = number_width,
+            ));
+            push_sequence_line(&mut text, TARGET_NAME, target_chunk, &mut target_position, number_width);
+        }
+        Some(text)
+    }
+    fn fits_in(&self, query_length: usize, target_length: usize) -> bool {
+        let (query_start, query_end) = self.position.query;
+        let (target_start, target_end) = self.position.target;
+        if query_end as usize > query_length || target_end as usize > target_length {
+            return false
+        }
+        let (query_span, target_span) = self.operations.iter().fold((0u64, 0u64), |(query_span, target_span), operations| {
+            let count = operations.count as u64;
+            match operations.operation {
+                AlignmentOperation::Match | AlignmentOperation::Subst => (query_span + count, target_span + count),
+                AlignmentOperation::Insertion => (query_span + count, target_span),
+                AlignmentOperation::Deletion => (query_span, target_span + count),
+            }
+        });
+        query_start as u64 + query_span == query_end as u64
+            && target_start as u64 + target_span == target_end as u64
+    }
+}
+
+/// `position` is 0-based position of the next base, and it is moved to the end of the chunk.
+#[inline]
+fn push_sequence_line(
+    text: &mut String,
+    name: &str,
+    chunk: &[u8],
+    position: &mut usize,
+    number_width: usize,
+) {
+    let base_count = chunk.iter().filter(|&&base| base != b'-').count();
+    let start = if base_count == 0 { *position } else { *position + 1 };
+    *position += base_count;
+    text.push_str(&format!(
+        "{:name_width$} {:<number_width$} {}  {}\n",
+        name, start, String::from_utf8_lossy(chunk), *position,
+        name_width = NAME_WIDTH, number_width = number_width,
+    ));
+}
+
+#[inline]
+fn as_matched(base: u8, option: &PairwiseTextOption) -> u8 {
+    if option.lowercase_mismatches { base.to_ascii_uppercase() } else { base }
+}
+#[inline]
+fn as_mismatched(base: u8, option: &PairwiseTextOption) -> u8 {
+    if option.lowercase_mismatches { base.to_ascii_lowercase() } else { base }
+}
+#[inline]
+fn percent(numerator: usize, denominator: usize) -> f64 {
+    if denominator == 0 { 0.0 } else { 100.0 * numerator as f64 / denominator as f64 }
+}
@@ -1,51 +1,88 @@
 use crate::utils::get_unique_characters_of_sequence;
 use super::static_lfi::{
-    Lfi32B2V64,
-    Lfi32B3V64,
-    Lfi32B4V64,
-    Lfi32B5V64,
+    StaticLfi,
     LfiOption,
+    LfiPosition,
 };
+use lt_fm_index::blocks::{Block2, Block3, Block4, Block5};
 // Re-export: The build error type is the same as the static version.
-pub use super::static_lfi::LfiBuildError;
+pub use super::static_lfi::{LfiBuildError, MAX_LOOKUP_TABLE_BYTES_SIZE};
 use sigalign_core::reference::PatternIndex;
 
 /// The LtFmIndex that can adjust the type by the number of characters.
 /// - The maximum number of characters that can be indexed is 31 (same as the `Lfi32B5V64`).
-/// - The maximum length of one sequence is u32::MAX (same as the static version).
+/// - The maximum length of concatenated sequence is `P::MAX` (`u32::MAX` by default).
+///   - `DynamicLfi64` can be used for the reference longer than `u32::MAX`.
+/// - The BWT block size is 64 or 128, as defined by `DynamicLfiOption::bwt_block_size`.
 #[derive(Clone)]
-pub enum DynamicLfi {
-    B2(Lfi32B2V64),
-    B3(Lfi32B3V64),
-    B4(Lfi32B4V64),
-    B5(Lfi32B5V64),
+pub enum DynamicLfiOf<P: LfiPosition> {
+    B2(StaticLfi<Block2<u64>, P>),
+    B3(StaticLfi<Block3<u64>, P>),
+    B4(StaticLfi<Block4<u64>, P>),
+    B5(StaticLfi<Block5<u64>, P>),
+    B2V128(StaticLfi<Block2<u128>, P>),
+    B3V128(StaticLfi<Block3<u128>, P>),
+    B4V128(StaticLfi<Block4<u128>, P>),
+    B5V128(StaticLfi<Block5<u128>, P>),
 }
+/// `DynamicLfiOf` with 32-bit position.
+pub type DynamicLfi = DynamicLfiOf<u32>;
+/// `DynamicLfiOf` with 64-bit position.
+pub type DynamicLfi64 = DynamicLfiOf<u64>;
 
 /// Option to define the structure of the LtFmIndex.
 #[derive(Debug, Clone)]
 pub struct DynamicLfiOption {
     pub suffix_array_sampling_ratio: u64,
     pub lookup_table_max_bytes_size: u64,
+    /// If set, the k-mer size of lookup table is fixed, ignoring `lookup_table_max_bytes_size`.
+    ///  - Must be at least one, and the lookup table must be within `MAX_LOOKUP_TABLE_BYTES_SIZE`.
+    pub lookup_table_kmer_size: Option<u32>,
     pub use_safe_guard: bool,
+    pub bwt_block_size: BwtBlockSize,
 }
 impl DynamicLfiOption {
     fn to_lfi_option(self) -> LfiOption {
         LfiOption {
             suffix_array_sampling_ratio: self.suffix_array_sampling_ratio,
             lookup_table_max_bytes_size: self.lookup_table_max_bytes_size,
+            lookup_table_kmer_size: self.lookup_table_kmer_size,
             use_safe_guard: self.use_safe_guard,
         }
     }
 }
 
-impl PatternIndex for DynamicLfi {
+/// Size of the block (in bits) to store the BWT.
+///  - `V128` makes the index smaller, but slower to locate.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BwtBlockSize {
+    #[default]
+    V64,
+    V128,
+}
+
+/// Structure of the built `DynamicLfiOf`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DynamicLfiInfo {
+    /// Number of bits to represent a character (2 to 5).
+    pub bits_per_character: u32,
+    /// Number of indexed characters, including the safe guard.
+    pub num_characters: u32,
+    pub bwt_block_size: BwtBlockSize,
+    pub suffix_array_sampling_ratio: u64,
+    pub lookup_table_kmer_size: u32,
+}
+
+impl<P: LfiPosition> PatternIndex for DynamicLfiOf<P> {
     type Option = DynamicLfiOption;
     type BuildError = LfiBuildError;
+    type Position = P;
 
     fn new(
         concatenated_sequence: Vec<u8>,
         option: Self::Option,
     ) -> Result<Self, Self::BuildError> {
+        let bwt_block_size = option.bwt_block_size;
         let lfi_option = option.to_lfi_option();
         let unique_sequence = get_unique_characters_of_sequence(&concatenated_sequence);
         let chr_count = {
@@ -56,28 +93,93 @@ impl PatternIndex for DynamicLfi {
             }
         };
 
-        if chr_count <= 3 {
-            let inner = Lfi32B2V64::new(concatenated_sequence, lfi_option)?;
-            Ok(Self::B2(inner))
-        } else if chr_count <= 7 {
-            let inner = Lfi32B3V64::new(concatenated_sequence, lfi_option)?;
-            Ok(Self::B3(inner))
-        } else if chr_count <= 15 {
-            let inner = Lfi32B4V64::new(concatenated_sequence, lfi_option)?;
-            Ok(Self::B4(inner))
-        } else if chr_count <= 31 {
-            let inner = Lfi32B5V64::new(concatenated_sequence, lfi_option)?;
-            Ok(Self::B5(inner))
-        } else {
-            Err(Self::BuildError::OverMaximumCharacters { max: 31, input: chr_count as u32 })
+        match bwt_block_size {
+            BwtBlockSize::V64 => {
+                if chr_count <= 3 {
+                    let inner = StaticLfi::new(concatenated_sequence, lfi_option)?;
+                    Ok(Self::B2(inner))
+                } else if chr_count <= 7 {
+                    let inner = StaticLfi::new(concatenated_sequence, lfi_option)?;
+                    Ok(Self::B3(inner))
+                } else if chr_count <= 15 {
+                    let inner = StaticLfi::new(concatenated_sequence, lfi_option)?;
+                    Ok(Self::B4(inner))
+                } else if chr_count <= 31 {
+                    let inner = StaticLfi::new(concatenated_sequence, lfi_option)?;
+                    Ok(Self::B5(inner))
+                } else {
+                    Err(Self::BuildError::OverMaximumCharacters { max: 31, input: chr_count as u32 })
+                }
+            },
+            BwtBlockSize::V128 => {
+                if chr_count <= 3 {
+                    let inner = StaticLfi::new(concatenated_sequence, lfi_option)?;
+                    Ok(Self::B2V128(inner))
+                } else if chr_count <= 7 {
+                    let inner = StaticLfi::new(concatenated_sequence, lfi_option)?;
+                    Ok(Self::B3V128(inner))
+                } else if chr_count <= 15 {
+                    let inner = StaticLfi::new(concatenated_sequence, lfi_option)?;
+                    Ok(Self::B4V128(inner))
+                } else if chr_count <= 31 {
+                    let inner = StaticLfi::new(concatenated_sequence, lfi_option)?;
+                    Ok(Self::B5V128(inner))
+                } else {
+                    Err(Self::BuildError::OverMaximumCharacters { max: 31, input: chr_count as u32 })
+                }
+            },
         }
     }
-    fn get_sorted_positions(&self, pattern: &[u8]) -> Vec<u32> {
+    fn get_sorted_positions(&self, pattern: &[u8]) -> Vec<P> {
         match self {
             Self::B2(v) => v.get_sorted_positions(pattern),
             Self::B3(v) => v.get_sorted_positions(pattern),
             Self::B4(v) => v.get_sorted_positions(pattern),
             Self::B5(v) => v.get_sorted_positions(pattern),
+            Self::B2V128(v) => v.get_sorted_positions(pattern),
+            Self::B3V128(v) => v.get_sorted_positions(pattern),
+            Self::B4V128(v) => v.get_sorted_positions(pattern),
+            Self::B5V128(v) => v.get_sorted_positions(pattern),
+        }
+    }
+    fn get_occurrence_count(&self, pattern: &[u8]) -> u64 {
+        match self {
+            Self::B2(v) => v.get_occurrence_count(pattern),
+            Self::B3(v) => v.get_occurrence_count(pattern),
+            Self::B4(v) => v.get_occurrence_count(pattern),
+            Self::B5(v) => v.get_occurrence_count(pattern),
+            Self::B2V128(v) => v.get_occurrence_count(pattern),
+            Self::B3V128(v) => v.get_occurrence_count(pattern),
+            Self::B4V128(v) => v.get_occurrence_count(pattern),
+            Self::B5V128(v) => v.get_occurrence_count(pattern),
+        }
+    }
+}
+
+macro_rules! info_of {
+    ($inner: expr, $bits: expr, $bwt_block_size: expr) => {
+        DynamicLfiInfo {
+            bits_per_character: $bits,
+            num_characters: $inner.get_num_characters(),
+            bwt_block_size: $bwt_block_size,
+            suffix_array_sampling_ratio: $inner.get_suffix_array_sampling_ratio(),
+            lookup_table_kmer_size: $inner.get_lookup_table_kmer_size(),
+        }
+    };
+}
+
+impl<P: LfiPosition> DynamicLfiOf<P> {
+    /// Get the structure chosen when building.
+    pub fn get_info(&self) -> DynamicLfiInfo {
+        match self {
+            Self::B2(v) => info_of!(v, 2, BwtBlockSize::V64),
+            Self::B3(v) => info_of!(v, 3, BwtBlockSize::V64),
+            Self::B4(v) => info_of!(v, 4, BwtBlockSize::V64),
+            Self::B5(v) => info_of!(v, 5, BwtBlockSize::V64),
+            Self::B2V128(v) => info_of!(v, 2, BwtBlockSize::V128),
+            Self::B3V128(v) => info_of!(v, 3, BwtBlockSize::V128),
+            Self::B4V128(v) => info_of!(v, 4, BwtBlockSize::V128),
+            Self::B5V128(v) => info_of!(v, 5, BwtBlockSize::V128),
         }
     }
 }
@@ -86,10 +188,13 @@ impl PatternIndex for DynamicLfi {
 use sigalign_core::reference::extensions::{
     Serialize,
     EstimateSize,
+    LoadInPlace,
+    SharedBytes,
+    load_by_copy,
 };
 //  - Serialize
 use crate::core::{EndianType, WriteBytesExt, ReadBytesExt};
-impl Serialize for DynamicLfi {
+impl<P: LfiPosition> Serialize for DynamicLfiOf<P> {
     fn save_to<W>(&self, mut writer: W) -> Result<(), std::io::Error> where
         W: std::io::Write
     {
@@ -114,6 +219,26 @@ impl Serialize for DynamicLfi {
                 v.save_to(&mut writer)?;
                 Ok(())
             },
+            Self::B2V128(v) => {
+                writer.write_u64::<EndianType>(Self::B2V128_MAGIC_NUMBER)?;
+                v.save_to(&mut writer)?;
+                Ok(())
+            },
+            Self::B3V128(v) => {
+                writer.write_u64::<EndianType>(Self::B3V128_MAGIC_NUMBER)?;
+                v.save_to(&mut writer)?;
+                Ok(())
+            },
+            Self::B4V128(v) => {
+                writer.write_u64::<EndianType>(Self::B4V128_MAGIC_NUMBER)?;
+                v.save_to(&mut writer)?;
+                Ok(())
+            },
+            Self::B5V128(v) => {
+                writer.write_u64::<EndianType>(Self::B5V128_MAGIC_NUMBER)?;
+                v.save_to(&mut writer)?;
+                Ok(())
+            },
         }
     }
     fn load_from<R>(mut reader: R) -> Result<Self, std::io::Error> where
@@ -122,41 +247,67 @@ impl Serialize for DynamicLfi {
     {
         let magic_number = reader.read_u64::<EndianType>()?;
         match magic_number {
-            Self::B2_MAGIC_NUMBER => {
-                let inner = Lfi32B2V64::load_from(&mut reader)?;
+            v if v == Self::B2_MAGIC_NUMBER => {
+                let inner = StaticLfi::load_from(&mut reader)?;
                 Ok(Self::B2(inner))
             },
-            Self::B3_MAGIC_NUMBER => {
-                let inner = Lfi32B3V64::load_from(&mut reader)?;
+            v if v == Self::B3_MAGIC_NUMBER => {
+                let inner = StaticLfi::load_from(&mut reader)?;
                 Ok(Self::B3(inner))
             },
-            Self::B4_MAGIC_NUMBER => {
-                let inner = Lfi32B4V64::load_from(&mut reader)?;
+            v if v == Self::B4_MAGIC_NUMBER => {
+                let inner = StaticLfi::load_from(&mut reader)?;
                 Ok(Self::B4(inner))
             },
-            Self::B5_MAGIC_NUMBER => {
-                let inner = Lfi32B5V64::load_from(&mut reader)?;
+            v if v == Self::B5_MAGIC_NUMBER => {
+                let inner = StaticLfi::load_from(&mut reader)?;
                 Ok(Self::B5(inner))
             },
+            v if v == Self::B2V128_MAGIC_NUMBER => {
+                let inner = StaticLfi::load_from(&mut reader)?;
+                Ok(Self::B2V128(inner))
+            },
+            v if v == Self::B3V128_MAGIC_NUMBER => {
+                let inner = StaticLfi::load_from(&mut reader)?;
+                Ok(Self::B3V128(inner))
+            },
+            v if v == Self::B4V128_MAGIC_NUMBER => {
+                let inner = StaticLfi::load_from(&mut reader)?;
+                Ok(Self::B4V128(inner))
+            },
+            v if v == Self::B5V128_MAGIC_NUMBER => {
+                let inner = StaticLfi::load_from(&mut reader)?;
+                Ok(Self::B5V128(inner))
+            },
             _ => {
                 Err((std::io::ErrorKind::InvalidData).into())
             },
         }
     }
 }
-impl DynamicLfi {
+impl<P: LfiPosition> DynamicLfiOf<P> {
     // MAGIC NUMBERS: FNV1A32 hash value of
-    // LtFmIndexPosition32Block2Vector64: 956ed7f2
-    const B2_MAGIC_NUMBER: u64 = 2507069426;
-    // LtFmIndexPosition32Block3Vector64: 626c069d
-    const B3_MAGIC_NUMBER: u64 = 1651246749;
-    // LtFmIndexPosition32Block4Vector64: 6e317038
-    const B4_MAGIC_NUMBER: u64 = 1848733752;
-    // LtFmIndexPosition32Block5Vector64: 6a2427ab
-    const B5_MAGIC_NUMBER: u64 = 1780754347;
+    // LtFmIndexPosition{32|64}Block{2|3|4|5}Vector{64|128}
+    const B2_MAGIC_NUMBER: u64 = P::MAGIC_NUMBERS[0];
+    const B3_MAGIC_NUMBER: u64 = P::MAGIC_NUMBERS[1];
+    const B4_MAGIC_NUMBER: u64 = P::MAGIC_NUMBERS[2];
+    const B5_MAGIC_NUMBER: u64 = P::MAGIC_NUMBERS[3];
+    const B2V128_MAGIC_NUMBER: u64 = P::MAGIC_NUMBERS_V128[0];
+    const B3V128_MAGIC_NUMBER: u64 = P::MAGIC_NUMBERS_V128[1];
+    const B4V128_MAGIC_NUMBER: u64 = P::MAGIC_NUMBERS_V128[2];
+    const B5V128_MAGIC_NUMBER: u64 = P::MAGIC_NUMBERS_V128[3];
+}
+//  - LoadInPlace
+// The index of `lt-fm-index` owns its data, so it is deserialized from the bytes.
+impl<P: LfiPosition> LoadInPlace for DynamicLfiOf<P> {
+    fn load_in_place(bytes: &SharedBytes, offset: &mut usize) -> Result<Self, std::io::Error> where
+        Self: Sized
+    {
+        load_by_copy(bytes, offset)
+    }
 }
 //  - EstimateSize
-impl EstimateSize for DynamicLfi {
+impl<P: LfiPosition> EstimateSize for DynamicLfiOf<P> {
     fn serialized_size(&self) -> usize {
         std::mem::size_of::<u64>()
         + match self {
@@ -164,6 +315,10 @@ impl EstimateSize for DynamicLfi {
             Self::B3(v) => v.serialized_size(),
             Self::B4(v) => v.serialized_size(),
             Self::B5(v) => v.serialized_size(),
+            Self::B2V128(v) => v.serialized_size(),
+            Self::B3V128(v) => v.serialized_size(),
+            Self::B4V128(v) => v.serialized_size(),
+            Self::B5V128(v) => v.serialized_size(),
         }
     }
 }
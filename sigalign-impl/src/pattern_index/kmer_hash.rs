@@ -0,0 +1,230 @@
+use thiserror::Error;
+
+use sigalign_core::reference::PatternIndex;
+
+/// Hash-based k-mer index for small references (e.g., amplicon panels of a few Mbp).
+///
+/// - Built for a fixed set of pattern sizes (k).
+///   - Use the pattern size of the aligner (`AlignmentRegulator::get_pattern_size`).
+/// - Locating a pattern of:
+///   - indexed size: Looks up the table of the size.
+///   - longer size: Looks up the prefix in the table of the largest size that fits,
+///     then compares the rest with the sequence.
+///   - shorter size than all: Scans the whole sequence with rolling hash.
+/// - Keeps the concatenated sequence, so the memory usage is about
+///   `(1 + 12 × number of pattern sizes) × sequence length` bytes.
+/// - The maximum length of concatenated sequence is `u32::MAX`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KmerHashIndex {
+    concatenated_sequence: Vec<u8>,
+    // Sorted by the pattern size
+    tables: Vec<KmerTable>,
+}
+
+/// Table of one pattern size.
+/// Entries are sorted by (hash, position), so positions of the same hash are sorted.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct KmerTable {
+    kmer_size: u32,
+    hashes: Vec<u64>,
+    positions: Vec<u32>,
+}
+
+/// Option for `KmerHashIndex`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KmerHashIndexOption {
+    /// Pattern sizes to index. Duplicates are ignored.
+    pub pattern_sizes: Vec<u32>,
+}
+
+impl KmerHashIndexOption {
+    pub fn new(pattern_sizes: Vec<u32>) -> Self {
+        Self { pattern_sizes }
+    }
+}
+
+/// Error type for `KmerHashIndex` build.
+#[derive(Debug, Error)]
+pub enum KmerHashIndexBuildError {
+    /// Triggered when sequence length exceeds the maximum allowable capacity.
+    #[error("Sequence length is over the maximum capacity {0}")]
+    SequenceLengthOver(u64),
+    /// Triggered when no valid (non-zero) pattern size is given.
+    #[error("At least one non-zero pattern size is required")]
+    NoPatternSize,
+}
+
+impl PatternIndex for KmerHashIndex {
+    type Option = KmerHashIndexOption;
+    type BuildError = KmerHashIndexBuildError;
+    type Position = u32;
+
+    fn new(concatenated_sequence: Vec<u8>, option: Self::Option) -> Result<Self, Self::BuildError> {
+        if concatenated_sequence.len() as u64 >= u32::MAX as u64 {
+            return Err(Self::BuildError::SequenceLengthOver(u32::MAX as u64));
+        }
+        let mut pattern_sizes = option.pattern_sizes;
+        pattern_sizes.retain(|&size| size != 0);
+        pattern_sizes.sort_unstable();
+        pattern_sizes.dedup();
+        if pattern_sizes.is_empty() {
+            return Err(Self::BuildError::NoPatternSize);
+        }
+
+        let tables = pattern_sizes.into_iter().map(|kmer_size| {
+            KmerTable::new(&concatenated_sequence, kmer_size)
+        }).collect();
+        Ok(Self {
+            concatenated_sequence,
+            tables,
+        })
+    }
+    fn get_sorted_positions(&self, pattern: &[u8]) -> Vec<u32> {
+        let pattern_size = pattern.len() as u32;
+        if pattern_size == 0 {
+            return Vec::new()
+        }
+        let table_index = self.tables.partition_point(|table| table.kmer_size <= pattern_size);
+        if table_index == 0 {
+            // Shorter than all indexed sizes
+            return self.scan_sequence(pattern)
+        }
+        let table = &self.tables[table_index - 1];
+        let kmer_size = table.kmer_size as usize;
+        table.candidates(&pattern[..kmer_size]).filter(|&position| {
+            let start = position as usize;
+            self.concatenated_sequence.get(start..start + pattern.len()) == Some(pattern)
+        }).collect()
+    }
+}
+
+impl KmerHashIndex {
+    /// Get the indexed pattern sizes in ascending order.
+    pub fn get_pattern_sizes(&self) -> Vec<u32> {
+        self.tables.iter().map(|table| table.kmer_size).collect()
+    }
+    fn scan_sequence(&self, pattern: &[u8]) -> Vec<u32> {
+        let pattern_hash = hash_of(pattern);
+        let mut positions = Vec::new();
+        for_each_rolling_hash(&self.concatenated_sequence, pattern.len(), |position, hash| {
+            if hash == pattern_hash && &self.concatenated_sequence[position..position + pattern.len()] == pattern {
+                positions.push(position as u32);
+            }
+        });
+        positions
+    }
+}
+
+impl KmerTable {
+    fn new(sequence: &[u8], kmer_size: u32) -> Self {
+        let mut entries: Vec<(u64, u32)> = Vec::with_capacity(sequence.len());
+        for_each_rolling_hash(sequence, kmer_size as usize, |position, hash| {
+            entries.push((hash, position as u32));
+        });
+        entries.sort_unstable();
+        let (hashes, positions) = entries.into_iter().unzip();
+        Self {
+            kmer_size,
+            hashes,
+            positions,
+        }
+    }
+    /// Positions having the same hash (not verified).
+    #[inline]
+    fn candidates(&self, kmer: &[u8]) -> impl Iterator<Item = u32> + '_ {
+        let hash = hash_of(kmer);
+        let start = self.hashes.partition_point(|&v| v < hash);
+        let end = start + self.hashes[start..].partition_point(|&v| v == hash);
+        self.positions[start..end].iter().copied()
+    }
+}
+
+// Polynomial rolling hash (mod 2^64)
+const HASH_BASE: u64 = 0x100000001b3;
+
+#[inline]
+fn hash_of(kmer: &[u8]) -> u64 {
+    kmer.iter().fold(0, |hash, &byte| hash.wrapping_mul(HASH_BASE).wrapping_add(byte as u64))
+}
+/// Call `f(position, hash)` for every k-mer in the sequence.
+#[inline]
+fn for_each_rolling_hash<F: FnMut(usize, u64)>(sequence: &[u8], kmer_size: usize, mut f: F) {
+    if kmer_size == 0 || sequence.len() < kmer_size {
+        return
+    }
+    // HASH_BASE^(k-1) to remove the first byte
+    let highest_power = (1..kmer_size).fold(1_u64, |power, _| power.wrapping_mul(HASH_BASE));
+    let mut hash = hash_of(&sequence[..kmer_size]);
+    f(0, hash);
+    for position in 1..=sequence.len() - kmer_size {
+        hash = hash
+            .wrapping_sub((sequence[position - 1] as u64).wrapping_mul(highest_power))
+            .wrapping_mul(HASH_BASE)
+            .wrapping_add(sequence[position + kmer_size - 1] as u64);
+        f(position, hash);
+    }
+}
+
+// Impl Extensions
+use capwriter::{Save, Load};
+use sigalign_core::reference::extensions::{
+    Serialize,
+    EstimateSize,
+    LoadInPlace,
+    SharedBytes,
+    load_by_copy,
+};
+use crate::core::{EndianType, WriteBytesExt, ReadBytesExt};
+//  - Serialize
+impl Serialize for KmerHashIndex {
+    fn save_to<W>(&self, mut writer: W) -> Result<(), std::io::Error> where
+        W: std::io::Write
+    {
+        self.concatenated_sequence.save_to(&mut writer)?;
+        writer.write_u64::<EndianType>(self.tables.len() as u64)?;
+        for table in &self.tables {
+            writer.write_u32::<EndianType>(table.kmer_size)?;
+            table.hashes.save_to(&mut writer)?;
+            table.positions.save_to(&mut writer)?;
+        }
+        Ok(())
+    }
+    fn load_from<R>(mut reader: R) -> Result<Self, std::io::Error> where
+        R: std::io::Read,
+        Self: Sized
+    {
+        let concatenated_sequence = Vec::load_from(&mut reader)?;
+        let table_count = reader.read_u64::<EndianType>()? as usize;
+        let mut tables = Vec::with_capacity(table_count);
+        for _ in 0..table_count {
+            let kmer_size = reader.read_u32::<EndianType>()?;
+            let hashes = Vec::load_from(&mut reader)?;
+            let positions = Vec::load_from(&mut reader)?;
+            tables.push(KmerTable { kmer_size, hashes, positions });
+        }
+        Ok(Self {
+            concatenated_sequence,
+            tables,
+        })
+    }
+}
+//  - LoadInPlace
+impl LoadInPlace for KmerHashIndex {
+    fn load_in_place(bytes: &SharedBytes, offset: &mut usize) -> Result<Self, std::io::Error> where
+        Self: Sized
+    {
+        load_by_copy(bytes, offset)
+    }
+}
+//  - EstimateSize
+impl EstimateSize for KmerHashIndex {
+    fn serialized_size(&self) -> usize {
+        self.concatenated_sequence.to_be_saved_size()
+        + std::mem::size_of::<u64>()
+        + self.tables.iter().map(|table| {
+            std::mem::size_of::<u32>()
+            + table.hashes.to_be_saved_size()
+            + table.positions.to_be_saved_size()
+        }).sum::<usize>()
+    }
+}
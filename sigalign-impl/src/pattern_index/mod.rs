@@ -5,6 +5,14 @@ Implementations for the trait `sigalign_core::reference::PatternIndex`.
 - Using `LtFmIndex` (<https://github.com/baku4/lt-fm-index>):
   - `static_lfi`: Has a maximum number of characters that can be indexed.
   - `dynamic_lfi`: Can adjust the internal type by the number of characters (slightly slower than static version).
+  - Both have 32-bit (default) and 64-bit position versions (e.g., `Lfi64B2V64`, `DynamicLfi64`).
+    The 64-bit version is needed for the reference longer than `u32::MAX`, but uses more memory.
+- Using hash table of k-mers:
+  - `kmer_hash`: Built for the fixed pattern sizes. Fast for small references, but uses more memory.
+- Using uncompressed suffix array:
+  - `suffix_array`: Faster to locate than `LtFmIndex`, but uses more memory.
 */
 pub mod static_lfi;
 pub mod dynamic_lfi;
+pub mod kmer_hash;
+pub mod suffix_array;
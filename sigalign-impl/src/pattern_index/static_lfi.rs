@@ -1,9 +1,9 @@
 use thiserror::Error;
 
 use crate::utils::get_unique_characters_of_sequence;
-use sigalign_core::reference::PatternIndex;
+use sigalign_core::reference::{PatternIndex, PatternPosition};
 use lt_fm_index::{
-    LtFmIndex, Block, blocks,
+    LtFmIndex, Block, blocks, Position,
 };
 
 /// `StaticLfi` that can index 3 (2^2 - 1) characters, with a BWT block size of 64.
@@ -14,20 +14,89 @@ pub type Lfi32B3V64 = StaticLfi<blocks::Block3<u64>>;
 pub type Lfi32B4V64 = StaticLfi<blocks::Block4<u64>>;
 /// `StaticLfi` that can index 31 (2^5 - 1) characters, with a BWT block size of 64.
 pub type Lfi32B5V64 = StaticLfi<blocks::Block5<u64>>;
+/// `StaticLfi` with 64-bit position that can index 3 (2^2 - 1) characters, with a BWT block size of 64.
+pub type Lfi64B2V64 = StaticLfi<blocks::Block2<u64>, u64>;
+/// `StaticLfi` with 64-bit position that can index 7 (2^3 - 1) characters, with a BWT block size of 64.
+pub type Lfi64B3V64 = StaticLfi<blocks::Block3<u64>, u64>;
+/// `StaticLfi` with 64-bit position that can index 15 (2^4 - 1) characters, with a BWT block size of 64.
+pub type Lfi64B4V64 = StaticLfi<blocks::Block4<u64>, u64>;
+/// `StaticLfi` with 64-bit position that can index 31 (2^5 - 1) characters, with a BWT block size of 64.
+pub type Lfi64B5V64 = StaticLfi<blocks::Block5<u64>, u64>;
+/// `StaticLfi` that can index 3 (2^2 - 1) characters, with a BWT block size of 128.
+pub type Lfi32B2V128 = StaticLfi<blocks::Block2<u128>>;
+/// `StaticLfi` that can index 7 (2^3 - 1) characters, with a BWT block size of 128.
+pub type Lfi32B3V128 = StaticLfi<blocks::Block3<u128>>;
+/// `StaticLfi` that can index 15 (2^4 - 1) characters, with a BWT block size of 128.
+pub type Lfi32B4V128 = StaticLfi<blocks::Block4<u128>>;
+/// `StaticLfi` that can index 31 (2^5 - 1) characters, with a BWT block size of 128.
+pub type Lfi32B5V128 = StaticLfi<blocks::Block5<u128>>;
+/// `StaticLfi` with 64-bit position that can index 3 (2^2 - 1) characters, with a BWT block size of 128.
+pub type Lfi64B2V128 = StaticLfi<blocks::Block2<u128>, u64>;
+/// `StaticLfi` with 64-bit position that can index 7 (2^3 - 1) characters, with a BWT block size of 128.
+pub type Lfi64B3V128 = StaticLfi<blocks::Block3<u128>, u64>;
+/// `StaticLfi` with 64-bit position that can index 15 (2^4 - 1) characters, with a BWT block size of 128.
+pub type Lfi64B4V128 = StaticLfi<blocks::Block4<u128>, u64>;
+/// `StaticLfi` with 64-bit position that can index 31 (2^5 - 1) characters, with a BWT block size of 128.
+pub type Lfi64B5V128 = StaticLfi<blocks::Block5<u128>, u64>;
 
 // TODO: Check if the specification is accurate.
 /// LtFmIndex that has a maximum number of characters that can be indexed.
-/// (The maximum length of one sequence is u32::MAX)
+/// - The maximum length of concatenated sequence is `P::MAX` (`u32::MAX` by default).
+/// - Use `u64` as `P` for the reference longer than `u32::MAX`.
 #[derive(Clone)]
-pub struct StaticLfi<B: Block<u32>> {
-    inner: LtFmIndex<u32, B>,
+pub struct StaticLfi<B: Block<P>, P: LfiPosition = u32> {
+    inner: LtFmIndex<P, B>,
 }
 
+/// Position type of `StaticLfi` (`u32` or `u64`).
+pub trait LfiPosition: Position + PatternPosition {
+    /// Magic numbers to tag the serialized index of block 2, 3, 4 and 5 (with vector 64).
+    ///  - FNV1A32 hash value of `LtFmIndexPosition{32|64}Block{2|3|4|5}Vector64`
+    const MAGIC_NUMBERS: [u64; 4];
+    /// Magic numbers to tag the serialized index of block 2, 3, 4 and 5 (with vector 128).
+    ///  - FNV1A32 hash value of `LtFmIndexPosition{32|64}Block{2|3|4|5}Vector128`
+    const MAGIC_NUMBERS_V128: [u64; 4];
+}
+impl LfiPosition for u32 {
+    const MAGIC_NUMBERS: [u64; 4] = [
+        2507069426, // 956ed7f2
+        1651246749, // 626c069d
+        1848733752, // 6e317038
+        1780754347, // 6a2427ab
+    ];
+    const MAGIC_NUMBERS_V128: [u64; 4] = [
+        1770778193, // 698bee51
+        740248896,  // 2c1f4d40
+        223024091,  // 0d4b13db
+        3047821682, // b5aa1172
+    ];
+}
+impl LfiPosition for u64 {
+    const MAGIC_NUMBERS: [u64; 4] = [
+        471699337,  // 1c1d8f89
+        2562966382, // 98c3c36e
+        430576927,  // 19aa151f
+        939683484,  // 38026e9c
+    ];
+    const MAGIC_NUMBERS_V128: [u64; 4] = [
+        853119140,  // 32d990a4
+        451277781,  // 1ae5f3d5
+        4052696526, // f18f3dce
+        1277048727, // 4c1e3797
+    ];
+}
+
+/// Maximum size of lookup table in bytes (4 GiB), whether the k-mer size is set by `LfiOption::lookup_table_kmer_size` or calculated.
+pub const MAX_LOOKUP_TABLE_BYTES_SIZE: u64 = 1 << 32;
+
 #[derive(Debug, Clone)]
 /// Option to define the structure of the LtFmIndex.
 pub struct LfiOption {
     pub suffix_array_sampling_ratio: u64,
     pub lookup_table_max_bytes_size : u64,
+    /// If set, the k-mer size of lookup table is fixed, ignoring `lookup_table_max_bytes_size`.
+    ///  - Must be at least one, and the lookup table must be within `MAX_LOOKUP_TABLE_BYTES_SIZE`.
+    pub lookup_table_kmer_size: Option<u32>,
     pub use_safe_guard: bool,
 }
 impl LfiOption {
@@ -39,14 +108,16 @@ impl LfiOption {
         Self {
             suffix_array_sampling_ratio,
             lookup_table_max_bytes_size,
+            lookup_table_kmer_size: None,
             use_safe_guard,
         }
     }
 }
 
-impl <B: Block<u32>> PatternIndex for StaticLfi<B> {
+impl<B: Block<P>, P: LfiPosition> PatternIndex for StaticLfi<B, P> {
     type Option = LfiOption;
     type BuildError = LfiBuildError;
+    type Position = P;
     
     fn new(concatenated_sequence : Vec<u8>, option: Self::Option) -> Result<Self, Self::BuildError> {
         let unique_sequence = get_unique_characters_of_sequence(&concatenated_sequence);
@@ -66,43 +137,83 @@ impl <B: Block<u32>> PatternIndex for StaticLfi<B> {
         }
 
         let sequence_length = concatenated_sequence.len();
-        if sequence_length >= u32::MAX as usize {
-            return Err(Self::BuildError::SequenceLengthOver(u32::MAX as u64));
+        if sequence_length as u64 >= <P as PatternPosition>::MAX {
+            return Err(Self::BuildError::SequenceLengthOver(<P as PatternPosition>::MAX));
         }
-        let lookup_table_kmer_size = calculate_lookup_table_kmer_size(
-            characters_by_index.len(),
-            option.lookup_table_max_bytes_size as usize,
-        );
+        if let Some(kmer_size) = option.lookup_table_kmer_size {
+            let bytes_size = lookup_table_bytes_size::<P>(characters_by_index.len(), kmer_size);
+            if kmer_size == 0 || !matches!(bytes_size, Some(size) if size <= MAX_LOOKUP_TABLE_BYTES_SIZE) {
+                return Err(Self::BuildError::InvalidOption(format!(
+                    "k-mer size of lookup table {} is zero or makes the table over {} bytes",
+                    kmer_size, MAX_LOOKUP_TABLE_BYTES_SIZE,
+                )));
+            }
+        }
+        let lookup_table_kmer_size = option.lookup_table_kmer_size.unwrap_or_else(|| {
+            calculate_lookup_table_kmer_size::<P>(
+                characters_by_index.len(),
+                option.lookup_table_max_bytes_size,
+            )
+        });
 
         match LtFmIndex::build(
             concatenated_sequence,
             &characters_by_index,
-            option.suffix_array_sampling_ratio as u32,
+            <P as Position>::from_u64(option.suffix_array_sampling_ratio),
             lookup_table_kmer_size,
         ) {
             Ok(v) => Ok(Self { inner: v }),
             Err(err) => Err(Self::BuildError::InvalidOption(format!("{}", err))),
         }
     }
-    fn get_sorted_positions(&self, pattern: &[u8]) -> Vec<u32> {
+    fn get_sorted_positions(&self, pattern: &[u8]) -> Vec<P> {
         let mut positions = self.inner.locate(pattern);
         positions.sort_unstable();
         positions
     }
+    fn get_occurrence_count(&self, pattern: &[u8]) -> u64 {
+        <P as Position>::as_u64(self.inner.count(pattern))
+    }
 }
 
-fn calculate_lookup_table_kmer_size(
+impl<B: Block<P>, P: LfiPosition> StaticLfi<B, P> {
+    /// Get the number of indexed characters.
+    pub fn get_num_characters(&self) -> u32 {
+        self.inner.index_count()
+    }
+    /// Get the sampling ratio of suffix array.
+    pub fn get_suffix_array_sampling_ratio(&self) -> u64 {
+        <P as Position>::as_u64(self.inner.suffix_array_sampling_ratio())
+    }
+    /// Get the k-mer size of lookup table.
+    pub fn get_lookup_table_kmer_size(&self) -> u32 {
+        self.inner.lookup_table_kmer_size()
+    }
+}
+
+// At least one, even if the lookup table of the large alphabet (e.g., protein) is over the size
+fn calculate_lookup_table_kmer_size<P>(
     chr_count: usize,
-    maximum_bytes_size: usize,
+    maximum_bytes_size: u64,
 ) -> u32 {
-    let max_cap = 50;
-    for v in 1..=max_cap {
-        let estimated_byte_size_of_lt = (chr_count+1).pow(v);
-        if estimated_byte_size_of_lt >= maximum_bytes_size {
-            return v - 1
-        }
+    let mut kmer_size = 1;
+    while matches!(
+        (chr_count as u64 + 1).checked_pow(kmer_size + 1),
+        Some(estimated_byte_size_of_lt) if estimated_byte_size_of_lt < maximum_bytes_size
+    ) && matches!(
+        lookup_table_bytes_size::<P>(chr_count, kmer_size + 1),
+        Some(bytes_size) if bytes_size <= MAX_LOOKUP_TABLE_BYTES_SIZE
+    ) {
+        kmer_size += 1;
     }
-    max_cap
+    kmer_size
+}
+
+// Size of the lookup table of LtFmIndex: `(chr_count + 2)^k` positions
+// (with the wildcard and the index for the unmatched). None if overflowed.
+fn lookup_table_bytes_size<P>(chr_count: usize, kmer_size: u32) -> Option<u64> {
+    (chr_count as u64 + 2).checked_pow(kmer_size)?
+        .checked_mul(std::mem::size_of::<P>() as u64)
 }
 
 /// Error type for `StaticLfi` build.
@@ -126,9 +237,12 @@ pub enum LfiBuildError {
 use sigalign_core::reference::extensions::{
     Serialize,
     EstimateSize,
+    LoadInPlace,
+    SharedBytes,
+    load_by_copy,
 };
 //  - Serialize
-impl<B: Block<u32>> Serialize for StaticLfi<B> {
+impl<B: Block<P>, P: LfiPosition> Serialize for StaticLfi<B, P> {
     fn save_to<W>(&self, mut writer: W) -> Result<(), std::io::Error> where
         W: std::io::Write
     {
@@ -143,8 +257,17 @@ impl<B: Block<u32>> Serialize for StaticLfi<B> {
         Ok(Self { inner })
     }
 }
+//  - LoadInPlace
+// The index of `lt-fm-index` owns its data, so it is deserialized from the bytes.
+impl<B: Block<P>, P: LfiPosition> LoadInPlace for StaticLfi<B, P> {
+    fn load_in_place(bytes: &SharedBytes, offset: &mut usize) -> Result<Self, std::io::Error> where
+        Self: Sized
+    {
+        load_by_copy(bytes, offset)
+    }
+}
 //  - EstimateSize
-impl<B: Block<u32>> EstimateSize for StaticLfi<B> {
+impl<B: Block<P>, P: LfiPosition> EstimateSize for StaticLfi<B, P> {
     fn serialized_size(&self) -> usize {
         self.inner.to_be_saved_size()
     }
@@ -0,0 +1,298 @@
+use std::cmp::Ordering;
+use std::thread;
+use thiserror::Error;
+
+use sigalign_core::reference::PatternIndex;
+
+/// Uncompressed suffix array of the concatenated sequence.
+///
+/// - Trades memory for speed: every suffix position is stored, so no locate step as in the FM-index.
+///   - The memory usage is about `5 × sequence length` bytes.
+/// - Patterns are located by the binary search skipping the common prefix
+///   already matched at both bounds (LCP-accelerated).
+/// - The suffix array is built by prefix doubling, and the groups of
+///   the same prefix are sorted on multiple threads (`SuffixArrayIndexOption::num_threads`).
+/// - The maximum length of concatenated sequence is `u32::MAX`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SuffixArrayIndex {
+    concatenated_sequence: Vec<u8>,
+    suffix_array: Vec<u32>,
+}
+
+/// Option for `SuffixArrayIndex`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SuffixArrayIndexOption {
+    /// Number of threads to build the suffix array. Zero is regarded as one.
+    pub num_threads: usize,
+}
+
+impl SuffixArrayIndexOption {
+    pub fn new(num_threads: usize) -> Self {
+        Self { num_threads }
+    }
+}
+
+impl Default for SuffixArrayIndexOption {
+    /// Use the available parallelism.
+    fn default() -> Self {
+        let num_threads = thread::available_parallelism().map(|v| v.get()).unwrap_or(1);
+        Self { num_threads }
+    }
+}
+
+/// Error type for `SuffixArrayIndex` build.
+#[derive(Debug, Error)]
+pub enum SuffixArrayIndexBuildError {
+    /// Triggered when sequence length exceeds the maximum allowable capacity.
+    #[error("Sequence length is over the maximum capacity {0}")]
+    SequenceLengthOver(u64),
+}
+
+impl PatternIndex for SuffixArrayIndex {
+    type Option = SuffixArrayIndexOption;
+    type BuildError = SuffixArrayIndexBuildError;
+    type Position = u32;
+
+    fn new(concatenated_sequence: Vec<u8>, option: Self::Option) -> Result<Self, Self::BuildError> {
+        if concatenated_sequence.len() as u64 >= u32::MAX as u64 {
+            return Err(Self::BuildError::SequenceLengthOver(u32::MAX as u64));
+        }
+        let suffix_array = build_suffix_array(&concatenated_sequence, option.num_threads.max(1));
+        Ok(Self {
+            concatenated_sequence,
+            suffix_array,
+        })
+    }
+    fn get_sorted_positions(&self, pattern: &[u8]) -> Vec<u32> {
+        if pattern.is_empty() {
+            return Vec::new()
+        }
+        let start = self.partition_point(0, pattern, false);
+        let end = self.partition_point(start, pattern, true);
+        let mut positions = self.suffix_array[start..end].to_vec();
+        positions.sort_unstable();
+        positions
+    }
+    fn get_occurrence_count(&self, pattern: &[u8]) -> u64 {
+        if pattern.is_empty() {
+            return 0
+        }
+        let start = self.partition_point(0, pattern, false);
+        let end = self.partition_point(start, pattern, true);
+        (end - start) as u64
+    }
+}
+
+impl SuffixArrayIndex {
+    /// Get the length of the indexed sequence.
+    pub fn get_sequence_length(&self) -> usize {
+        self.concatenated_sequence.len()
+    }
+    /// Index of the first suffix (from `left`) of which prefix is greater than the pattern
+    /// (or equal to, if not `include_equal`).
+    fn partition_point(&self, mut left: usize, pattern: &[u8], include_equal: bool) -> usize {
+        let mut right = self.suffix_array.len();
+        // Length of common prefix with the pattern at the suffixes just outside of the both bounds
+        let mut left_lcp = 0;
+        let mut right_lcp = 0;
+        while left < right {
+            let middle = left + (right - left) / 2;
+            let (ordering, lcp) = self.compare_suffix(
+                self.suffix_array[middle] as usize,
+                pattern,
+                left_lcp.min(right_lcp),
+            );
+            let is_before = match ordering {
+                Ordering::Less => true,
+                Ordering::Equal => include_equal,
+                Ordering::Greater => false,
+            };
+            if is_before {
+                left = middle + 1;
+                left_lcp = lcp;
+            } else {
+                right = middle;
+                right_lcp = lcp;
+            }
+        }
+        left
+    }
+    /// Compare the prefix of the suffix (of pattern length) with the pattern,
+    /// skipping the first `skip` bytes that are known to be the same.
+    /// Returns the ordering and the length of common prefix.
+    #[inline]
+    fn compare_suffix(&self, position: usize, pattern: &[u8], skip: usize) -> (Ordering, usize) {
+        let suffix = &self.concatenated_sequence[position..];
+        for (index, pattern_byte) in pattern.iter().enumerate().skip(skip) {
+            match suffix.get(index) {
+                None => return (Ordering::Less, index),
+                Some(byte) => match byte.cmp(pattern_byte) {
+                    Ordering::Equal => {},
+                    ordering => return (ordering, index),
+                },
+            }
+        }
+        (Ordering::Equal, pattern.len())
+    }
+}
+
+/// Build the suffix array by prefix doubling.
+///  - `rank` of a suffix is the index of the first suffix of the group sharing the same prefix.
+///  - Each round sorts the groups of more than one suffix by the rank of the suffix `k` later.
+fn build_suffix_array(sequence: &[u8], num_threads: usize) -> Vec<u32> {
+    let length = sequence.len();
+    let mut suffix_array: Vec<u32> = (0..length as u32).collect();
+    suffix_array.sort_by_key(|&position| sequence[position as usize]);
+
+    let mut rank = vec![0_u32; length];
+    let mut groups = Vec::new();
+    let mut group_start = 0;
+    for index in 0..length {
+        let is_new_group = index == 0
+            || sequence[suffix_array[index] as usize] != sequence[suffix_array[index - 1] as usize];
+        if is_new_group {
+            push_unsorted_group(&mut groups, group_start, index);
+            group_start = index;
+        }
+        rank[suffix_array[index] as usize] = group_start as u32;
+    }
+    push_unsorted_group(&mut groups, group_start, length);
+
+    let mut new_rank = rank.clone();
+    let mut offset = 1;
+    while !groups.is_empty() {
+        // Zero for the suffix shorter than the offset
+        let key_of = |position: u32| -> u32 {
+            rank.get(position as usize + offset).map(|&v| v + 1).unwrap_or(0)
+        };
+        sort_groups(&mut suffix_array, &groups, num_threads, key_of);
+
+        let mut next_groups = Vec::new();
+        for &(start, end) in &groups {
+            let mut group_start = start;
+            for index in start..end {
+                if index != start && key_of(suffix_array[index]) != key_of(suffix_array[index - 1]) {
+                    push_unsorted_group(&mut next_groups, group_start, index);
+                    group_start = index;
+                }
+                new_rank[suffix_array[index] as usize] = group_start as u32;
+            }
+            push_unsorted_group(&mut next_groups, group_start, end);
+        }
+        for &(start, end) in &groups {
+            for &position in &suffix_array[start..end] {
+                rank[position as usize] = new_rank[position as usize];
+            }
+        }
+        groups = next_groups;
+        offset *= 2;
+    }
+    suffix_array
+}
+
+#[inline]
+fn push_unsorted_group(groups: &mut Vec<(usize, usize)>, start: usize, end: usize) {
+    if end - start > 1 {
+        groups.push((start, end));
+    }
+}
+
+/// Sort each group of the suffix array by the key, dividing the groups into the threads.
+fn sort_groups<F>(
+    suffix_array: &mut [u32],
+    groups: &[(usize, usize)],
+    num_threads: usize,
+    key_of: F,
+) where
+    F: Fn(u32) -> u32 + Sync,
+{
+    let sort_group = |group: &mut [u32]| group.sort_unstable_by_key(|&position| key_of(position));
+    let total_size: usize = groups.iter().map(|(start, end)| end - start).sum();
+    if num_threads == 1 || total_size < MIN_SIZE_TO_SORT_IN_PARALLEL {
+        for &(start, end) in groups {
+            sort_group(&mut suffix_array[start..end]);
+        }
+        return
+    }
+
+    // Split the suffix array at the group boundaries into chunks of similar size
+    let chunk_size = total_size.div_ceil(num_threads);
+    let mut chunks: Vec<Vec<&mut [u32]>> = Vec::with_capacity(num_threads);
+    let mut chunk = Vec::new();
+    let mut size_of_chunk = 0;
+    let mut rest = suffix_array;
+    let mut consumed = 0;
+    for &(start, end) in groups {
+        let (_, tail) = rest.split_at_mut(start - consumed);
+        let (group, tail) = tail.split_at_mut(end - start);
+        rest = tail;
+        consumed = end;
+
+        chunk.push(group);
+        size_of_chunk += end - start;
+        if size_of_chunk >= chunk_size {
+            chunks.push(std::mem::take(&mut chunk));
+            size_of_chunk = 0;
+        }
+    }
+    if !chunk.is_empty() {
+        chunks.push(chunk);
+    }
+
+    let sort_group = &sort_group;
+    thread::scope(|scope| {
+        for chunk in chunks {
+            scope.spawn(move || {
+                chunk.into_iter().for_each(sort_group);
+            });
+        }
+    });
+}
+
+const MIN_SIZE_TO_SORT_IN_PARALLEL: usize = 1 << 16;
+
+// Impl Extensions
+use capwriter::{Save, Load};
+use sigalign_core::reference::extensions::{
+    Serialize,
+    EstimateSize,
+    LoadInPlace,
+    SharedBytes,
+    load_by_copy,
+};
+//  - Serialize
+impl Serialize for SuffixArrayIndex {
+    fn save_to<W>(&self, mut writer: W) -> Result<(), std::io::Error> where
+        W: std::io::Write
+    {
+        self.concatenated_sequence.save_to(&mut writer)?;
+        self.suffix_array.save_to(&mut writer)?;
+        Ok(())
+    }
+    fn load_from<R>(mut reader: R) -> Result<Self, std::io::Error> where
+        R: std::io::Read,
+        Self: Sized
+    {
+        let concatenated_sequence = Vec::load_from(&mut reader)?;
+        let suffix_array = Vec::load_from(&mut reader)?;
+        Ok(Self {
+            concatenated_sequence,
+            suffix_array,
+        })
+    }
+}
+//  - LoadInPlace
+impl LoadInPlace for SuffixArrayIndex {
+    fn load_in_place(bytes: &SharedBytes, offset: &mut usize) -> Result<Self, std::io::Error> where
+        Self: Sized
+    {
+        load_by_copy(bytes, offset)
+    }
+}
+//  - EstimateSize
+impl EstimateSize for SuffixArrayIndex {
+    fn serialized_size(&self) -> usize {
+        self.concatenated_sequence.to_be_saved_size()
+        + self.suffix_array.to_be_saved_size()
+    }
+}
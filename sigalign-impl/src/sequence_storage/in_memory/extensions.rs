@@ -1,4 +1,4 @@
-use std::io::{Read, Write, Error, ErrorKind};
+use std::io::{Read, Write, Error, ErrorKind, Cursor};
 
 use capwriter::{Save, Load};
 
@@ -6,9 +6,11 @@ use sigalign_core::reference::extensions::{
     Serialize,
     EstimateSize,
     LabelStorage,
+    LoadInPlace,
+    SharedBytes,
 };
 use crate::core::{EndianType, ReadBytesExt, WriteBytesExt};
-use super::InMemoryStorage;
+use super::{InMemoryStorage, SequenceBytes};
 
 //  - Serialize
 impl Serialize for InMemoryStorage {
@@ -16,7 +18,7 @@ impl Serialize for InMemoryStorage {
         W: Write
     {
         writer.write_u64::<EndianType>(self.target_count as u64)?;
-        self.concatenated_sequence.save_to(&mut writer)?;
+        (&self.concatenated_sequence[..]).save_to(&mut writer)?;
         self.sequence_index.save_to(&mut writer)?;
         self.concatenated_label.as_bytes().save_to(&mut writer)?;
         self.label_index.save_to(&mut writer)?;
@@ -27,7 +29,7 @@ impl Serialize for InMemoryStorage {
         Self: Sized,
     {
         let target_count = reader.read_u64::<EndianType>()? as usize;
-        let concatenated_sequence = Vec::load_from(&mut reader)?;
+        let concatenated_sequence = SequenceBytes::Owned(Vec::load_from(&mut reader)?);
         let sequence_index = Vec::load_from(&mut reader)?;
         let concatenated_label = match String::from_utf8(Vec::<u8>::load_from(&mut reader)?) {
             Ok(v) => v,
@@ -44,13 +46,52 @@ impl Serialize for InMemoryStorage {
     }
 }
 
+//  - LoadInPlace
+// The concatenated sequence refers to the bytes without copy.
+impl LoadInPlace for InMemoryStorage {
+    fn load_in_place(bytes: &SharedBytes, offset: &mut usize) -> Result<Self, Error> where
+        Self: Sized
+    {
+        let shared: &[u8] = (**bytes).as_ref();
+        let mut cursor = Cursor::new(shared.get(*offset..).ok_or(ErrorKind::UnexpectedEof)?);
+
+        let target_count = cursor.read_u64::<EndianType>()? as usize;
+        let concatenated_sequence = {
+            // Length is written as `usize` by `capwriter`
+            #[cfg(target_pointer_width = "32")]
+            let length = cursor.read_u32::<EndianType>()? as usize;
+            #[cfg(target_pointer_width = "64")]
+            let length = cursor.read_u64::<EndianType>()? as usize;
+            let start = *offset + cursor.position() as usize;
+            let end = start.checked_add(length).filter(|&end| end <= shared.len()).ok_or(ErrorKind::UnexpectedEof)?;
+            cursor.set_position((end - *offset) as u64);
+            SequenceBytes::Shared { bytes: bytes.clone(), range: start..end }
+        };
+        let sequence_index = Vec::load_from(&mut cursor)?;
+        let concatenated_label = match String::from_utf8(Vec::<u8>::load_from(&mut cursor)?) {
+            Ok(v) => v,
+            Err(_) => return Err(ErrorKind::InvalidData.into()),
+        };
+        let label_index = Vec::load_from(&mut cursor)?;
+        *offset += cursor.position() as usize;
+
+        Ok(Self {
+            target_count,
+            concatenated_sequence,
+            sequence_index,
+            concatenated_label,
+            label_index,
+        })
+    }
+}
+
 //  - EstimateSize
 impl EstimateSize for InMemoryStorage {
     fn serialized_size(&self) -> usize {
         // target_count
         std::mem::size_of::<u64>()
         // concatenated_sequence
-        + self.concatenated_sequence.to_be_saved_size()
+        + (&self.concatenated_sequence[..]).to_be_saved_size()
         // sequence_index
         + self.sequence_index.to_be_saved_size()
         // concatenated_label
@@ -15,7 +15,7 @@ use sigalign_utils::sequence_reader::{
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct InMemoryStorage {
     target_count: usize,
-    concatenated_sequence: Vec<u8>,
+    concatenated_sequence: SequenceBytes,
     sequence_index: Vec<usize>,
     concatenated_label: String,
     label_index: Vec<usize>,
@@ -58,10 +58,10 @@ impl SequenceStorage for InMemoryStorage {
     }
     fn get_concatenated_sequence_with_boundaries_of_targets(&self) -> (
         Vec<u8>,
-        Vec<u32>,
+        Vec<u64>,
     ) {
         let concatenated_sequence = self.concatenated_sequence.to_vec();
-        let boundaries = self.sequence_index.iter().map(|x| *x as u32).collect();
+        let boundaries = self.sequence_index.iter().map(|x| *x as u64).collect();
         (concatenated_sequence, boundaries)
     }
 }
@@ -75,7 +75,7 @@ impl InMemoryStorage {
     pub fn new() -> Self {
         Self {
             target_count: 0,
-            concatenated_sequence: Vec::new(),
+            concatenated_sequence: SequenceBytes::Owned(Vec::new()),
             sequence_index: vec![0],
             concatenated_label: String::new(),
             label_index: vec![0],
@@ -87,7 +87,7 @@ impl InMemoryStorage {
         sequence: &[u8],
     ) {
         self.target_count += 1;
-        self.concatenated_sequence.extend_from_slice(sequence);
+        self.concatenated_sequence.to_mut().extend_from_slice(sequence);
         self.sequence_index.push(self.concatenated_sequence.len());
         self.concatenated_label.push_str(label);
         self.label_index.push(self.concatenated_label.len());
@@ -96,7 +96,7 @@ impl InMemoryStorage {
         let mut fasta_reader = FastaReader::new(reader);
         while let Some(mut record) = fasta_reader.next() {
             self.target_count += 1;
-            record.extend_seq_buf(&mut self.concatenated_sequence);
+            record.extend_seq_buf(self.concatenated_sequence.to_mut());
             self.sequence_index.push(self.concatenated_sequence.len());
             record.extend_id_string(&mut self.concatenated_label)?;
             self.label_index.push(self.concatenated_label.len());
@@ -119,9 +119,9 @@ impl InMemoryStorage {
         
         while let Some(mut record) = fasta_reader.next() {
             record.extend_seq_buf(&mut seq_buffer);
-            let new_seq_length = seq_buffer.len() as u32;
+            let new_seq_length = seq_buffer.len() as u64;
 
-            if (current_seq_length != 0) && (current_seq_length + new_seq_length > max_length) {
+            if (current_seq_length != 0) && (current_seq_length + new_seq_length > max_length as u64) {
                 let filled_storage = std::mem::replace(self, Self::new());
                 // Save current storage
                 filled_storages.push(filled_storage);
@@ -132,7 +132,7 @@ impl InMemoryStorage {
             // Add record to current storage
             current_seq_length += new_seq_length;
             self.target_count += 1;
-            self.concatenated_sequence.append(&mut seq_buffer);
+            self.concatenated_sequence.to_mut().append(&mut seq_buffer);
             self.sequence_index.push(self.concatenated_sequence.len());
             record.extend_id_string(&mut self.concatenated_label)?;
             self.label_index.push(self.concatenated_label.len());
@@ -144,7 +144,7 @@ impl InMemoryStorage {
         let mut fasta_reader = FastaReader::new(decomp_reader);
         while let Some(mut record) = fasta_reader.next() {
             self.target_count += 1;
-            record.extend_seq_buf(&mut self.concatenated_sequence);
+            record.extend_seq_buf(self.concatenated_sequence.to_mut());
             self.sequence_index.push(self.concatenated_sequence.len());
             record.extend_id_string(&mut self.concatenated_label)?;
             self.label_index.push(self.concatenated_label.len());
@@ -154,7 +154,7 @@ impl InMemoryStorage {
     pub fn merge(&mut self, other: Self) {
         let Self {
             target_count: other_target_count,
-            concatenated_sequence: mut other_combined_sequence,
+            concatenated_sequence: other_combined_sequence,
             sequence_index: other_sequence_index,
             concatenated_label: other_combined_label,
             label_index: other_label_index,
@@ -162,7 +162,7 @@ impl InMemoryStorage {
         // record_count
         self.target_count += other_target_count;
         // concatenated_sequence
-        self.concatenated_sequence.append(&mut other_combined_sequence);
+        self.concatenated_sequence.to_mut().append(&mut other_combined_sequence.into_vec());
         // sequence_index
         let last_seq_idx = *self.sequence_index.last().unwrap();
         self.sequence_index.reserve(other_target_count);
@@ -187,8 +187,12 @@ impl InMemoryStorage {
         let seq = buffer.buffered_sequence().to_vec();
         Some(seq)
     }
-    pub fn get_total_length(&self) -> u32 {
-        self.concatenated_sequence.len() as u32
+    /// True if the concatenated sequence refers to the shared bytes (e.g., memory-mapped file).
+    pub fn is_sequence_shared(&self) -> bool {
+        self.concatenated_sequence.is_shared()
+    }
+    pub fn get_total_length(&self) -> u64 {
+        self.concatenated_sequence.len() as u64
     }
     /// Remove all labels
     /// !Cannot be undone
@@ -199,7 +203,7 @@ impl InMemoryStorage {
     /// Set sequence to uppercase
     /// !Cannot be undone
     pub fn set_sequences_to_uppercase(&mut self) {
-        self.concatenated_sequence.make_ascii_uppercase();
+        self.concatenated_sequence.to_mut().make_ascii_uppercase();
     }
     /// Make all designated bases to defined base
     /// !Cannot be undone
@@ -211,7 +215,7 @@ impl InMemoryStorage {
         bases_to_change.iter().for_each(|v| {
             byte_mapper[*v as usize] = target_base;
         });
-        self.concatenated_sequence.iter_mut().for_each(|v| {
+        self.concatenated_sequence.to_mut().iter_mut().for_each(|v| {
             *v = byte_mapper[*v as usize];
         });
     }
@@ -227,3 +231,5 @@ impl InMemoryBuffer {
 }
 
 mod extensions;
+mod sequence_bytes;
+use sequence_bytes::SequenceBytes;
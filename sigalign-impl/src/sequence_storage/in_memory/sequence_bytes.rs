@@ -0,0 +1,62 @@
+use std::ops::{Deref, Range};
+
+use sigalign_core::reference::extensions::SharedBytes;
+
+/// Concatenated sequence of `InMemoryStorage`.
+///  - `Owned`: Built or loaded by copy.
+///  - `Shared`: Refers to the range of `SharedBytes` (e.g., memory-mapped file).
+///    It is copied to `Owned` when modified.
+#[derive(Clone)]
+pub enum SequenceBytes {
+    Owned(Vec<u8>),
+    Shared {
+        bytes: SharedBytes,
+        range: Range<usize>,
+    },
+}
+
+impl SequenceBytes {
+    pub fn is_shared(&self) -> bool {
+        matches!(self, Self::Shared { .. })
+    }
+    /// Get the mutable vector, copying the shared bytes if needed.
+    pub fn to_mut(&mut self) -> &mut Vec<u8> {
+        if let Self::Shared { .. } = self {
+            *self = Self::Owned(self.to_vec());
+        }
+        match self {
+            Self::Owned(vec) => vec,
+            Self::Shared { .. } => unreachable!(),
+        }
+    }
+    pub fn into_vec(self) -> Vec<u8> {
+        match self {
+            Self::Owned(vec) => vec,
+            Self::Shared { .. } => self.to_vec(),
+        }
+    }
+}
+
+impl Deref for SequenceBytes {
+    type Target = [u8];
+
+    fn deref(&self) -> &Self::Target {
+        match self {
+            Self::Owned(vec) => vec,
+            Self::Shared { bytes, range } => &(**bytes).as_ref()[range.clone()],
+        }
+    }
+}
+
+impl std::fmt::Debug for SequenceBytes {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.deref().fmt(f)
+    }
+}
+
+impl PartialEq for SequenceBytes {
+    fn eq(&self, other: &Self) -> bool {
+        self.deref() == other.deref()
+    }
+}
+impl Eq for SequenceBytes {}
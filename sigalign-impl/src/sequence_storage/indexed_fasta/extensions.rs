@@ -0,0 +1,104 @@
+use std::io::{Read, Write, Error, ErrorKind};
+use std::path::PathBuf;
+
+use capwriter::{Save, Load};
+
+use sigalign_core::reference::extensions::{
+    Serialize,
+    EstimateSize,
+    LabelStorage,
+    LoadInPlace,
+    SharedBytes,
+    load_by_copy,
+};
+use crate::core::{EndianType, ReadBytesExt, WriteBytesExt};
+use super::{IndexedFastaStorage, FaiRecord};
+
+// Only the path and the index are saved, not the sequences.
+//  - Serialize
+impl Serialize for IndexedFastaStorage {
+    fn save_to<W>(&self, mut writer: W) -> Result<(), Error> where
+        W: Write
+    {
+        let fasta_file_path = match self.fasta_file_path.to_str() {
+            Some(v) => v,
+            None => return Err(Error::new(ErrorKind::InvalidData, "Path of FASTA file is not valid UTF-8")),
+        };
+        fasta_file_path.as_bytes().save_to(&mut writer)?;
+        writer.write_u64::<EndianType>(self.records.len() as u64)?;
+        for record in &self.records {
+            writer.write_u64::<EndianType>(record.length)?;
+            writer.write_u64::<EndianType>(record.offset)?;
+            writer.write_u64::<EndianType>(record.line_bases)?;
+            writer.write_u64::<EndianType>(record.line_bytes)?;
+        }
+        self.concatenated_label.as_bytes().save_to(&mut writer)?;
+        self.label_index.save_to(&mut writer)?;
+        Ok(())
+    }
+    fn load_from<R>(mut reader: R) -> Result<Self, Error> where
+        R: Read,
+        Self: Sized,
+    {
+        let fasta_file_path = match String::from_utf8(Vec::<u8>::load_from(&mut reader)?) {
+            Ok(v) => PathBuf::from(v),
+            Err(_) => return Err(ErrorKind::InvalidData.into()),
+        };
+        let target_count = reader.read_u64::<EndianType>()? as usize;
+        let mut records = Vec::with_capacity(target_count);
+        for _ in 0..target_count {
+            records.push(FaiRecord {
+                length: reader.read_u64::<EndianType>()?,
+                offset: reader.read_u64::<EndianType>()?,
+                line_bases: reader.read_u64::<EndianType>()?,
+                line_bytes: reader.read_u64::<EndianType>()?,
+            });
+        }
+        let concatenated_label = match String::from_utf8(Vec::<u8>::load_from(&mut reader)?) {
+            Ok(v) => v,
+            Err(_) => return Err(ErrorKind::InvalidData.into()),
+        };
+        let label_index = Vec::load_from(&mut reader)?;
+        Ok(Self {
+            fasta_file_path,
+            records,
+            concatenated_label,
+            label_index,
+        })
+    }
+}
+//  - LoadInPlace
+impl LoadInPlace for IndexedFastaStorage {
+    fn load_in_place(bytes: &SharedBytes, offset: &mut usize) -> Result<Self, Error> where
+        Self: Sized
+    {
+        load_by_copy(bytes, offset)
+    }
+}
+//  - EstimateSize
+impl EstimateSize for IndexedFastaStorage {
+    fn serialized_size(&self) -> usize {
+        // fasta_file_path
+        self.fasta_file_path.as_os_str().len() + std::mem::size_of::<usize>()
+        // records
+        + std::mem::size_of::<u64>() * (1 + 4 * self.records.len())
+        // concatenated_label
+        + self.concatenated_label.as_bytes().to_be_saved_size()
+        // label_index
+        + self.label_index.to_be_saved_size()
+    }
+}
+//  - Label Storage
+impl LabelStorage for IndexedFastaStorage {
+    fn label_of_target_unchecked(&self, target_index: u32) -> String {
+        self.label_of(target_index as usize).to_string()
+    }
+}
+impl IndexedFastaStorage {
+    pub fn get_label_safely(&self, target_index: u32) -> Option<String> {
+        if target_index as usize >= self.records.len() {
+            return None
+        }
+        Some(self.label_of_target_unchecked(target_index))
+    }
+}
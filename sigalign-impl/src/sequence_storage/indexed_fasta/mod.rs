@@ -0,0 +1,262 @@
+use std::{
+    fs::File,
+    io::{BufRead, BufReader, BufWriter, Read, Seek, SeekFrom, Write},
+    path::{Path, PathBuf},
+};
+use thiserror::Error;
+
+use sigalign_core::reference::{
+    SequenceStorage,
+    SequenceBuffer,
+};
+
+/// `SequenceStorage` that reads sequences from an indexed FASTA file (`samtools faidx` format).
+///
+/// - Only the offsets of the records (`.fai`) are stored in memory.
+/// - The sequence is read from the FASTA file when the buffer is filled.
+/// - The FASTA file must not be modified after indexing.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IndexedFastaStorage {
+    fasta_file_path: PathBuf,
+    records: Vec<FaiRecord>,
+    concatenated_label: String,
+    label_index: Vec<usize>,
+}
+
+/// A line of `.fai` file except the name.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct FaiRecord {
+    length: u64,
+    offset: u64,
+    line_bases: u64,
+    line_bytes: u64,
+}
+
+/// `SequenceBuffer` for `IndexedFastaStorage`.
+///  - Each buffer opens its own file handle when filled first.
+pub struct IndexedFastaBuffer {
+    file: Option<File>,
+    raw_buffer: Vec<u8>,
+    sequence_buffer: Vec<u8>,
+}
+
+/// Error for indexing or reading the indexed FASTA file.
+#[derive(Debug, Error)]
+pub enum IndexedFastaError {
+    #[error("Invalid FASTA index at line {0}")]
+    InvalidIndex(usize),
+    #[error("Invalid FASTA format at record {0}: {1}")]
+    InvalidFasta(usize, &'static str),
+    #[error(transparent)]
+    IoError(#[from] std::io::Error),
+}
+
+// Sequence Storage
+impl SequenceStorage for IndexedFastaStorage {
+    type Buffer = IndexedFastaBuffer;
+
+    fn num_targets(&self) -> u32 {
+        self.records.len() as u32
+    }
+    fn get_buffer(&self) -> Self::Buffer {
+        IndexedFastaBuffer {
+            file: None,
+            raw_buffer: Vec::new(),
+            sequence_buffer: Vec::new(),
+        }
+    }
+    /// ## Panics
+    /// Panics if the FASTA file cannot be read (e.g., removed or truncated after indexing).
+    fn fill_buffer(&self, target_index: u32, buffer: &mut Self::Buffer) {
+        let record = &self.records[target_index as usize];
+        if let Err(err) = buffer.fill_with_record(&self.fasta_file_path, record) {
+            panic!(
+                "Failed to read the sequence of target {} from {}: {}",
+                target_index, self.fasta_file_path.display(), err,
+            );
+        }
+    }
+}
+impl SequenceBuffer for IndexedFastaBuffer {
+    fn buffered_sequence(&self) -> &[u8] {
+        &self.sequence_buffer
+    }
+}
+
+impl IndexedFastaStorage {
+    /// Load the index of FASTA file from `{fasta_file_path}.fai`.
+    /// Use `index_fasta_file` if the index does not exist.
+    pub fn new<P: AsRef<Path>>(fasta_file_path: P) -> Result<Self, IndexedFastaError> {
+        let fai_file_path = Self::default_fai_file_path(&fasta_file_path);
+        Self::from_fai_file(fasta_file_path, fai_file_path)
+    }
+    /// Load the index of FASTA file from the `.fai` file at any path.
+    pub fn from_fai_file<P1, P2>(
+        fasta_file_path: P1,
+        fai_file_path: P2,
+    ) -> Result<Self, IndexedFastaError> where
+        P1: AsRef<Path>,
+        P2: AsRef<Path>,
+    {
+        // Absolute path not to depend on the working directory when loaded.
+        let fasta_file_path = std::fs::canonicalize(fasta_file_path)?;
+        let mut storage = Self::with_path(fasta_file_path);
+
+        let reader = BufReader::new(File::open(fai_file_path)?);
+        for (line_index, line) in reader.lines().enumerate() {
+            let line = line?;
+            if line.is_empty() {
+                continue
+            }
+            let fields: Vec<&str> = line.split('\t').collect();
+            if fields.len() != 5 {
+                return Err(IndexedFastaError::InvalidIndex(line_index + 1));
+            }
+            let mut numbers = [0_u64; 4];
+            for (number, field) in numbers.iter_mut().zip(&fields[1..]) {
+                *number = field.parse().map_err(|_| IndexedFastaError::InvalidIndex(line_index + 1))?;
+            }
+            let [length, offset, line_bases, line_bytes] = numbers;
+            if length != 0 && (line_bases == 0 || line_bytes < line_bases) {
+                return Err(IndexedFastaError::InvalidIndex(line_index + 1));
+            }
+            storage.push_record(fields[0], FaiRecord { length, offset, line_bases, line_bytes });
+        }
+        Ok(storage)
+    }
+    /// Index the FASTA file, write the index to `{fasta_file_path}.fai`, and return the storage.
+    ///  - All lines of a record, except the last one, must have the same length.
+    pub fn index_fasta_file<P: AsRef<Path>>(fasta_file_path: P) -> Result<Self, IndexedFastaError> {
+        let fasta_file_path = std::fs::canonicalize(fasta_file_path)?;
+        let mut storage = Self::with_path(fasta_file_path.clone());
+        let mut reader = BufReader::new(File::open(&fasta_file_path)?);
+
+        let mut line = Vec::new();
+        let mut position = 0_u64;
+        // (label, record, is the last line shorter)
+        let mut current: Option<(String, FaiRecord, bool)> = None;
+        loop {
+            line.clear();
+            let line_bytes = reader.read_until(b'\n', &mut line)? as u64;
+            if line_bytes == 0 {
+                break
+            }
+            position += line_bytes;
+            let line_bases = line.iter().filter(|&&b| b != b'\n' && b != b'\r').count() as u64;
+
+            if line.first() == Some(&b'>') {
+                if let Some((label, record, _)) = current.take() {
+                    storage.push_record(&label, record);
+                }
+                let header = String::from_utf8_lossy(&line[1..]);
+                let label = header.split_whitespace().next().unwrap_or_default().to_string();
+                let record = FaiRecord { length: 0, offset: position, line_bases: 0, line_bytes: 0 };
+                current = Some((label, record, false));
+                continue
+            }
+            let record_index = storage.records.len() + 1;
+            let Some((_, record, is_shorter)) = current.as_mut() else {
+                if line_bases == 0 {
+                    continue
+                }
+                return Err(IndexedFastaError::InvalidFasta(record_index, "sequence before the header"));
+            };
+            if line_bases == 0 {
+                // Empty line is allowed only at the end of record.
+                *is_shorter = true;
+                continue
+            }
+            if *is_shorter {
+                return Err(IndexedFastaError::InvalidFasta(record_index, "different line length"));
+            }
+            if record.line_bases == 0 {
+                record.line_bases = line_bases;
+                record.line_bytes = line_bytes;
+            } else if line_bases > record.line_bases || (line_bases == record.line_bases && line_bytes != record.line_bytes) {
+                return Err(IndexedFastaError::InvalidFasta(record_index, "different line length"));
+            } else if line_bases < record.line_bases {
+                *is_shorter = true;
+            }
+            record.length += line_bases;
+        }
+        if let Some((label, record, _)) = current.take() {
+            storage.push_record(&label, record);
+        }
+
+        storage.write_fai_file(Self::default_fai_file_path(&storage.fasta_file_path))?;
+        Ok(storage)
+    }
+    /// Write the index as `.fai` file.
+    pub fn write_fai_file<P: AsRef<Path>>(&self, fai_file_path: P) -> Result<(), std::io::Error> {
+        let mut writer = BufWriter::new(File::create(fai_file_path)?);
+        for (target_index, record) in self.records.iter().enumerate() {
+            writeln!(
+                writer,
+                "{}\t{}\t{}\t{}\t{}",
+                self.label_of(target_index), record.length, record.offset, record.line_bases, record.line_bytes,
+            )?;
+        }
+        writer.flush()
+    }
+    pub fn get_fasta_file_path(&self) -> &Path {
+        &self.fasta_file_path
+    }
+    pub fn get_sequence_safely(&self, target_index: u32) -> Option<Vec<u8>> {
+        if target_index as usize >= self.records.len() {
+            return None
+        }
+        let mut buffer = self.get_buffer();
+        self.fill_buffer(target_index, &mut buffer);
+        Some(buffer.sequence_buffer)
+    }
+    pub fn get_total_length(&self) -> u64 {
+        self.records.iter().map(|record| record.length).sum()
+    }
+
+    fn with_path(fasta_file_path: PathBuf) -> Self {
+        Self {
+            fasta_file_path,
+            records: Vec::new(),
+            concatenated_label: String::new(),
+            label_index: vec![0],
+        }
+    }
+    fn default_fai_file_path<P: AsRef<Path>>(fasta_file_path: P) -> PathBuf {
+        let mut fai_file_path = fasta_file_path.as_ref().as_os_str().to_owned();
+        fai_file_path.push(".fai");
+        PathBuf::from(fai_file_path)
+    }
+    fn push_record(&mut self, label: &str, record: FaiRecord) {
+        self.records.push(record);
+        self.concatenated_label.push_str(label);
+        self.label_index.push(self.concatenated_label.len());
+    }
+    fn label_of(&self, target_index: usize) -> &str {
+        &self.concatenated_label[self.label_index[target_index]..self.label_index[target_index + 1]]
+    }
+}
+
+impl IndexedFastaBuffer {
+    fn fill_with_record(&mut self, fasta_file_path: &Path, record: &FaiRecord) -> Result<(), std::io::Error> {
+        self.sequence_buffer.clear();
+        if record.length == 0 {
+            return Ok(())
+        }
+        let full_lines = (record.length - 1) / record.line_bases;
+        let raw_length = full_lines * record.line_bytes + (record.length - full_lines * record.line_bases);
+
+        let file = match &mut self.file {
+            Some(file) => file,
+            None => self.file.insert(File::open(fasta_file_path)?),
+        };
+        file.seek(SeekFrom::Start(record.offset))?;
+        self.raw_buffer.resize(raw_length as usize, 0);
+        file.read_exact(&mut self.raw_buffer)?;
+        self.sequence_buffer.extend(
+            self.raw_buffer.iter().filter(|&&b| b != b'\n' && b != b'\r')
+        );
+        Ok(())
+    }
+}
+
+mod extensions;
@@ -5,6 +5,14 @@ Implementations for the trait `sigalign_core::reference::SequenceStorage`.
 - `in_memory`: Stores the sequences in memory.
   - Generally most fast.
   - Requires enough memory to store all sequences.
+- `indexed_fasta`: Reads the sequences from the indexed FASTA file (`.fai`).
+  - Only the index is in memory, so the reference larger than memory can be used.
+  - Slower than `in_memory` because of the disk access.
+- `packed_dna`: Stores the nucleotide sequences in memory at 2 bits per base.
+  - About 1/4 of memory of `in_memory` for the sequences mostly consisting of `ACGT`.
+  - Slower than `in_memory` because the sequence is decoded when the buffer is filled.
 */
 
-pub mod in_memory;
\ No newline at end of file
+pub mod in_memory;
+pub mod indexed_fasta;
+pub mod packed_dna;
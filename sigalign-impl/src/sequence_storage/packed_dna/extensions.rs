@@ -0,0 +1,113 @@
+use std::io::{Read, Write, Error, ErrorKind};
+
+use capwriter::{Save, Load};
+
+use sigalign_core::reference::extensions::{
+    Serialize,
+    EstimateSize,
+    LabelStorage,
+    LoadInPlace,
+    SharedBytes,
+    load_by_copy,
+};
+use crate::core::{EndianType, ReadBytesExt, WriteBytesExt};
+use super::PackedDnaStorage;
+
+//  - Serialize
+impl Serialize for PackedDnaStorage {
+    fn save_to<W>(&self, mut writer: W) -> Result<(), Error> where
+        W: Write
+    {
+        writer.write_u64::<EndianType>(self.target_count as u64)?;
+        writer.write_u64::<EndianType>(self.total_length as u64)?;
+        self.packed_sequence.save_to(&mut writer)?;
+        self.sequence_index.save_to(&mut writer)?;
+        self.lowercase_starts.save_to(&mut writer)?;
+        self.lowercase_ends.save_to(&mut writer)?;
+        self.exception_starts.save_to(&mut writer)?;
+        self.exception_ends.save_to(&mut writer)?;
+        self.exception_bytes.save_to(&mut writer)?;
+        self.concatenated_label.as_bytes().save_to(&mut writer)?;
+        self.label_index.save_to(&mut writer)?;
+        Ok(())
+    }
+    fn load_from<R>(mut reader: R) -> Result<Self, Error> where
+        R: Read,
+        Self: Sized,
+    {
+        let target_count = reader.read_u64::<EndianType>()? as usize;
+        let total_length = reader.read_u64::<EndianType>()? as usize;
+        let packed_sequence = Vec::load_from(&mut reader)?;
+        let sequence_index = Vec::load_from(&mut reader)?;
+        let lowercase_starts = Vec::load_from(&mut reader)?;
+        let lowercase_ends = Vec::load_from(&mut reader)?;
+        let exception_starts = Vec::load_from(&mut reader)?;
+        let exception_ends = Vec::load_from(&mut reader)?;
+        let exception_bytes = Vec::load_from(&mut reader)?;
+        let concatenated_label = match String::from_utf8(Vec::<u8>::load_from(&mut reader)?) {
+            Ok(v) => v,
+            Err(_) => return Err(ErrorKind::InvalidData.into()),
+        };
+        let label_index = Vec::load_from(&mut reader)?;
+        Ok(Self {
+            target_count,
+            total_length,
+            packed_sequence,
+            sequence_index,
+            lowercase_starts,
+            lowercase_ends,
+            exception_starts,
+            exception_ends,
+            exception_bytes,
+            concatenated_label,
+            label_index,
+        })
+    }
+}
+//  - LoadInPlace
+impl LoadInPlace for PackedDnaStorage {
+    fn load_in_place(bytes: &SharedBytes, offset: &mut usize) -> Result<Self, Error> where
+        Self: Sized
+    {
+        load_by_copy(bytes, offset)
+    }
+}
+//  - EstimateSize
+impl EstimateSize for PackedDnaStorage {
+    fn serialized_size(&self) -> usize {
+        // target_count, total_length
+        2 * std::mem::size_of::<u64>()
+        // packed_sequence
+        + self.packed_sequence.to_be_saved_size()
+        // sequence_index
+        + self.sequence_index.to_be_saved_size()
+        // lowercase ranges
+        + self.lowercase_starts.to_be_saved_size()
+        + self.lowercase_ends.to_be_saved_size()
+        // exceptions
+        + self.exception_starts.to_be_saved_size()
+        + self.exception_ends.to_be_saved_size()
+        + self.exception_bytes.to_be_saved_size()
+        // concatenated_label
+        + self.concatenated_label.as_bytes().to_be_saved_size()
+        // label_index
+        + self.label_index.to_be_saved_size()
+    }
+}
+//  - Label Storage
+impl LabelStorage for PackedDnaStorage {
+    fn label_of_target_unchecked(&self, target_index: u32) -> String {
+        self.concatenated_label[
+            self.label_index[target_index as usize]
+            ..self.label_index[target_index as usize + 1]
+        ].to_string()
+    }
+}
+impl PackedDnaStorage {
+    pub fn get_label_safely(&self, target_index: u32) -> Option<String> {
+        if target_index as usize >= self.target_count {
+            return None
+        }
+        Some(self.label_of_target_unchecked(target_index))
+    }
+}
@@ -85,7 +85,7 @@ impl PyReference {
         Ok(self.inner.get_estimated_size_in_bytes())
     }
     #[getter(total_length)]
-    fn get_total_length(&self) -> PyResult<u32> {
+    fn get_total_length(&self) -> PyResult<u64> {
         Ok(self.inner.get_total_length())
     }
 
@@ -115,7 +115,7 @@ impl PyReference {
         }
 
         let file = File::create(file_path)?;
-        match self.inner.save_to(file) {
+        match self.inner.save_to_seekable(file) {
             Ok(_) => Ok(()),
             Err(e) => Err(PyOSError::new_err(format!(
                 "Failed to save the reference to file '{}'. Error: {}",
@@ -1 +1,2 @@
-pub mod reverse_complementary;
\ No newline at end of file
+pub mod reverse_complementary;
+pub mod translation;
@@ -0,0 +1,93 @@
+/// Genetic code to translate the codons into amino acids.
+///
+/// - The variants follow the NCBI translation tables (see `ncbi_table_id`).
+/// - Stop codons are translated into `*`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum GeneticCode {
+    /// Table 1
+    #[default]
+    Standard,
+    /// Table 2
+    VertebrateMitochondrial,
+    /// Table 3
+    YeastMitochondrial,
+    /// Table 4 (also for Mycoplasma and Spiroplasma)
+    MoldMitochondrial,
+    /// Table 5
+    InvertebrateMitochondrial,
+    /// Table 6
+    Ciliate,
+    /// Table 11 (also for Archaea and plant plastids)
+    Bacterial,
+}
+
+// Amino acids of the codons in the order of TCAG (TTT, TTC, TTA, TTG, TCT, ...)
+const STANDARD: &[u8; 64] = b"FFLLSSSSYY**CC*WLLLLPPPPHHQQRRRRIIIMTTTTNNKKSSRRVVVVAAAADDEEGGGG";
+const VERTEBRATE_MITOCHONDRIAL: &[u8; 64] = b"FFLLSSSSYY**CCWWLLLLPPPPHHQQRRRRIIMMTTTTNNKKSS**VVVVAAAADDEEGGGG";
+const YEAST_MITOCHONDRIAL: &[u8; 64] = b"FFLLSSSSYY**CCWWTTTTPPPPHHQQRRRRIIMMTTTTNNKKSSRRVVVVAAAADDEEGGGG";
+const MOLD_MITOCHONDRIAL: &[u8; 64] = b"FFLLSSSSYY**CCWWLLLLPPPPHHQQRRRRIIIMTTTTNNKKSSRRVVVVAAAADDEEGGGG";
+const INVERTEBRATE_MITOCHONDRIAL: &[u8; 64] = b"FFLLSSSSYY**CCWWLLLLPPPPHHQQRRRRIIMMTTTTNNKKSSSSVVVVAAAADDEEGGGG";
+const CILIATE: &[u8; 64] = b"FFLLSSSSYYQQCC*WLLLLPPPPHHQQRRRRIIIMTTTTNNKKSSRRVVVVAAAADDEEGGGG";
+
+impl GeneticCode {
+    /// Get the genetic code of the NCBI translation table ID.
+    pub fn from_ncbi_table_id(table_id: u32) -> Option<Self> {
+        match table_id {
+            1 => Some(Self::Standard),
+            2 => Some(Self::VertebrateMitochondrial),
+            3 => Some(Self::YeastMitochondrial),
+            4 => Some(Self::MoldMitochondrial),
+            5 => Some(Self::InvertebrateMitochondrial),
+            6 => Some(Self::Ciliate),
+            11 => Some(Self::Bacterial),
+            _ => None,
+        }
+    }
+    /// Get the ID of the NCBI translation table.
+    pub fn ncbi_table_id(&self) -> u32 {
+        match self {
+            Self::Standard => 1,
+            Self::VertebrateMitochondrial => 2,
+            Self::YeastMitochondrial => 3,
+            Self::MoldMitochondrial => 4,
+            Self::InvertebrateMitochondrial => 5,
+            Self::Ciliate => 6,
+            Self::Bacterial => 11,
+        }
+    }
+    /// Translate a codon (three bases) into an amino acid.
+    /// Bases are case-insensitive and `U` is regarded as `T`.
+    /// The codon with the other characters is translated into `X`.
+    pub fn translate_codon(&self, codon: &[u8; 3]) -> u8 {
+        let mut index = 0;
+        for base in codon {
+            let value = match base {
+                b'T' | b't' | b'U' | b'u' => 0,
+                b'C' | b'c' => 1,
+                b'A' | b'a' => 2,
+                b'G' | b'g' => 3,
+                _ => return b'X',
+            };
+            index = index * 4 + value;
+        }
+        self.amino_acids()[index]
+    }
+    fn amino_acids(&self) -> &'static [u8; 64] {
+        match self {
+            Self::Standard | Self::Bacterial => STANDARD,
+            Self::VertebrateMitochondrial => VERTEBRATE_MITOCHONDRIAL,
+            Self::YeastMitochondrial => YEAST_MITOCHONDRIAL,
+            Self::MoldMitochondrial => MOLD_MITOCHONDRIAL,
+            Self::InvertebrateMitochondrial => INVERTEBRATE_MITOCHONDRIAL,
+            Self::Ciliate => CILIATE,
+        }
+    }
+}
+
+/// Returns the amino acids translated from the first base of a DNA sequence.
+/// The remaining bases shorter than a codon are ignored.
+pub fn translate_dna_sequence(sequence: &[u8], genetic_code: GeneticCode) -> Vec<u8> {
+    sequence.chunks_exact(3).map(|codon| {
+        genetic_code.translate_codon(&[codon[0], codon[1], codon[2]])
+    }).collect()
+}
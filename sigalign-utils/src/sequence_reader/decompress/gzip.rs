@@ -1,6 +1,11 @@
 use std::io::prelude::*;
-use flate2::read::GzDecoder;
+use flate2::read::{GzDecoder, MultiGzDecoder};
 
 pub fn get_gzip_decoder<R: Read>(reader: R) -> GzDecoder<R> {
     GzDecoder::new(reader)
 }
+
+/// Decoder for the gzip file with multiple members (e.g., made by `bgzip` or concatenation).
+pub fn get_multi_gzip_decoder<R: Read>(reader: R) -> MultiGzDecoder<R> {
+    MultiGzDecoder::new(reader)
+}
@@ -1,5 +1,5 @@
 mod gzip;
-pub use gzip::get_gzip_decoder;
+pub use gzip::{get_gzip_decoder, get_multi_gzip_decoder};
 
 mod zlib;
 pub use zlib::get_zlib_decoder;
\ No newline at end of file
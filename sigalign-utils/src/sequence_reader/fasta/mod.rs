@@ -36,6 +36,16 @@ impl<'a, R: Read> FastaReader<R> {
             None
         }
     }
+    /// Same as `next`, but returns the error of reading or parsing instead of `None`.
+    pub fn try_next(&'a mut self) -> Option<Result<FastaRecord<'a>, std::io::Error>> {
+        self.reader.next().map(|result| match result {
+            Ok(seq) => Ok(FastaRecord {
+                record: seq,
+            }),
+            Err(seq_io::fasta::Error::Io(error)) => Err(error),
+            Err(error) => Err(std::io::Error::new(std::io::ErrorKind::InvalidData, error.to_string())),
+        })
+    }
 }
 impl FastaReader<File> {
     pub fn from_path<P: AsRef<Path>>(path: P) -> Result<Self, Error> {
@@ -42,6 +42,16 @@ impl<'a, R: Read> FastqReader<R> {
             None
         }
     }
+    /// Same as `next`, but returns the error of reading or parsing instead of `None`.
+    pub fn try_next(&'a mut self) -> Option<Result<FastqRecord<'a>, std::io::Error>> {
+        self.reader.next().map(|result| match result {
+            Ok(seq) => Ok(FastqRecord {
+                record: seq,
+            }),
+            Err(seq_io::fastq::Error::Io(error)) => Err(error),
+            Err(error) => Err(std::io::Error::new(std::io::ErrorKind::InvalidData, error.to_string())),
+        })
+    }
 }
 impl FastqReader<File> {
     pub fn from_path<P: AsRef<Path>>(path: P) -> Result<Self, std::io::Error> {
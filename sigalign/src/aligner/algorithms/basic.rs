@@ -1,7 +1,9 @@
 use sigalign_core::aligner::{
     AlignmentRegulator,
+    SubstitutionMatrix,
     local::LocalAligner,
     semi_global::SemiGlobalAligner,
+    global::GlobalAligner,
 };
 use crate::{
     Reference,
@@ -21,6 +23,11 @@ pub struct SemiGlobal {
     inner: SemiGlobalAligner,
 }
 
+#[derive(Clone)]
+pub struct Global {
+    inner: GlobalAligner,
+}
+
 // New
 fn get_basic_regulator(
     mismatch_penalty: u32,
@@ -35,6 +42,19 @@ fn get_basic_regulator(
     check_pattern_size(&regulator)?;
     Ok(regulator)
 }
+fn get_regulator_with_substitution_matrix(
+    substitution_matrix: SubstitutionMatrix,
+    gap_open_penalty: u32,
+    gap_extend_penalty: u32,
+    minimum_length: u32,
+    maximum_penalty_per_length: f32,
+) -> Result<AlignmentRegulator, ParamsError> {
+    let regulator = AlignmentRegulator::new_with_substitution_matrix(
+        substitution_matrix, gap_open_penalty, gap_extend_penalty, minimum_length, maximum_penalty_per_length
+    )?;
+    check_pattern_size(&regulator)?;
+    Ok(regulator)
+}
 
 impl Local {
     pub fn new(
@@ -49,6 +69,19 @@ impl Local {
             inner: LocalAligner::new(regulator),
         })
     }
+    /// The penalties of substitutions are defined by the `SubstitutionMatrix`, instead of a single mismatch penalty.
+    pub fn with_substitution_matrix(
+        substitution_matrix: SubstitutionMatrix,
+        gap_open_penalty: u32,
+        gap_extend_penalty: u32,
+        minimum_length: u32,
+        maximum_penalty_per_length: f32,
+    ) -> Result<Self, ParamsError> {
+        let regulator = get_regulator_with_substitution_matrix(substitution_matrix, gap_open_penalty, gap_extend_penalty, minimum_length, maximum_penalty_per_length)?;
+        Ok(Self {
+            inner: LocalAligner::new(regulator),
+        })
+    }
 }
 
 impl SemiGlobal {
@@ -64,21 +97,63 @@ impl SemiGlobal {
             inner: SemiGlobalAligner::new(regulator),
         })
     }
+    /// The penalties of substitutions are defined by the `SubstitutionMatrix`, instead of a single mismatch penalty.
+    pub fn with_substitution_matrix(
+        substitution_matrix: SubstitutionMatrix,
+        gap_open_penalty: u32,
+        gap_extend_penalty: u32,
+        minimum_length: u32,
+        maximum_penalty_per_length: f32,
+    ) -> Result<Self, ParamsError> {
+        let regulator = get_regulator_with_substitution_matrix(substitution_matrix, gap_open_penalty, gap_extend_penalty, minimum_length, maximum_penalty_per_length)?;
+        Ok(Self {
+            inner: SemiGlobalAligner::new(regulator),
+        })
+    }
+}
+
+impl Global {
+    pub fn new(
+        mismatch_penalty: u32,
+        gap_open_penalty: u32,
+        gap_extend_penalty: u32,
+        minimum_length: u32,
+        maximum_penalty_per_length: f32,
+    ) -> Result<Self, ParamsError> {
+        let regulator = get_basic_regulator(mismatch_penalty, gap_open_penalty, gap_extend_penalty, minimum_length, maximum_penalty_per_length)?;
+        Ok(Self {
+            inner: GlobalAligner::new(regulator),
+        })
+    }
+    /// The penalties of substitutions are defined by the `SubstitutionMatrix`, instead of a single mismatch penalty.
+    pub fn with_substitution_matrix(
+        substitution_matrix: SubstitutionMatrix,
+        gap_open_penalty: u32,
+        gap_extend_penalty: u32,
+        minimum_length: u32,
+        maximum_penalty_per_length: f32,
+    ) -> Result<Self, ParamsError> {
+        let regulator = get_regulator_with_substitution_matrix(substitution_matrix, gap_open_penalty, gap_extend_penalty, minimum_length, maximum_penalty_per_length)?;
+        Ok(Self {
+            inner: GlobalAligner::new(regulator),
+        })
+    }
 }
 
 // Implement Algorithm
 impl Algorithm for Local {
-    fn align(
+    fn align_to_sorted_targets(
         &mut self,
         query: &[u8],
         reference: &Reference,
         sequence_buffer: &mut DefaultSequenceBuffer,
+        sorted_target_indices: &[u32],
     ) -> QueryAlignment {
         self.inner.align(
             query,
             reference.as_ref(),
             sequence_buffer,
-            reference.get_full_sorted_target_indices(),
+            sorted_target_indices,
         )
     }
     fn regulator(&self) -> &AlignmentRegulator {
@@ -87,17 +162,38 @@ impl Algorithm for Local {
 }
 
 impl Algorithm for SemiGlobal {
-    fn align(
+    fn align_to_sorted_targets(
+        &mut self,
+        query: &[u8],
+        reference: &Reference,
+        sequence_buffer: &mut DefaultSequenceBuffer,
+        sorted_target_indices: &[u32],
+    ) -> QueryAlignment {
+        self.inner.align(
+            query,
+            reference.as_ref(),
+            sequence_buffer,
+            sorted_target_indices,
+        )
+    }
+    fn regulator(&self) -> &AlignmentRegulator {
+        self.inner.regulator()
+    }
+}
+
+impl Algorithm for Global {
+    fn align_to_sorted_targets(
         &mut self,
         query: &[u8],
         reference: &Reference,
         sequence_buffer: &mut DefaultSequenceBuffer,
+        sorted_target_indices: &[u32],
     ) -> QueryAlignment {
         self.inner.align(
             query,
             reference.as_ref(),
             sequence_buffer,
-            reference.get_full_sorted_target_indices(),
+            sorted_target_indices,
         )
     }
     fn regulator(&self) -> &AlignmentRegulator {
@@ -128,3 +224,14 @@ impl std::fmt::Debug for SemiGlobal {
             .finish()
     }
 }
+impl std::fmt::Debug for Global {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Global")
+            .field("mismatch_penalty", &self.regulator().get_mismatch_penalty())
+            .field("gap_open_penalty", &self.regulator().get_gap_open_penalty())
+            .field("gap_extend_penalty", &self.regulator().get_gap_extend_penalty())
+            .field("minimum_length", &self.regulator().get_minimum_length())
+            .field("maximum_penalty_per_length", &self.regulator().get_maximum_penalty_per_length())
+            .finish()
+    }
+}
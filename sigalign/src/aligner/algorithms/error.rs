@@ -16,9 +16,13 @@ impl From::<RegulatorError> for ParamsError  {
     }
 }
 
+// Minimum size of the nucleotide pattern
+//  - For the larger alphabet of the substitution matrix (e.g., protein),
+//    the smaller pattern is allowed if the number of possible patterns is not less.
 const MINIMUM_PATTERN_SIZE: u32 = 4;
+const NUCLEOTIDE_ALPHABET_SIZE: u32 = 4;
 pub fn check_pattern_size(alignment_regulator: &AlignmentRegulator) -> Result<(), ParamsError> {
-    if alignment_regulator.get_pattern_size() < MINIMUM_PATTERN_SIZE {
+    if alignment_regulator.get_pattern_size() < minimum_pattern_size(alignment_regulator) {
         Err(ParamsError::InhibitedLowEfficiency(
             "Cutoff is too low to detect the pattern.".to_string(),
         ))
@@ -26,3 +30,19 @@ pub fn check_pattern_size(alignment_regulator: &AlignmentRegulator) -> Result<()
         Ok(())
     }
 }
+fn minimum_pattern_size(alignment_regulator: &AlignmentRegulator) -> u32 {
+    let Some(alphabet_size) = alignment_regulator.get_substitution_matrix()
+        .and_then(|substitution_matrix| substitution_matrix.get_alphabet_size())
+        .filter(|alphabet_size| *alphabet_size > NUCLEOTIDE_ALPHABET_SIZE)
+    else {
+        return MINIMUM_PATTERN_SIZE
+    };
+    let minimum_pattern_count = NUCLEOTIDE_ALPHABET_SIZE.pow(MINIMUM_PATTERN_SIZE);
+    let mut pattern_size = 1;
+    let mut pattern_count = alphabet_size;
+    while pattern_count < minimum_pattern_count {
+        pattern_size += 1;
+        pattern_count *= alphabet_size;
+    }
+    pattern_size
+}
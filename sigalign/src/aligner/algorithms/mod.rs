@@ -16,6 +16,7 @@ Currently, the following algorithms are implemented:
 1. **Basic**: Basic algorithm without constraints.
    - `Local`: Performs local alignment.
    - `SemiGlobal`: Performs semi-global alignment.
+   - `Global`: Performs global alignment.
 
 2. **With Limit**: Performs alignment with a limit on the number of alignments. 
    The algorithm stops after finding a certain number of alignments that satisfy the cutoffs, 
@@ -66,9 +67,83 @@ For example:
                   ||||||
     TARGET:    ----------------
     ```
+
+## Global
+
+In the **global** mode (`Global`), both the query and the target sequence are completely consumed from end to end:
+```text
+QUERY : -------------
+        |||||||||||||
+TARGET: -------------
+```
+```rust
+use sigalign::{Aligner, ReferenceBuilder, algorithms::Global};
+
+let reference = ReferenceBuilder::new()
+    .add_target("allele_1", b"ACGTGCTAGCTAGCTAGTCGATCGATGCTAGCTAGTCGATCGTAGCTAGCTAGCTGATCGATCGTAGCTAGC")
+    .build().unwrap();
+let mut aligner = Aligner::new(Global::new(4, 6, 2, 50, 0.1).unwrap());
+let result = aligner.align(b"ACGTGCTAGCTAGCTAGTCGATCGATGCTAGCTTGTCGATCGTAGCTAGCTAGCTGATCGATCGTAGCTAGC", &reference);
+assert_eq!(result.0[0].alignments[0].penalty, 4);
+```
+- Only the alignment with the least penalty is returned for each target.
+- The alignment has to satisfy the cutoffs (MinL and MaxP) as the other modes,
+  so the targets much longer or shorter than the query are not aligned.
+- This is useful to compare the query with the short targets of similar length (e.g., amplicons and alleles).
+
+## Substitution matrix
+
+By default, all substitutions have the same mismatch penalty.
+`Local`, `SemiGlobal`, and `Global` can be defined with a `SubstitutionMatrix` to penalize each pair of bases differently
+(e.g., transitions and transversions of DNA, or BLOSUM62 for proteins):
+```rust
+use sigalign::algorithms::{Local, SubstitutionMatrix};
+
+let substitution_matrix = SubstitutionMatrix::dna_transition_transversion(2, 4).unwrap();
+let algorithm = Local::with_substitution_matrix(substitution_matrix, 6, 2, 50, 0.1).unwrap();
+```
+The pattern size is calculated from the minimum penalty of the matrix, so no alignment satisfying the cutoffs is missed.
+
+### IUPAC ambiguity codes
+
+`SubstitutionMatrix::iupac` matches the IUPAC nucleotide codes to the codes sharing any base (e.g., `R` to `A` and `G`),
+with a configurable penalty:
+```rust
+use sigalign::algorithms::{Local, SubstitutionMatrix};
+
+// The ambiguous pairs are matched (zero penalty)
+let substitution_matrix = SubstitutionMatrix::iupac(4, 0).unwrap();
+let algorithm = Local::with_substitution_matrix(substitution_matrix, 6, 2, 50, 0.1).unwrap();
+```
+- With a zero ambiguity penalty, the ambiguous pairs are reported as `AlignmentOperation::Match`.
+  The patterns are also searched with every matched code occurring in the reference,
+  so the ambiguity codes in both the query and the reference are found and no alignment is missed.
+  The query with many ambiguity codes (e.g., a run of `N`) takes longer to search.
+- With a positive ambiguity penalty, the ambiguous pairs are substitutions and no alignment is missed.
+- Do not ignore the ambiguity codes with `ReferenceBuilder::ignore_base`; the ignored bases never match.
+
+### Protein
+
+Proteins are aligned with a protein substitution matrix to the `Reference` built by `ReferenceBuilder::new_protein`:
+```rust
+use sigalign::{Aligner, ReferenceBuilder, algorithms::{Local, SubstitutionMatrix}};
+
+let reference = ReferenceBuilder::new_protein()
+    .add_target("target_1", b"MKVLAAGIVALLLAAGCSSSKEETPVTQTEAPAAAPAEAVESAPAEAAPAAE")
+    .build().unwrap();
+let algorithm = Local::with_substitution_matrix(SubstitutionMatrix::blosum62(), 11, 1, 30, 0.3).unwrap();
+let mut aligner = Aligner::new(algorithm);
+let result = aligner.align(b"MKVLAAGIVALLLAAGCSSSKEETPVTQTE", &reference);
+assert_eq!(result.0.len(), 1);
+```
+- The penalties are converted from the similarity scores (see `SubstitutionMatrix::from_similarity_scores`).
+- Since the protein alphabet is larger, shorter patterns than the nucleotides are allowed for the matrices from the similarity scores.
+- The nucleotide queries can be searched against the protein `Reference` by `Aligner::align_translated`.
+  The query is translated in six frames by the `GeneticCode` set with `Aligner::set_genetic_code`.
  */
 
 use sigalign_core::aligner::AlignmentRegulator;
+pub use sigalign_core::aligner::{SubstitutionMatrix, SubstitutionMatrixError};
 use super::{
     Reference, DefaultSequenceBuffer,
     QueryAlignment,
@@ -81,7 +156,7 @@ use error::check_pattern_size;
 mod basic;
 mod with_limit;
 mod with_chunk;
-pub use basic::{Local, SemiGlobal};
+pub use basic::{Local, SemiGlobal, Global};
 pub use with_limit::{LocalWithLimit, SemiGlobalWithLimit};
 pub use with_chunk::{LocalWithChunk, SemiGlobalWithChunk};
 
@@ -93,7 +168,27 @@ pub trait Algorithm: std::fmt::Debug + Clone {
         query: &[u8],
         reference: &Reference,
         sequence_buffer: &mut DefaultSequenceBuffer,
+    ) -> QueryAlignment {
+        self.align_to_sorted_targets(
+            query,
+            reference,
+            sequence_buffer,
+            reference.get_full_sorted_target_indices(),
+        )
+    }
+    // Low-level alignment method for the part of targets
+    //  - `sorted_target_indices` must be sorted, deduplicated, and in range of the reference.
+    fn align_to_sorted_targets(
+        &mut self,
+        query: &[u8],
+        reference: &Reference,
+        sequence_buffer: &mut DefaultSequenceBuffer,
+        sorted_target_indices: &[u32],
     ) -> QueryAlignment;
     // Can access the regulator
     fn regulator(&self) -> &AlignmentRegulator;
+    // Maximum number of alignments per query, if limited
+    fn limit(&self) -> Option<u32> {
+        None
+    }
 }
@@ -91,11 +91,12 @@ impl SemiGlobalWithChunk {
 
 // Implement Algorithm
 impl Algorithm for LocalWithChunk {
-    fn align(
+    fn align_to_sorted_targets(
         &mut self,
         query: &[u8],
         reference: &Reference,
         sequence_buffer: &mut DefaultSequenceBuffer,
+        sorted_target_indices: &[u32],
     ) -> QueryAlignment {
         let mut results = Vec::new();
         
@@ -106,7 +107,7 @@ impl Algorithm for LocalWithChunk {
                 slice,
                 reference.as_ref(),
                 sequence_buffer,
-                reference.get_full_sorted_target_indices(),
+                sorted_target_indices,
             );
             adjust_positions(&mut alignment, start);
             results.append(&mut alignment.0);
@@ -122,11 +123,12 @@ impl Algorithm for LocalWithChunk {
 }
 
 impl Algorithm for SemiGlobalWithChunk {
-    fn align(
+    fn align_to_sorted_targets(
         &mut self,
         query: &[u8],
         reference: &Reference,
         sequence_buffer: &mut DefaultSequenceBuffer,
+        sorted_target_indices: &[u32],
     ) -> QueryAlignment {
         let mut results = Vec::new();
         
@@ -137,7 +139,7 @@ impl Algorithm for SemiGlobalWithChunk {
                 slice,
                 reference.as_ref(),
                 sequence_buffer,
-                reference.get_full_sorted_target_indices(),
+                sorted_target_indices,
             );
             adjust_positions(&mut alignment, start);
             results.append(&mut alignment.0);
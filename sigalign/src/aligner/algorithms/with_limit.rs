@@ -70,41 +70,49 @@ impl SemiGlobalWithLimit {
 
 // Implement Algorithm
 impl Algorithm for LocalWithLimit {
-    fn align(
+    fn align_to_sorted_targets(
         &mut self,
         query: &[u8],
         reference: &Reference,
         sequence_buffer: &mut DefaultSequenceBuffer,
+        sorted_target_indices: &[u32],
     ) -> QueryAlignment {
         self.inner.align(
             query,
             reference.as_ref(),
             sequence_buffer,
-            reference.get_full_sorted_target_indices(),
+            sorted_target_indices,
         )
     }
     fn regulator(&self) -> &AlignmentRegulator {
         self.inner.regulator()
     }
+    fn limit(&self) -> Option<u32> {
+        Some(self.inner.limit())
+    }
 }
 
 impl Algorithm for SemiGlobalWithLimit {
-    fn align(
+    fn align_to_sorted_targets(
         &mut self,
         query: &[u8],
         reference: &Reference,
         sequence_buffer: &mut DefaultSequenceBuffer,
+        sorted_target_indices: &[u32],
     ) -> QueryAlignment {
         self.inner.align(
             query,
             reference.as_ref(),
             sequence_buffer,
-            reference.get_full_sorted_target_indices(),
+            sorted_target_indices,
         )
     }
     fn regulator(&self) -> &AlignmentRegulator {
         self.inner.regulator()
     }
+    fn limit(&self) -> Option<u32> {
+        Some(self.inner.limit())
+    }
 }
 
 // Debug
@@ -2,6 +2,7 @@ use std::fmt::Debug;
 
 use super::{
     Aligner,
+    GeneticCode,
     algorithms::Algorithm,
 };
 
@@ -10,6 +11,7 @@ impl<A: Algorithm> Debug for Aligner<A> {
         f.debug_struct("Aligner")
             .field("algorithm", &self.algorithm)
             .field("sequence_buffer", &"InMemorySequenceBuffer")
+            .field("genetic_code", &self.genetic_code)
             .finish()
     }
 }
@@ -39,4 +41,8 @@ impl<A: Algorithm> Aligner<A> {
     pub fn get_pattern_size(&self) -> u32 {
         self.algorithm.regulator().get_pattern_size()
     }
+    /// Get genetic code to translate the query
+    pub fn get_genetic_code(&self) -> GeneticCode {
+        self.genetic_code
+    }
 }
@@ -1,21 +1,38 @@
 use crate::{
-    results::QueryAlignment,
+    results::{QueryAlignment, StrandedQueryAlignment, TranslatedQueryAlignment},
     reference::{
         Reference,
         DefaultSequenceBuffer,
+        TargetSubset,
     }
 };
 
 pub mod algorithms;
 use algorithms::Algorithm;
 
+mod strand;
+pub use strand::Strand;
+use strand::align_by_strand;
+
+mod translated;
+pub use translated::GeneticCode;
+use translated::align_by_frame;
+
 mod debug;
 
+mod parallel;
+pub use parallel::ParallelAligner;
+
+mod reader;
+
+mod sharded;
+
 /// An alignment executor.
 #[derive(Clone)]
 pub struct Aligner<A: Algorithm> {
     algorithm: A,
     sequence_buffer: DefaultSequenceBuffer,
+    genetic_code: GeneticCode,
 }
 
 impl<A: Algorithm> Aligner<A> {
@@ -23,10 +40,92 @@ impl<A: Algorithm> Aligner<A> {
     pub fn new(algorithm: A) -> Self {
         Self::from(algorithm)
     }
+    /// Set the genetic code to translate the query in `align_translated` methods.
+    pub fn set_genetic_code(&mut self, genetic_code: GeneticCode) {
+        self.genetic_code = genetic_code;
+    }
     /// Align a query to a reference.
+    ///  - Only the query as is (forward strand) is aligned. Use `align_stranded` for the reverse complement.
     pub fn align(&mut self, query: &[u8], reference: &Reference) -> QueryAlignment {
         self.algorithm.align(query, reference, &mut self.sequence_buffer)
     }
+    /// Align a query to the subset of targets in reference.
+    ///  - `target_subset` must be made from the same `reference`.
+    pub fn align_to_subset(
+        &mut self,
+        query: &[u8],
+        reference: &Reference,
+        target_subset: &TargetSubset,
+    ) -> QueryAlignment {
+        self.algorithm.align_to_sorted_targets(
+            query,
+            reference,
+            &mut self.sequence_buffer,
+            target_subset.get_sorted_target_indices(),
+        )
+    }
+    /// Align a query to a reference for the strands defined by `strand`.
+    pub fn align_stranded(
+        &mut self,
+        query: &[u8],
+        reference: &Reference,
+        strand: Strand,
+    ) -> StrandedQueryAlignment {
+        let Self { algorithm, sequence_buffer, .. } = self;
+        align_by_strand(strand, query, |query| {
+            algorithm.align(query, reference, sequence_buffer)
+        })
+    }
+    /// Align a query to the subset of targets for the strands defined by `strand`.
+    ///  - `target_subset` must be made from the same `reference`.
+    pub fn align_stranded_to_subset(
+        &mut self,
+        query: &[u8],
+        reference: &Reference,
+        target_subset: &TargetSubset,
+        strand: Strand,
+    ) -> StrandedQueryAlignment {
+        let Self { algorithm, sequence_buffer, .. } = self;
+        align_by_strand(strand, query, |query| {
+            algorithm.align_to_sorted_targets(
+                query,
+                reference,
+                sequence_buffer,
+                target_subset.get_sorted_target_indices(),
+            )
+        })
+    }
+    /// Align a nucleotide query translated in six frames to a protein reference.
+    ///  - All six frames (both strands) are aligned.
+    ///  - The query is translated by the `GeneticCode` (`GeneticCode::Standard` by default).
+    pub fn align_translated(
+        &mut self,
+        query: &[u8],
+        reference: &Reference,
+    ) -> TranslatedQueryAlignment {
+        let Self { algorithm, sequence_buffer, genetic_code, .. } = self;
+        align_by_frame(*genetic_code, query, |query| {
+            algorithm.align(query, reference, sequence_buffer)
+        })
+    }
+    /// Align a nucleotide query translated in six frames to the subset of targets.
+    ///  - `target_subset` must be made from the same `reference`.
+    pub fn align_translated_to_subset(
+        &mut self,
+        query: &[u8],
+        reference: &Reference,
+        target_subset: &TargetSubset,
+    ) -> TranslatedQueryAlignment {
+        let Self { algorithm, sequence_buffer, genetic_code, .. } = self;
+        align_by_frame(*genetic_code, query, |query| {
+            algorithm.align_to_sorted_targets(
+                query,
+                reference,
+                sequence_buffer,
+                target_subset.get_sorted_target_indices(),
+            )
+        })
+    }
 }
 
 impl<A: Algorithm> From<A> for Aligner<A> {
@@ -34,6 +133,7 @@ impl<A: Algorithm> From<A> for Aligner<A> {
         Self {
             algorithm,
             sequence_buffer: Reference::get_sequence_buffer(),
+            genetic_code: GeneticCode::default(),
         }
     }
 }
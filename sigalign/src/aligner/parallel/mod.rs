@@ -0,0 +1,203 @@
+use std::collections::VecDeque;
+use std::num::NonZeroUsize;
+use std::thread;
+
+use crate::{
+    results::{QueryAlignment, StrandedQueryAlignment, TranslatedQueryAlignment},
+    reference::Reference,
+};
+use super::{
+    Aligner,
+    Strand,
+    algorithms::Algorithm,
+};
+
+const DEFAULT_CHUNK_SIZE: usize = 64;
+
+/// An alignment executor running on multiple threads.
+///
+/// - Each thread has its own clone of `Aligner`, and all threads share one `Reference`.
+/// - Queries are taken from an iterator by `chunk_size` for each thread.
+/// - Results are yielded in the order of input queries.
+///
+/// ```rust
+/// use sigalign::{Aligner, ParallelAligner, ReferenceBuilder, algorithms::Local};
+///
+/// let reference = ReferenceBuilder::new()
+///     .add_target("target", b"ACACAGATCGCAAACTCACAATTGTATTTCTTTGCCACCTGGGCATATACTTTTTGCGCCCCCTCATTTA")
+///     .build().unwrap();
+/// let aligner = Aligner::new(Local::new(4, 6, 2, 50, 0.2).unwrap());
+/// let mut parallel_aligner = ParallelAligner::new(aligner);
+/// parallel_aligner.set_num_threads(2);
+///
+/// let queries = vec![b"CAAACTCACAATTGTATTTCTTTGCCAGCTGGGCATATACTTTTTCCGCCCCCTCATTTA".to_vec(); 10];
+/// for (_query, result) in parallel_aligner.align_batch(queries, &reference) {
+///     println!("{:?}", result);
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct ParallelAligner<A: Algorithm> {
+    aligners: Vec<Aligner<A>>,
+    chunk_size: usize,
+}
+
+impl<A: Algorithm + Send> ParallelAligner<A> {
+    /// Create a new parallel aligner.
+    /// The number of threads is set to the available parallelism.
+    pub fn new(aligner: Aligner<A>) -> Self {
+        let num_threads = thread::available_parallelism().map(NonZeroUsize::get).unwrap_or(1);
+        Self {
+            aligners: vec![aligner; num_threads],
+            chunk_size: DEFAULT_CHUNK_SIZE,
+        }
+    }
+    /// Set the number of threads. Zero is regarded as one.
+    pub fn set_num_threads(&mut self, num_threads: usize) {
+        self.aligners.resize(num_threads.max(1), self.aligners[0].clone());
+    }
+    /// Set the number of queries given to a thread at once. Zero is regarded as one.
+    pub fn set_chunk_size(&mut self, chunk_size: usize) {
+        self.chunk_size = chunk_size.max(1);
+    }
+    /// Get the number of threads.
+    pub fn get_num_threads(&self) -> usize {
+        self.aligners.len()
+    }
+    /// Get the number of queries given to a thread at once.
+    pub fn get_chunk_size(&self) -> usize {
+        self.chunk_size
+    }
+    /// Align queries to a reference.
+    ///  - Each query is yielded with its result in input order.
+    ///  - Only the forward strand is aligned, as `Aligner::align`.
+    pub fn align_batch<'a, I, T>(
+        &'a mut self,
+        queries: I,
+        reference: &'a Reference,
+    ) -> impl Iterator<Item = (T, QueryAlignment)> + 'a where
+        I: IntoIterator<Item = T>,
+        I::IntoIter: 'a,
+        T: AsRef<[u8]> + Send + 'a,
+    {
+        self.batch_iterator(queries.into_iter(), reference, |aligner, query, reference| {
+            aligner.align(query, reference)
+        })
+    }
+    /// Align queries to a reference for the strands defined by `strand`, as `Aligner::align_stranded`.
+    ///  - Each query is yielded with its result in input order.
+    pub fn align_stranded_batch<'a, I, T>(
+        &'a mut self,
+        queries: I,
+        reference: &'a Reference,
+        strand: Strand,
+    ) -> impl Iterator<Item = (T, StrandedQueryAlignment)> + 'a where
+        I: IntoIterator<Item = T>,
+        I::IntoIter: 'a,
+        T: AsRef<[u8]> + Send + 'a,
+    {
+        self.batch_iterator(queries.into_iter(), reference, move |aligner, query, reference| {
+            aligner.align_stranded(query, reference, strand)
+        })
+    }
+    /// Align nucleotide queries translated in six frames to a protein reference by `GeneticCode` of `Aligner`.
+    ///  - Each query is yielded with its result in input order.
+    pub fn align_translated_batch<'a, I, T>(
+        &'a mut self,
+        queries: I,
+        reference: &'a Reference,
+    ) -> impl Iterator<Item = (T, TranslatedQueryAlignment)> + 'a where
+        I: IntoIterator<Item = T>,
+        I::IntoIter: 'a,
+        T: AsRef<[u8]> + Send + 'a,
+    {
+        self.batch_iterator(queries.into_iter(), reference, |aligner, query, reference| {
+            aligner.align_translated(query, reference)
+        })
+    }
+
+    fn batch_iterator<'a, I, T, R, F>(
+        &'a mut self,
+        queries: I,
+        reference: &'a Reference,
+        align: F,
+    ) -> BatchIterator<'a, A, I, T, R, F> where
+        I: Iterator<Item = T>,
+        F: Fn(&mut Aligner<A>, &[u8], &Reference) -> R + Copy + Send,
+    {
+        BatchIterator {
+            aligners: &mut self.aligners,
+            chunk_size: self.chunk_size,
+            reference,
+            queries,
+            align,
+            results: VecDeque::new(),
+        }
+    }
+}
+
+impl<A: Algorithm + Send> From<Aligner<A>> for ParallelAligner<A> {
+    fn from(aligner: Aligner<A>) -> Self {
+        Self::new(aligner)
+    }
+}
+
+struct BatchIterator<'a, A: Algorithm, I, T, R, F> {
+    aligners: &'a mut [Aligner<A>],
+    chunk_size: usize,
+    reference: &'a Reference,
+    queries: I,
+    align: F,
+    results: VecDeque<(T, R)>,
+}
+
+impl<'a, A, I, T, R, F> Iterator for BatchIterator<'a, A, I, T, R, F> where
+    A: Algorithm + Send,
+    I: Iterator<Item = T>,
+    T: AsRef<[u8]> + Send,
+    R: Send,
+    F: Fn(&mut Aligner<A>, &[u8], &Reference) -> R + Copy + Send,
+{
+    type Item = (T, R);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.results.is_empty() {
+            self.align_next_batch();
+        }
+        self.results.pop_front()
+    }
+}
+
+impl<'a, A, I, T, R, F> BatchIterator<'a, A, I, T, R, F> where
+    A: Algorithm + Send,
+    I: Iterator<Item = T>,
+    T: AsRef<[u8]> + Send,
+    R: Send,
+    F: Fn(&mut Aligner<A>, &[u8], &Reference) -> R + Copy + Send,
+{
+    fn align_next_batch(&mut self) {
+        let mut chunks: Vec<Vec<T>> = Vec::with_capacity(self.aligners.len());
+        for _ in 0..self.aligners.len() {
+            let chunk: Vec<T> = self.queries.by_ref().take(self.chunk_size).collect();
+            if chunk.is_empty() {
+                break;
+            }
+            chunks.push(chunk);
+        }
+
+        let Self { aligners, reference, align, results, .. } = self;
+        let (reference, align) = (*reference, *align);
+        thread::scope(|scope| {
+            let handles: Vec<_> = aligners.iter_mut().zip(chunks).map(|(aligner, chunk)| {
+                scope.spawn(move || {
+                    chunk.into_iter().map(|query| {
+                        let result = align(aligner, query.as_ref(), reference);
+                        (query, result)
+                    }).collect::<Vec<_>>()
+                })
+            }).collect();
+            handles.into_iter().for_each(|handle| {
+                results.extend(handle.join().unwrap());
+            });
+        });
+    }
+}
@@ -0,0 +1,231 @@
+use std::{
+    fs::File,
+    io::{BufRead, BufReader, Error, ErrorKind, Read},
+    path::Path,
+};
+
+use sigalign_utils::sequence_reader::{
+    SeqRecord as _,
+    IdRecord as _,
+    fasta::FastaReader,
+    fastq::FastqReader,
+    decompress::get_multi_gzip_decoder,
+};
+
+use crate::{
+    results::{LabeledQueryAlignment, LabeledStrandedQueryAlignment},
+    reference::Reference,
+};
+use super::{
+    Aligner,
+    Strand,
+    algorithms::Algorithm,
+};
+
+const GZIP_MAGIC_NUMBER: [u8; 2] = [0x1f, 0x8b];
+
+type DynReader<'a> = BufReader<Box<dyn Read + 'a>>;
+
+enum RecordReader<'a> {
+    Fasta(FastaReader<DynReader<'a>>),
+    Fastq(FastqReader<DynReader<'a>>),
+}
+
+struct RecordAlignments<'a, A: Algorithm, F> {
+    aligner: &'a mut Aligner<A>,
+    reference: &'a Reference,
+    record_reader: RecordReader<'a>,
+    align: F,
+    is_finished: bool,
+    id_buffer: Vec<u8>,
+    query_buffer: Vec<u8>,
+}
+
+impl<A: Algorithm> Aligner<A> {
+    /// Align the queries in FASTA file to a reference.
+    ///  - Returns a lazy iterator of `(record_id, LabeledQueryAlignment)`.
+    ///    The error of reading or parsing the record is returned as the last item.
+    ///  - Gzip compressed file is decompressed transparently.
+    ///  - Only the forward strand is aligned, as `align`.
+    pub fn align_fasta<'a, P: AsRef<Path>>(
+        &'a mut self,
+        file_path: P,
+        reference: &'a Reference,
+    ) -> Result<impl Iterator<Item = Result<(String, LabeledQueryAlignment), Error>> + 'a, Error> {
+        let record_reader = fasta_record_reader(file_path)?;
+        Ok(RecordAlignments::new(self, reference, record_reader, align_labeled))
+    }
+    /// Align the queries in FASTQ file to a reference.
+    ///  - Returns a lazy iterator of `(record_id, LabeledQueryAlignment)`, as `align_fasta`.
+    ///  - Gzip compressed file is decompressed transparently.
+    ///  - Only the forward strand is aligned, as `align`.
+    pub fn align_fastq<'a, P: AsRef<Path>>(
+        &'a mut self,
+        file_path: P,
+        reference: &'a Reference,
+    ) -> Result<impl Iterator<Item = Result<(String, LabeledQueryAlignment), Error>> + 'a, Error> {
+        let record_reader = fastq_record_reader(file_path)?;
+        Ok(RecordAlignments::new(self, reference, record_reader, align_labeled))
+    }
+    /// Align the queries from a reader of FASTA or FASTQ to a reference.
+    ///  - The format is detected from the first character (`>` for FASTA, `@` for FASTQ).
+    ///  - Returns a lazy iterator of `(record_id, LabeledQueryAlignment)`, as `align_fasta`.
+    ///  - Gzip compressed stream is decompressed transparently.
+    ///  - Only the forward strand is aligned, as `align`.
+    pub fn align_reader<'a, R: Read + 'a>(
+        &'a mut self,
+        reader: R,
+        reference: &'a Reference,
+    ) -> Result<impl Iterator<Item = Result<(String, LabeledQueryAlignment), Error>> + 'a, Error> {
+        let record_reader = detected_record_reader(reader)?;
+        Ok(RecordAlignments::new(self, reference, record_reader, align_labeled))
+    }
+    /// Align the queries in FASTA file to a reference for the strands, as `align_stranded`.
+    ///  - Returns a lazy iterator of `(record_id, LabeledStrandedQueryAlignment)`, as `align_fasta`.
+    pub fn align_fasta_stranded<'a, P: AsRef<Path>>(
+        &'a mut self,
+        file_path: P,
+        reference: &'a Reference,
+        strand: Strand,
+    ) -> Result<impl Iterator<Item = Result<(String, LabeledStrandedQueryAlignment), Error>> + 'a, Error> {
+        let record_reader = fasta_record_reader(file_path)?;
+        Ok(RecordAlignments::new(self, reference, record_reader, move |aligner, query, reference| {
+            align_stranded_labeled(aligner, query, reference, strand)
+        }))
+    }
+    /// Align the queries in FASTQ file to a reference for the strands, as `align_stranded`.
+    ///  - Returns a lazy iterator of `(record_id, LabeledStrandedQueryAlignment)`, as `align_fasta`.
+    pub fn align_fastq_stranded<'a, P: AsRef<Path>>(
+        &'a mut self,
+        file_path: P,
+        reference: &'a Reference,
+        strand: Strand,
+    ) -> Result<impl Iterator<Item = Result<(String, LabeledStrandedQueryAlignment), Error>> + 'a, Error> {
+        let record_reader = fastq_record_reader(file_path)?;
+        Ok(RecordAlignments::new(self, reference, record_reader, move |aligner, query, reference| {
+            align_stranded_labeled(aligner, query, reference, strand)
+        }))
+    }
+    /// Align the queries from a reader of FASTA or FASTQ to a reference for the strands, as `align_stranded`.
+    ///  - Returns a lazy iterator of `(record_id, LabeledStrandedQueryAlignment)`, as `align_reader`.
+    pub fn align_reader_stranded<'a, R: Read + 'a>(
+        &'a mut self,
+        reader: R,
+        reference: &'a Reference,
+        strand: Strand,
+    ) -> Result<impl Iterator<Item = Result<(String, LabeledStrandedQueryAlignment), Error>> + 'a, Error> {
+        let record_reader = detected_record_reader(reader)?;
+        Ok(RecordAlignments::new(self, reference, record_reader, move |aligner, query, reference| {
+            align_stranded_labeled(aligner, query, reference, strand)
+        }))
+    }
+}
+
+fn align_labeled<A: Algorithm>(aligner: &mut Aligner<A>, query: &[u8], reference: &Reference) -> LabeledQueryAlignment {
+    reference.label_query_alignment(aligner.align(query, reference))
+}
+fn align_stranded_labeled<A: Algorithm>(aligner: &mut Aligner<A>, query: &[u8], reference: &Reference, strand: Strand) -> LabeledStrandedQueryAlignment {
+    reference.label_stranded_query_alignment(aligner.align_stranded(query, reference, strand))
+}
+
+fn fasta_record_reader<'a, P: AsRef<Path>>(file_path: P) -> Result<RecordReader<'a>, Error> {
+    let reader = decompressed_reader(File::open(file_path)?)?;
+    Ok(RecordReader::Fasta(FastaReader::new(reader)))
+}
+fn fastq_record_reader<'a, P: AsRef<Path>>(file_path: P) -> Result<RecordReader<'a>, Error> {
+    let reader = decompressed_reader(File::open(file_path)?)?;
+    Ok(RecordReader::Fastq(FastqReader::new(reader)))
+}
+fn detected_record_reader<'a, R: Read + 'a>(reader: R) -> Result<RecordReader<'a>, Error> {
+    let mut reader = decompressed_reader(reader)?;
+    match reader.fill_buf()?.first() {
+        Some(b'>') | None => Ok(RecordReader::Fasta(FastaReader::new(reader))),
+        Some(b'@') => Ok(RecordReader::Fastq(FastqReader::new(reader))),
+        Some(_) => Err(Error::new(
+            ErrorKind::InvalidData,
+            "The reader is neither FASTA nor FASTQ.",
+        )),
+    }
+}
+
+/// Wrap the reader with the gzip decoder if the stream starts with the gzip magic number.
+fn decompressed_reader<'a, R: Read + 'a>(reader: R) -> Result<DynReader<'a>, Error> {
+    let mut reader = BufReader::new(reader);
+    let is_gzip = reader.fill_buf()?.starts_with(&GZIP_MAGIC_NUMBER);
+    let reader: Box<dyn Read + 'a> = if is_gzip {
+        Box::new(get_multi_gzip_decoder(reader))
+    } else {
+        Box::new(reader)
+    };
+    Ok(BufReader::new(reader))
+}
+
+impl<'a, A, T, F> RecordAlignments<'a, A, F> where
+    A: Algorithm,
+    F: FnMut(&mut Aligner<A>, &[u8], &Reference) -> T,
+{
+    fn new(
+        aligner: &'a mut Aligner<A>,
+        reference: &'a Reference,
+        record_reader: RecordReader<'a>,
+        align: F,
+    ) -> Self {
+        Self {
+            aligner,
+            reference,
+            record_reader,
+            align,
+            is_finished: false,
+            id_buffer: Vec::new(),
+            query_buffer: Vec::new(),
+        }
+    }
+    // Read the next record into the buffers. False at the end of records.
+    fn read_next_record(&mut self) -> Result<bool, Error> {
+        self.id_buffer.clear();
+        self.query_buffer.clear();
+        match &mut self.record_reader {
+            RecordReader::Fasta(reader) => {
+                let Some(record) = reader.try_next() else { return Ok(false) };
+                let mut record = record?;
+                record.extend_id_buf(&mut self.id_buffer);
+                record.extend_seq_buf(&mut self.query_buffer);
+            },
+            RecordReader::Fastq(reader) => {
+                let Some(record) = reader.try_next() else { return Ok(false) };
+                let mut record = record?;
+                record.extend_id_buf(&mut self.id_buffer);
+                record.extend_seq_buf(&mut self.query_buffer);
+            },
+        }
+        Ok(true)
+    }
+}
+
+impl<'a, A, T, F> Iterator for RecordAlignments<'a, A, F> where
+    A: Algorithm,
+    F: FnMut(&mut Aligner<A>, &[u8], &Reference) -> T,
+{
+    type Item = Result<(String, T), Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.is_finished {
+            return None
+        }
+        match self.read_next_record() {
+            Ok(true) => {},
+            Ok(false) => {
+                self.is_finished = true;
+                return None
+            },
+            Err(error) => {
+                // Stop after the error, not to parse the broken records.
+                self.is_finished = true;
+                return Some(Err(error))
+            },
+        }
+        let alignment = (self.align)(self.aligner, &self.query_buffer, self.reference);
+        let record_id = String::from_utf8_lossy(&self.id_buffer).into_owned();
+        Some(Ok((record_id, alignment)))
+    }
+}
@@ -0,0 +1,84 @@
+use crate::{
+    results::{LabeledQueryAlignment, LabeledTargetAlignment},
+    reference::{ShardedReference, ShardedReferenceError},
+};
+use super::{
+    Aligner,
+    algorithms::Algorithm,
+};
+
+impl<A: Algorithm> Aligner<A> {
+    /// Align a query to all shards of `ShardedReference`, and merge the results.
+    ///  - Target indices of the result are global (the index of the record in the original FASTA).
+    ///  - Only the forward strand is aligned, as `align`.
+    ///  - The limit of `*WithLimit` algorithms is applied to the merged result, not to each shard.
+    ///  - Error can occur only when loading a shard (`ShardLoading::Lazy`).
+    ///  - With `ShardLoading::Lazy`, every shard is loaded again for each call.
+    ///    Use `align_sharded_batch` to align multiple queries with one load per shard.
+    pub fn align_sharded(
+        &mut self,
+        query: &[u8],
+        sharded_reference: &ShardedReference,
+    ) -> Result<LabeledQueryAlignment, ShardedReferenceError> {
+        let mut merged = LabeledQueryAlignment(Vec::new());
+        sharded_reference.for_each_shard(|reference, target_offset| {
+            let result = self.align(query, reference);
+            merge_shard_result(&mut merged, reference.label_query_alignment(result), target_offset);
+        })?;
+        if let Some(limit) = self.algorithm.limit() {
+            truncate_to_limit(&mut merged, limit);
+        }
+        Ok(merged)
+    }
+    /// Align queries to all shards of `ShardedReference`, and merge the results.
+    ///  - Each shard is loaded only once for all queries.
+    ///  - Results are in the order of input queries.
+    ///  - As `align_sharded`, the limit of `*WithLimit` algorithms is applied to each merged result.
+    pub fn align_sharded_batch<T: AsRef<[u8]>>(
+        &mut self,
+        queries: &[T],
+        sharded_reference: &ShardedReference,
+    ) -> Result<Vec<LabeledQueryAlignment>, ShardedReferenceError> {
+        let mut merged: Vec<LabeledQueryAlignment> = queries.iter().map(|_| {
+            LabeledQueryAlignment(Vec::new())
+        }).collect();
+        sharded_reference.for_each_shard(|reference, target_offset| {
+            queries.iter().zip(merged.iter_mut()).for_each(|(query, merged)| {
+                let result = self.align(query.as_ref(), reference);
+                merge_shard_result(merged, reference.label_query_alignment(result), target_offset);
+            });
+        })?;
+        if let Some(limit) = self.algorithm.limit() {
+            merged.iter_mut().for_each(|merged| truncate_to_limit(merged, limit));
+        }
+        Ok(merged)
+    }
+}
+
+#[inline]
+fn merge_shard_result(
+    merged: &mut LabeledQueryAlignment,
+    shard_result: LabeledQueryAlignment,
+    target_offset: u32,
+) {
+    merged.0.extend(shard_result.0.into_iter().map(|target_alignment| {
+        LabeledTargetAlignment {
+            index: target_alignment.index + target_offset,
+            ..target_alignment
+        }
+    }));
+}
+
+// Keep the first `limit` alignments in the order of shards, as a single reference does in the order of targets.
+#[inline]
+fn truncate_to_limit(
+    merged: &mut LabeledQueryAlignment,
+    limit: u32,
+) {
+    let mut remaining = limit as usize;
+    merged.0.retain_mut(|target_alignment| {
+        target_alignment.alignments.truncate(remaining);
+        remaining -= target_alignment.alignments.len();
+        !target_alignment.alignments.is_empty()
+    });
+}
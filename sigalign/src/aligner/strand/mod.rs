@@ -0,0 +1,74 @@
+use serde::{Deserialize, Serialize};
+use sigalign_utils::sequence_manipulation::reverse_complementary::reverse_complement_of_dna_sequence;
+
+use crate::results::{
+    QueryAlignment,
+    StrandedQueryAlignment,
+};
+
+/// Strand of the query to be aligned.
+///
+/// - `Forward`: Only the query as is (default).
+/// - `Reverse`: Only the reverse complement of the query.
+/// - `Both`: Both of the query and its reverse complement.
+///
+/// The reverse complement is made for the DNA sequence (A, C, G, T).
+///
+/// As the orientation of an alignment (e.g., `StrandedAlignment::strand`),
+/// only `Forward` and `Reverse` are used, and `Both` is regarded as `Forward`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Strand {
+    #[default]
+    Forward,
+    Reverse,
+    Both,
+}
+
+impl Strand {
+    pub fn includes_forward(&self) -> bool {
+        matches!(self, Self::Forward | Self::Both)
+    }
+    pub fn includes_reverse(&self) -> bool {
+        matches!(self, Self::Reverse | Self::Both)
+    }
+    /// True if the alignment of this orientation is of the reverse complement of the query.
+    pub fn is_reverse_orientation(&self) -> bool {
+        matches!(self, Self::Reverse)
+    }
+}
+
+pub(super) fn align_by_strand<F>(
+    strand: Strand,
+    query: &[u8],
+    mut align: F,
+) -> StrandedQueryAlignment where
+    F: FnMut(&[u8]) -> QueryAlignment,
+{
+    let forward = if strand.includes_forward() {
+        align(query)
+    } else {
+        QueryAlignment(Vec::new())
+    };
+    let reverse = if strand.includes_reverse() {
+        let reverse_complement = reverse_complement_of_dna_sequence(query);
+        let mut reverse = align(&reverse_complement);
+        convert_to_forward_query_positions(&mut reverse, query.len() as u32);
+        reverse
+    } else {
+        QueryAlignment(Vec::new())
+    };
+
+    StrandedQueryAlignment { forward, reverse }
+}
+
+fn convert_to_forward_query_positions(
+    alignment: &mut QueryAlignment,
+    query_length: u32,
+) {
+    alignment.0.iter_mut().for_each(|tgt_aln| {
+        tgt_aln.alignments.iter_mut().for_each(|aln| {
+            let (start, end) = aln.position.query;
+            aln.position.query = (query_length - end, query_length - start);
+        })
+    });
+}
@@ -0,0 +1,82 @@
+use sigalign_utils::sequence_manipulation::{
+    reverse_complementary::reverse_complement_of_dna_sequence,
+    translation::translate_dna_sequence,
+};
+pub use sigalign_utils::sequence_manipulation::translation::GeneticCode;
+
+use crate::results::{
+    QueryAlignment,
+    TranslatedQueryAlignment,
+    TranslatedTargetAlignment,
+    TranslatedAlignment,
+};
+
+const CODON_SIZE: u32 = 3;
+
+pub(super) fn align_by_frame<F>(
+    genetic_code: GeneticCode,
+    query: &[u8],
+    mut align: F,
+) -> TranslatedQueryAlignment where
+    F: FnMut(&[u8]) -> QueryAlignment,
+{
+    let query_length = query.len() as u32;
+    // The reverse complement is only for the uppercase bases
+    let query = query.to_ascii_uppercase();
+    let reverse_complement = reverse_complement_of_dna_sequence(&query);
+
+    let mut target_alignments: Vec<TranslatedTargetAlignment> = Vec::new();
+    for (is_forward, nucleotides) in [(true, &query[..]), (false, &reverse_complement[..])] {
+        for offset in 0..CODON_SIZE {
+            let translated_query = translate_dna_sequence(
+                nucleotides.get(offset as usize..).unwrap_or_default(),
+                genetic_code,
+            );
+            if translated_query.is_empty() {
+                continue
+            }
+            let frame = if is_forward { offset as i8 + 1 } else { -(offset as i8 + 1) };
+
+            let mut query_alignment = align(&translated_query);
+            convert_to_nucleotide_query_positions(&mut query_alignment, is_forward, offset, query_length);
+            query_alignment.0.into_iter().for_each(|target_alignment| {
+                let alignments = target_alignment.alignments.into_iter().map(|alignment| {
+                    TranslatedAlignment { frame, alignment }
+                });
+                match target_alignments.iter_mut().find(|x| x.index == target_alignment.index) {
+                    Some(translated_target_alignment) => {
+                        translated_target_alignment.alignments.extend(alignments);
+                    },
+                    None => {
+                        target_alignments.push(TranslatedTargetAlignment {
+                            index: target_alignment.index,
+                            alignments: alignments.collect(),
+                        });
+                    },
+                }
+            });
+        }
+    }
+    target_alignments.sort_by_key(|x| x.index);
+
+    TranslatedQueryAlignment(target_alignments)
+}
+
+fn convert_to_nucleotide_query_positions(
+    alignment: &mut QueryAlignment,
+    is_forward: bool,
+    offset: u32,
+    query_length: u32,
+) {
+    alignment.0.iter_mut().for_each(|tgt_aln| {
+        tgt_aln.alignments.iter_mut().for_each(|aln| {
+            let (start, end) = aln.position.query;
+            let (start, end) = (offset + start * CODON_SIZE, offset + end * CODON_SIZE);
+            aln.position.query = if is_forward {
+                (start, end)
+            } else {
+                (query_length - end, query_length - start)
+            };
+        })
+    });
+}
@@ -85,11 +85,26 @@ pub use reference::{
     ReferenceBuilder,
     ReferenceBuildError,
     ReferenceLoadError,
+    PROTEIN_ALPHABET,
+    ReferenceMetadata,
+    BuildConfiguration,
+    SourceFile,
+    PatternIndexInfo,
+    BwtBlockSize,
+    TargetSubset,
+    TargetSubsetError,
+    ShardedReference,
+    ShardedReferenceBuilder,
+    ShardedReferenceError,
+    ShardLoading,
 };
 
 mod aligner;
 pub use aligner::{
     Aligner,
+    ParallelAligner,
+    Strand,
+    GeneticCode,
     algorithms,
 };
 
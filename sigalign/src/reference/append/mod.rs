@@ -0,0 +1,69 @@
+use sigalign_impl::pattern_index::dynamic_lfi::DynamicLfiOption;
+
+use super::{
+    Reference,
+    ReferenceBuilder,
+    ReferenceBuildError,
+};
+
+impl Reference {
+    /// Append the targets in `ReferenceBuilder` without rebuilding the whole pattern index.
+    ///  - The targets are processed by the configuration of `targets` (e.g., uppercase and bases to ignore),
+    ///    so use the same configuration as this `Reference` was built with.
+    ///  - The appended targets get the indices following the existing targets, keeping their labels.
+    ///    The indices of existing targets are not changed.
+    ///  - The appended targets are indexed separately, by the pattern index configuration of `targets`.
+    ///    This index is rebuilt with all appended targets at each call,
+    ///    so call `merge_appended_targets` when many targets are appended.
+    ///  - The source files of `targets` are added to the `ReferenceMetadata`.
+    ///  - If the pattern index cannot be built (e.g., too many characters), this `Reference` is not changed.
+    pub fn append_targets(&mut self, mut targets: ReferenceBuilder) -> Result<(), ReferenceBuildError> {
+        let num_targets = self.get_num_targets();
+        let appended_length: u64 = (num_targets - self.get_num_appended_targets()..num_targets).map(|target_index| {
+            self.get_sequence_length(target_index).unwrap_or(0) as u64
+        }).sum();
+        let sequence_storage = targets.take_sequence_storage();
+        let dynamic_lfi_option = targets.get_option_for_dynamic_lfi(
+            appended_length + sequence_storage.get_total_length(),
+        );
+
+        self.raw_reference.append_targets(
+            sequence_storage,
+            |stored, sequence_storage| stored.merge(sequence_storage),
+            dynamic_lfi_option,
+        )?;
+        // Appended targets are included, keeping the exclusions.
+        self.full_sorted_target_indices.extend(num_targets..self.raw_reference.num_targets());
+        self.metadata.source_files.extend(targets.get_metadata().source_files);
+        Ok(())
+    }
+    /// Rebuild the pattern index with all targets, including the appended ones.
+    ///  - The pattern index options of the `BuildConfiguration` in the metadata are used.
+    ///  - Without the `BuildConfiguration` (loaded from the legacy file), the suffix array sampling ratio
+    ///    and BWT block size of the current index are kept, and the other options are the defaults of `ReferenceBuilder`.
+    pub fn merge_appended_targets(&mut self) -> Result<(), ReferenceBuildError> {
+        if self.get_num_appended_targets() == 0 {
+            return Ok(())
+        }
+        let dynamic_lfi_option = self.get_option_to_rebuild();
+        self.raw_reference.merge_appended_targets(dynamic_lfi_option)?;
+        Ok(())
+    }
+    /// Get the number of targets appended by `append_targets` and not merged yet.
+    pub fn get_num_appended_targets(&self) -> u32 {
+        self.raw_reference.num_appended_targets()
+    }
+
+    fn get_option_to_rebuild(&self) -> DynamicLfiOption {
+        let builder = match &self.metadata.build_configuration {
+            Some(build_configuration) => ReferenceBuilder::from_build_configuration(build_configuration),
+            None => {
+                let pattern_index_info = self.get_pattern_index_info();
+                ReferenceBuilder::new()
+                    .set_suffix_array_sampling_ratio(pattern_index_info.suffix_array_sampling_ratio)
+                    .set_bwt_block_size(pattern_index_info.bwt_block_size)
+            },
+        };
+        builder.get_option_for_dynamic_lfi(self.get_total_length())
+    }
+}
@@ -5,11 +5,17 @@ use thiserror::Error;
 use sigalign_core::reference::Reference as RawReference;
 use sigalign_impl::{
     pattern_index::dynamic_lfi::{
-        DynamicLfiOption, LfiBuildError,
+        DynamicLfiOption, LfiBuildError, BwtBlockSize,
     },
     sequence_storage::in_memory::InMemoryStorage,
 };
-use super::Reference;
+use super::{
+    Reference,
+    ReferenceMetadata,
+    BuildConfiguration,
+    SourceFile,
+    metadata::DigestReader,
+};
 
 /// Builder for `Reference`.
 /// 
@@ -18,10 +24,49 @@ use super::Reference;
 ///      - Reference treats uppercase and lowercase letters as different bases.
 ///   - Ignore bases: None
 ///      - Reference treats all characters as bases.
+///   - Alphabet: None
+///      - Reference keeps all characters as they are.
+///   - Pattern index:
+///      - Suffix array sampling ratio: 1 (no sampling)
+///      - Lookup table: 1/8 of total length (maximum 200 MiB)
+///      - BWT block size: `BwtBlockSize::V64`
+///      - Safe guard: true
+///      - The chosen structure can be checked by `Reference::get_pattern_index_info`.
 pub struct ReferenceBuilder {
     uppercase: bool,
     to_ignore_bases: Vec<u8>,
+    alphabet: Option<(Vec<u8>, u8)>,
     sequence_storage: InMemoryStorage,
+    suffix_array_sampling_ratio: u64,
+    lookup_table_size: LookupTableSize,
+    bwt_block_size: BwtBlockSize,
+    use_safe_guard: bool,
+    source_files: Vec<SourceFile>,
+}
+
+/// Protein alphabet: 20 amino acids with the ambiguous (`X`, `B`, `Z`) and the stop (`*`) codes.
+pub const PROTEIN_ALPHABET: &[u8] = b"ACDEFGHIKLMNPQRSTVWYXBZ*";
+
+/// Structure of the pattern index of `Reference`, chosen when building.
+///  - Returned by `Reference::get_pattern_index_info`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PatternIndexInfo {
+    /// Number of bits to represent a character (2 to 5), by the number of characters.
+    pub bits_per_character: u32,
+    /// Number of indexed characters (including the safe guard).
+    pub num_characters: u32,
+    pub bwt_block_size: BwtBlockSize,
+    pub suffix_array_sampling_ratio: u64,
+    pub lookup_table_kmer_size: u32,
+    /// Estimated size of the pattern index in bytes.
+    pub size_in_bytes: usize,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum LookupTableSize {
+    Auto,
+    KmerSize(u32),
+    MaxBytesSize(u64),
 }
 
 /// Error for building `Reference`.
@@ -43,9 +88,23 @@ impl ReferenceBuilder {
         Self {
             uppercase: true,
             to_ignore_bases: Vec::new(),
+            alphabet: None,
             sequence_storage: InMemoryStorage::new(),
+            suffix_array_sampling_ratio: 1,
+            lookup_table_size: LookupTableSize::Auto,
+            bwt_block_size: BwtBlockSize::default(),
+            use_safe_guard: true,
+            source_files: Vec::new(),
         }
     }
+    /// Make a new `ReferenceBuilder` for protein sequences.
+    ///  - The alphabet is `PROTEIN_ALPHABET`, and the other characters are changed to `X`.
+    ///  - Use with the protein substitution matrix (e.g., `SubstitutionMatrix::blosum62`).
+    pub fn new_protein() -> Self {
+        Self::new()
+            .set_uppercase(true)
+            .set_alphabet(PROTEIN_ALPHABET, b'X')
+    }
     /* Configuration */
     /// Set all letters to uppercase when building.
     pub fn set_uppercase(mut self, uppercase: bool) -> Self {
@@ -53,6 +112,7 @@ impl ReferenceBuilder {
         self
     }
     /// Set the base that never match to any other bases.
+    ///  - To match the ambiguity codes, use `SubstitutionMatrix::iupac` instead.
     pub fn ignore_base(mut self, base: u8) -> Self {
         self.to_ignore_bases.push(base);
         self
@@ -67,6 +127,55 @@ impl ReferenceBuilder {
         self.to_ignore_bases.clear();
         self
     }
+    /// Set the alphabet of the sequences.
+    ///  - The characters not in the `alphabet` are changed to `unknown_base` (after changed to uppercase).
+    pub fn set_alphabet(mut self, alphabet: &[u8], unknown_base: u8) -> Self {
+        self.alphabet = Some((alphabet.to_vec(), unknown_base));
+        self
+    }
+    /// Reset the alphabet to keep all characters.
+    pub fn reset_alphabet(mut self) -> Self {
+        self.alphabet = None;
+        self
+    }
+    /* Pattern Index */
+    /// Set the sampling ratio of suffix array. Zero is regarded as one.
+    ///  - A larger ratio makes the index smaller, but slower to locate patterns.
+    pub fn set_suffix_array_sampling_ratio(mut self, sampling_ratio: u64) -> Self {
+        self.suffix_array_sampling_ratio = sampling_ratio.max(1);
+        self
+    }
+    /// Set the k-mer size of lookup table.
+    ///  - The lookup table takes about `(number of characters + 1)^k` entries.
+    ///  - Must be at least one, and the lookup table must be within 4 GiB
+    ///    (`MAX_LOOKUP_TABLE_BYTES_SIZE` of the pattern index), or `build` returns an error.
+    ///  - Overrides `set_lookup_table_max_bytes_size`.
+    pub fn set_lookup_table_kmer_size(mut self, kmer_size: u32) -> Self {
+        self.lookup_table_size = LookupTableSize::KmerSize(kmer_size);
+        self
+    }
+    /// Set the maximum size of lookup table in bytes.
+    ///  - The largest k-mer size within this size is used.
+    ///  - Overrides `set_lookup_table_kmer_size`.
+    pub fn set_lookup_table_max_bytes_size(mut self, max_bytes_size: u64) -> Self {
+        self.lookup_table_size = LookupTableSize::MaxBytesSize(max_bytes_size);
+        self
+    }
+    /// Set the size of the block to store the BWT.
+    ///  - `BwtBlockSize::V128` makes the index smaller, but slower to locate patterns.
+    pub fn set_bwt_block_size(mut self, bwt_block_size: BwtBlockSize) -> Self {
+        self.bwt_block_size = bwt_block_size;
+        self
+    }
+    /// Set whether to index all characters of the sequence.
+    ///  - If false, the last character in ASCII order is not indexed, to use a smaller block when
+    ///    the number of characters is just over the capacity (e.g., `ACGT` and one more).
+    ///    Then, the patterns including the characters not in the reference can be located wrongly.
+    pub fn set_safe_guard(mut self, use_safe_guard: bool) -> Self {
+        self.use_safe_guard = use_safe_guard;
+        self
+    }
+
     /* Add Sequences */
     pub fn add_target(mut self, label: &str, sequence: &[u8]) -> Self {
         self.sequence_storage.add_target(label, sequence);
@@ -76,45 +185,121 @@ impl ReferenceBuilder {
         self.sequence_storage.add_fasta(reader).map_err(|_| ReferenceBuildError::invalid_fasta_record())?;
         Ok(self)
     }
+    /// Add the targets in the FASTA file.
+    ///  - The path and digest of the file are recorded in the `ReferenceMetadata`.
     pub fn add_fasta_file<P>(mut self, path: P) -> Result<Self, ReferenceBuildError> where
         P: AsRef<std::path::Path> + std::fmt::Debug,
     {
-        let file = File::open(path)?;
-        self.sequence_storage.add_fasta(file).map_err(|_| ReferenceBuildError::invalid_fasta_record())?;
+        let mut reader = DigestReader::new(File::open(&path)?);
+        self.sequence_storage.add_fasta(&mut reader).map_err(|_| ReferenceBuildError::invalid_fasta_record())?;
+        let digest = reader.digest();
+        self.source_files.push(SourceFile {
+            path: path.as_ref().to_string_lossy().into_owned(),
+            size_in_bytes: digest.length,
+            crc32: digest.crc32,
+        });
         Ok(self)
     }
 
     /// Finish building `Reference`.
     pub fn build(mut self) -> Result<Reference, ReferenceBuildError> {
-        // Sequence Storage
-        if self.uppercase {
-            self.sequence_storage.set_sequences_to_uppercase()
-        }
-        if !self.to_ignore_bases.is_empty() {
-            self.sequence_storage.change_bases_to(&self.to_ignore_bases, b'?');
-        }
-
-        // Pattern index option
-        let dynamic_lfi_option = Self::get_option_for_dynamic_lfi(&self.sequence_storage);
+        let sequence_storage = self.take_sequence_storage();
+        let dynamic_lfi_option = self.get_option_for_dynamic_lfi(sequence_storage.get_total_length());
         let raw_reference = RawReference::new(
-            self.sequence_storage,
+            sequence_storage,
             dynamic_lfi_option,
         )?;
-        Ok(Reference::from(raw_reference))
+        let mut reference = Reference::from(raw_reference);
+        reference.metadata = self.get_metadata();
+        Ok(reference)
     }
 
-    fn get_option_for_dynamic_lfi(sequence_storage: &InMemoryStorage) -> DynamicLfiOption {
-        let total_length = sequence_storage.get_total_length();
-        // Use 1/8 of total length as the maximum size of lookup table.
-        // Maximum: 200 MiB
-        let lookup_table_max_bytes_size = u64::min(
-            200 * 1024 * 1024,
-            (total_length / 8) as u64,
-        );
+    /// Take the sequence storage with the bases changed by the configuration.
+    pub(super) fn take_sequence_storage(&mut self) -> InMemoryStorage {
+        let mut sequence_storage = std::mem::replace(&mut self.sequence_storage, InMemoryStorage::new());
+        if self.uppercase {
+            sequence_storage.set_sequences_to_uppercase()
+        }
+        if let Some((alphabet, unknown_base)) = &self.alphabet {
+            let bases_not_in_alphabet: Vec<u8> = (0..=u8::MAX).filter(|base| !alphabet.contains(base)).collect();
+            sequence_storage.change_bases_to(&bases_not_in_alphabet, *unknown_base);
+        }
+        if !self.to_ignore_bases.is_empty() {
+            sequence_storage.change_bases_to(&self.to_ignore_bases, b'?');
+        }
+        sequence_storage
+    }
+    /// Metadata of the `Reference` built now.
+    pub(super) fn get_metadata(&mut self) -> ReferenceMetadata {
+        let created_at = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|duration| duration.as_secs())
+            .ok();
+        let (lookup_table_kmer_size, lookup_table_max_bytes_size) = match self.lookup_table_size {
+            LookupTableSize::Auto => (None, None),
+            LookupTableSize::KmerSize(kmer_size) => (Some(kmer_size), None),
+            LookupTableSize::MaxBytesSize(max_bytes_size) => (None, Some(max_bytes_size)),
+        };
+        let bwt_block_size = match self.bwt_block_size {
+            BwtBlockSize::V64 => 64,
+            BwtBlockSize::V128 => 128,
+        };
+        ReferenceMetadata {
+            created_at,
+            build_configuration: Some(BuildConfiguration {
+                uppercase: self.uppercase,
+                ignored_bases: self.to_ignore_bases.clone(),
+                alphabet: self.alphabet.clone(),
+                suffix_array_sampling_ratio: self.suffix_array_sampling_ratio,
+                lookup_table_kmer_size,
+                lookup_table_max_bytes_size,
+                bwt_block_size,
+                use_safe_guard: self.use_safe_guard,
+            }),
+            source_files: std::mem::take(&mut self.source_files),
+        }
+    }
+    /// `ReferenceBuilder` with the configuration recorded in the `ReferenceMetadata`.
+    pub(super) fn from_build_configuration(build_configuration: &BuildConfiguration) -> Self {
+        let mut builder = Self::new()
+            .set_uppercase(build_configuration.uppercase)
+            .ignore_bases(&build_configuration.ignored_bases)
+            .set_suffix_array_sampling_ratio(build_configuration.suffix_array_sampling_ratio)
+            .set_bwt_block_size(match build_configuration.bwt_block_size {
+                128 => BwtBlockSize::V128,
+                _ => BwtBlockSize::V64,
+            })
+            .set_safe_guard(build_configuration.use_safe_guard);
+        if let Some((alphabet, unknown_base)) = &build_configuration.alphabet {
+            builder = builder.set_alphabet(alphabet, *unknown_base);
+        }
+        if let Some(kmer_size) = build_configuration.lookup_table_kmer_size {
+            builder = builder.set_lookup_table_kmer_size(kmer_size);
+        } else if let Some(max_bytes_size) = build_configuration.lookup_table_max_bytes_size {
+            builder = builder.set_lookup_table_max_bytes_size(max_bytes_size);
+        }
+        builder
+    }
+    pub(super) fn get_option_for_dynamic_lfi(&self, total_length: u64) -> DynamicLfiOption {
+        let (lookup_table_max_bytes_size, lookup_table_kmer_size) = match self.lookup_table_size {
+            LookupTableSize::Auto => {
+                // Use 1/8 of total length as the maximum size of lookup table.
+                // Maximum: 200 MiB
+                let max_bytes_size = u64::min(
+                    200 * 1024 * 1024,
+                    total_length / 8,
+                );
+                (max_bytes_size, None)
+            },
+            LookupTableSize::KmerSize(kmer_size) => (0, Some(kmer_size)),
+            LookupTableSize::MaxBytesSize(max_bytes_size) => (max_bytes_size, None),
+        };
         DynamicLfiOption {
-            suffix_array_sampling_ratio: 1,
+            suffix_array_sampling_ratio: self.suffix_array_sampling_ratio,
             lookup_table_max_bytes_size,
-            use_safe_guard: true,
+            lookup_table_kmer_size,
+            use_safe_guard: self.use_safe_guard,
+            bwt_block_size: self.bwt_block_size,
         }
     }
 }
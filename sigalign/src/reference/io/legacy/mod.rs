@@ -0,0 +1,55 @@
+use std::io::{Write, Read, Seek};
+use std::sync::Arc;
+
+use sigalign_core::reference::{
+    Reference as RawReference,
+    extensions::{Serialize, SharedBytes},
+};
+use sigalign_impl::{
+    pattern_index::dynamic_lfi::DynamicLfi,
+    sequence_storage::in_memory::InMemoryStorage,
+};
+use super::{
+    Reference,
+    ReferenceLoadError,
+    ReferenceMetadata,
+    sectioned::{
+        METADATA_SECTION, RAW_REFERENCE_SECTION,
+        write_section, write_section_by_seeking,
+    },
+};
+
+// Legacy format (1):
+//  - Raw reference (to the end of file), without metadata and checksum.
+//  - The raw reference is in the layout before the appended targets (`LEGACY_CORE_VERSION`).
+
+impl Reference {
+    pub(super) fn load_legacy<R: Read>(reader: R) -> Result<Self, ReferenceLoadError> {
+        let raw_reference = RawReference::load_legacy_from(reader)?;
+        Ok(Self::from(raw_reference))
+    }
+    pub(super) fn load_legacy_in_place(
+        mmap: memmap2::Mmap,
+        mut offset: usize,
+    ) -> Result<Self, ReferenceLoadError> {
+        let bytes: SharedBytes = Arc::new(mmap);
+        let raw_reference = RawReference::load_legacy_in_place(&bytes, &mut offset)?;
+        Ok(Self::from(raw_reference))
+    }
+    pub(super) fn verify_legacy<R: Read>(_reader: R) -> Result<ReferenceMetadata, ReferenceLoadError> {
+        Err(ReferenceLoadError::MissingChecksum)
+    }
+    /// Write the sections of the current format, with the raw reference in the current layout.
+    pub(super) fn upgrade_legacy<R, W>(reader: R, mut writer: W) -> Result<(), ReferenceLoadError> where
+        R: Read,
+        W: Write + Seek,
+    {
+        let raw_reference: RawReference<DynamicLfi, InMemoryStorage> = RawReference::load_legacy_from(reader)?;
+        let metadata = ReferenceMetadata::default();
+        write_section(&mut writer, METADATA_SECTION, &serde_json::to_vec(&metadata).map_err(std::io::Error::from)?)?;
+        write_section_by_seeking(&mut writer, RAW_REFERENCE_SECTION, |section_writer| {
+            raw_reference.save_to(section_writer)
+        })?;
+        Ok(())
+    }
+}
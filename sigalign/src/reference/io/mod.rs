@@ -1,46 +1,217 @@
-use std::io::{Write, Read};
+use std::io::{Write, Read, Seek, Cursor, BufReader, BufWriter};
+use std::fs::File;
+use std::path::Path;
 
 use base64::{Engine as _, engine::{general_purpose, GeneralPurpose}};
 use thiserror::Error;
-use capwriter::{Save, Load};
+use capwriter::Save;
 
-use sigalign_core::reference::{
-    Reference as RawReference, extensions::Serialize,
+use sigalign_core::reference::Reference as RawReference;
+use sigalign_impl::{
+    pattern_index::dynamic_lfi::DynamicLfi,
+    sequence_storage::in_memory::InMemoryStorage,
 };
-use super::Reference;
+use super::{
+    Reference,
+    ReferenceMetadata,
+};
+
+mod legacy;
+mod sectioned;
 
+// Signature of the file:
+//  - Current: `PREFIX:FORMAT_KEYWORD:{format version}:CORE_VERSION`
+//  - Legacy (format 1): `PREFIX:LEGACY_WRAPPER_VERSION:LEGACY_CORE_VERSION`
+// The core version is of the layout of the raw reference (the pattern index and sequence storage).
+// The file can be read only if the core version is the one of its format.
 const PREFIX: &str = "SIGALIGN_REFERENCE";
-const LOWEST_COMPARABLE_WRAPPER_VERSION: &str = "0.4.0";
-const CORE_VERSION: &str = "0.2.0";
+const FORMAT_KEYWORD: &str = "FORMAT";
+const LEGACY_WRAPPER_VERSION: &str = "0.4.0";
+const LEGACY_FORMAT_VERSION: u32 = 1;
+const CORE_VERSION: &str = "0.3.0";
+const LEGACY_CORE_VERSION: &str = "0.2.0";
 const DELIMITER: &str = ":";
 
 impl Reference {
+    /// Version of the file format written by `save_to`.
+    ///  - The files of the previous format versions with the same index layout can be loaded,
+    ///    and rewritten in the current format by `upgrade_file`.
+    pub const FILE_FORMAT_VERSION: u32 = 2;
+
     /// Save `Reference` to a writer.
+    ///  - The raw reference is serialized once, computing its checksum on the way.
+    ///  - The checksum of the raw reference is written after it, as the writer may not seek.
+    ///    Use `save_to_seekable` to write it in the header (e.g., to a file).
     pub fn save_to<W>(&self, mut writer: W) -> Result<(), std::io::Error> where
         W: Write
     {
         let signature = Self::get_base64_encoded_signature_of_current_version();
         signature.as_bytes().save_to(&mut writer)?;
-        self.raw_reference.save_to(writer)?;
+        self.save_sections(writer)?;
+        Ok(())
+    }
+    /// Save `Reference` to a seekable writer.
+    ///  - Same as `save_to`, but the checksum of the raw reference is written in the header by seeking back.
+    pub fn save_to_seekable<W>(&self, mut writer: W) -> Result<(), std::io::Error> where
+        W: Write + Seek
+    {
+        let signature = Self::get_base64_encoded_signature_of_current_version();
+        signature.as_bytes().save_to(&mut writer)?;
+        self.save_sections_by_seeking(writer)?;
         Ok(())
     }
     /// Load `Reference` from a reader.
+    ///  - The checksums are verified after reading each section.
+    ///    The file is read to the end to report the truncation or corruption, if reading the raw reference fails.
     pub fn load_from<R>(mut reader: R) -> Result<Self, ReferenceLoadError> where
         R: Read,
         Self: Sized
     {
-        let encoded_signature: Vec<u8> = Vec::load_from(&mut reader)?;
+        match Self::read_format_version(&mut reader)? {
+            LEGACY_FORMAT_VERSION => Self::load_legacy(reader),
+            _ => Self::load_sections(reader),
+        }
+    }
+    /// Load `Reference` from a file, mapping only the target sequences into memory.
+    ///  - The target sequences are not copied into memory, but read from the mapped file,
+    ///    so the processes loading the same file share the pages of the sequences.
+    ///  - Only the sequences are mapped: the pattern index is deserialized (copied) into memory as `load_from`,
+    ///    so the loading time and the memory for the pattern index are not reduced.
+    ///  - The file is the same format as the one written by `save_to`.
+    ///  - Only the length of the raw reference is checked, not to read the whole file (use `verify` for the checksum).
+    ///
+    /// # Caution
+    /// The file must not be modified or truncated while the `Reference` (or its clone) is alive.
+    /// Modifying the file leads to undefined behavior.
+    pub fn load_with_mapped_sequences<P>(file_path: P) -> Result<Self, ReferenceLoadError> where
+        P: AsRef<Path>,
+    {
+        let file = File::open(file_path)?;
+        // Safety: the file is supposed not to be modified while mapped (documented above).
+        let mmap = unsafe { memmap2::Mmap::map(&file)? };
+
+        let mut cursor = Cursor::new(&mmap[..]);
+        let format_version = Self::read_format_version(&mut cursor)?;
+        let offset = cursor.position() as usize;
+        match format_version {
+            LEGACY_FORMAT_VERSION => Self::load_legacy_in_place(mmap, offset),
+            _ => Self::load_sections_in_place(mmap, offset),
+        }
+    }
+    /// Check the integrity of the saved `Reference` file, without loading it.
+    ///  - The whole file is read to compare the lengths and checksums with the ones written.
+    ///  - Returns the metadata if the file is intact.
+    ///  - The legacy files without checksum cannot be verified (`ReferenceLoadError::MissingChecksum`).
+    pub fn verify<P>(file_path: P) -> Result<ReferenceMetadata, ReferenceLoadError> where
+        P: AsRef<Path>,
+    {
+        let mut reader = BufReader::new(File::open(file_path)?);
+        match Self::read_format_version(&mut reader)? {
+            LEGACY_FORMAT_VERSION => Self::verify_legacy(reader),
+            _ => Self::verify_sections(reader),
+        }
+    }
+    /// Get the format version of the saved `Reference` file.
+    pub fn get_file_format_version<P>(file_path: P) -> Result<u32, ReferenceLoadError> where
+        P: AsRef<Path>,
+    {
+        Self::read_format_version(BufReader::new(File::open(file_path)?))
+    }
+    /// Rewrite the `Reference` file of the previous format into the current format.
+    ///  - The raw reference (pattern index and sequence storage) is rewritten in the current layout, without rebuilding the index.
+    ///  - The file of the current format is copied, verifying its checksums on the way.
+    ///  - The legacy file has no checksum to verify; its raw reference is checked by loading it.
+    ///  - `destination_path` can be the same as `source_path`: the file is written to a temporary file
+    ///    next to the destination, and then renamed.
+    ///  - Returns the format version of the source file.
+    ///  - The file with a different index layout (`ReferenceLoadError::IncompatibleVersion`)
+    ///    cannot be upgraded, and has to be built again.
+    pub fn upgrade_file<P1, P2>(source_path: P1, destination_path: P2) -> Result<u32, ReferenceLoadError> where
+        P1: AsRef<Path>,
+        P2: AsRef<Path>,
+    {
+        let mut reader = BufReader::new(File::open(source_path)?);
+        let format_version = Self::read_format_version(&mut reader)?;
+
+        let destination_path = destination_path.as_ref();
+        let mut temporary_path = destination_path.as_os_str().to_owned();
+        temporary_path.push(".upgrading");
+        let result = File::create(&temporary_path).map_err(ReferenceLoadError::from).and_then(|file| {
+            let mut writer = BufWriter::new(file);
+            let signature = Self::get_base64_encoded_signature_of_current_version();
+            signature.as_bytes().save_to(&mut writer)?;
+            match format_version {
+                LEGACY_FORMAT_VERSION => Self::upgrade_legacy(reader, &mut writer)?,
+                _ => {
+                    Self::verify_sections(TeeReader { reader, writer: &mut writer })?;
+                },
+            }
+            writer.flush()?;
+            Ok(())
+        });
+        match result {
+            Ok(()) => {
+                std::fs::rename(&temporary_path, destination_path)?;
+                Ok(format_version)
+            },
+            Err(error) => {
+                let _ = std::fs::remove_file(&temporary_path);
+                Err(error)
+            },
+        }
+    }
+    /// True if the target sequences are read from a memory-mapped file (loaded by `load_with_mapped_sequences`).
+    ///  - The pattern index is always in memory.
+    pub fn has_mapped_sequences(&self) -> bool {
+        self.raw_reference.get_sequence_storage().is_sequence_shared()
+    }
+    fn from_raw_reference_excluding(
+        raw_reference: RawReference<DynamicLfi, InMemoryStorage>,
+        excluded_target_indices: &[u32],
+    ) -> Result<Self, ReferenceLoadError> {
+        let mut reference = Self::from(raw_reference);
+        reference.exclude_targets(excluded_target_indices).map_err(|_| {
+            std::io::Error::from(std::io::ErrorKind::InvalidData)
+        })?;
+        Ok(reference)
+    }
+    /// Read the signature and return the format version.
+    fn read_format_version<R: Read>(mut reader: R) -> Result<u32, ReferenceLoadError> {
+        // The signature is read by `take` not to allocate a huge buffer for unknown file.
+        let mut length_bytes = [0; std::mem::size_of::<usize>()];
+        reader.read_exact(&mut length_bytes).map_err(|_| ReferenceLoadError::UnknownFile)?;
+        let signature_length = usize::from_ne_bytes(length_bytes) as u64;
+        let mut encoded_signature = Vec::new();
+        reader.take(signature_length).read_to_end(&mut encoded_signature)?;
+        if encoded_signature.len() as u64 != signature_length {
+            return Err(ReferenceLoadError::UnknownFile)
+        }
+
         let signatures = Self::get_base64_decoded_signature(&encoded_signature)?;
-        if signatures[0] == PREFIX && signatures[1] == LOWEST_COMPARABLE_WRAPPER_VERSION && signatures[2] == CORE_VERSION {
-            let raw_reference = RawReference::load_from(reader)?;
-            Ok(Self::from(raw_reference))
+        if signatures[0] != PREFIX || signatures.len() < 3 {
+            return Err(ReferenceLoadError::UnknownFile)
+        }
+        let (format_version, core_version, expected_core_version) = if signatures[1] == FORMAT_KEYWORD && signatures.len() == 4 {
+            let format_version = signatures[2].parse::<u32>().ok()
+                .filter(|&version| version > LEGACY_FORMAT_VERSION)
+                .ok_or(ReferenceLoadError::UnknownFile)?;
+            (format_version, &signatures[3], CORE_VERSION)
+        } else if signatures[1] == LEGACY_WRAPPER_VERSION && signatures.len() == 3 {
+            (LEGACY_FORMAT_VERSION, &signatures[2], LEGACY_CORE_VERSION)
         } else {
-            Err(ReferenceLoadError::IncompatibleVersion(signatures[1].clone()))
+            return Err(ReferenceLoadError::IncompatibleVersion(signatures[1..].join(DELIMITER)))
+        };
+        if format_version > Self::FILE_FORMAT_VERSION || core_version != expected_core_version {
+            return Err(ReferenceLoadError::IncompatibleVersion(signatures[1..].join(DELIMITER)))
         }
+        Ok(format_version)
     }
     fn get_base64_encoded_signature_of_current_version() -> String {
         let engine = Self::get_base64_engine();
-        let combined_signature = [PREFIX, DELIMITER, LOWEST_COMPARABLE_WRAPPER_VERSION, DELIMITER, CORE_VERSION].concat();
+        let format_version = Self::FILE_FORMAT_VERSION.to_string();
+        let combined_signature = [
+            PREFIX, DELIMITER, FORMAT_KEYWORD, DELIMITER, &format_version, DELIMITER, CORE_VERSION,
+        ].concat();
         let mut encoded_signature = String::new();
         engine.encode_string(combined_signature, &mut encoded_signature);
 
@@ -63,12 +234,36 @@ impl Reference {
 }
 
 /// Error for loading `Reference`.
+/// Reader writing the bytes read to the writer.
+struct TeeReader<R: Read, W: Write> {
+    reader: R,
+    writer: W,
+}
+
+impl<R: Read, W: Write> Read for TeeReader<R, W> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let read = self.reader.read(buf)?;
+        self.writer.write_all(&buf[..read])?;
+        Ok(read)
+    }
+}
+
 #[derive(Debug, Error)]
 pub enum ReferenceLoadError {
     #[error("Unknown file format. The file does not appear to be a SigAlign reference file.")]
     UnknownFile,
     #[error("This reference file is incompatible with the current version of SigAlign. Detected version: {0}")]
     IncompatibleVersion(String),
+    #[error("The reference file is truncated: {actual} of {expected} bytes")]
+    Truncated { expected: u64, actual: u64 },
+    #[error("The reference file is corrupted: {actual} bytes, but {expected} bytes expected")]
+    LengthMismatch { expected: u64, actual: u64 },
+    #[error("The reference file is corrupted: checksum {actual:08x}, but {expected:08x} expected")]
+    ChecksumMismatch { expected: u32, actual: u32 },
+    #[error("The reference file has no checksum to verify. It was saved by the previous version.")]
+    MissingChecksum,
+    #[error("Invalid metadata in the reference file: {0}")]
+    InvalidMetadata(String),
     #[error(transparent)]
     IoError(#[from] std::io::Error),
 }
@@ -0,0 +1,347 @@
+use std::io::{Write, Read, Seek, SeekFrom, Cursor};
+use std::sync::Arc;
+
+use capwriter::{Save, Load};
+
+use sigalign_core::reference::{
+    Reference as RawReference,
+    extensions::{Serialize, LoadInPlace, SharedBytes},
+};
+use sigalign_impl::{
+    pattern_index::dynamic_lfi::DynamicLfi,
+    sequence_storage::in_memory::InMemoryStorage,
+};
+use super::{
+    Reference,
+    ReferenceLoadError,
+    ReferenceMetadata,
+};
+use crate::reference::metadata::{Digest, DigestReader, DigestWriter};
+
+// Current format (2) is a sequence of sections:
+//  - Each section: tag (8 bytes), length of payload (u64, LE), CRC32 of payload (u32, LE), payload
+//  - The raw reference section is the last one, ending at the end of file.
+//    If the writer cannot seek, its length in the header is `TRAILING_DIGEST`,
+//    and the length and CRC32 are written after the payload instead.
+//  - The sections of unknown tags are skipped, so the later versions can add sections
+//    readable by this version. The change of the required sections needs a new format version.
+pub(super) const METADATA_SECTION: [u8; 8] = *b"METADATA";
+pub(super) const EXCLUDED_TARGETS_SECTION: [u8; 8] = *b"EXCLUDED";
+pub(super) const RAW_REFERENCE_SECTION: [u8; 8] = *b"RAWREFER";
+
+const SECTION_HEADER_SIZE: usize = 8 + 8 + 4;
+const DIGEST_SIZE: usize = 8 + 4;
+const TRAILING_DIGEST: u64 = u64::MAX;
+
+struct SectionHeader {
+    tag: [u8; 8],
+    digest: Digest,
+}
+
+impl SectionHeader {
+    fn to_bytes(&self) -> [u8; SECTION_HEADER_SIZE] {
+        let mut bytes = [0; SECTION_HEADER_SIZE];
+        bytes[..8].copy_from_slice(&self.tag);
+        bytes[8..].copy_from_slice(&digest_to_bytes(&self.digest));
+        bytes
+    }
+    fn from_bytes(bytes: &[u8]) -> Result<Self, ReferenceLoadError> {
+        if bytes.len() < SECTION_HEADER_SIZE {
+            return Err(ReferenceLoadError::Truncated {
+                expected: SECTION_HEADER_SIZE as u64,
+                actual: bytes.len() as u64,
+            })
+        }
+        Ok(Self {
+            tag: bytes[..8].try_into().unwrap(),
+            digest: digest_from_bytes(&bytes[8..SECTION_HEADER_SIZE]),
+        })
+    }
+    fn has_trailing_digest(&self) -> bool {
+        self.digest.length == TRAILING_DIGEST
+    }
+    fn load_from<R: Read>(reader: R) -> Result<Self, ReferenceLoadError> {
+        let mut bytes = Vec::with_capacity(SECTION_HEADER_SIZE);
+        reader.take(SECTION_HEADER_SIZE as u64).read_to_end(&mut bytes)?;
+        Self::from_bytes(&bytes)
+    }
+    /// Read the whole payload, checking its digest.
+    fn read_payload<R: Read>(&self, reader: R) -> Result<Vec<u8>, ReferenceLoadError> {
+        let mut payload = Vec::new();
+        reader.take(self.digest.length).read_to_end(&mut payload)?;
+        self.digest.check(Digest::of(&payload))?;
+        Ok(payload)
+    }
+}
+
+pub(super) fn write_section<W: Write>(
+    mut writer: W,
+    tag: [u8; 8],
+    payload: &[u8],
+) -> Result<(), std::io::Error> {
+    let header = SectionHeader { tag, digest: Digest::of(payload) };
+    writer.write_all(&header.to_bytes())?;
+    writer.write_all(payload)
+}
+fn digest_to_bytes(digest: &Digest) -> [u8; DIGEST_SIZE] {
+    let mut bytes = [0; DIGEST_SIZE];
+    bytes[..8].copy_from_slice(&digest.length.to_le_bytes());
+    bytes[8..].copy_from_slice(&digest.crc32.to_le_bytes());
+    bytes
+}
+fn digest_from_bytes(bytes: &[u8]) -> Digest {
+    Digest {
+        length: u64::from_le_bytes(bytes[..8].try_into().unwrap()),
+        crc32: u32::from_le_bytes(bytes[8..DIGEST_SIZE].try_into().unwrap()),
+    }
+}
+
+/// Write the section of which payload is written by `write_payload`,
+/// and then write the header by seeking back (for the payload too large to keep in memory).
+pub(super) fn write_section_by_seeking<W, F>(
+    mut writer: W,
+    tag: [u8; 8],
+    write_payload: F,
+) -> Result<(), std::io::Error> where
+    W: Write + Seek,
+    F: FnOnce(&mut DigestWriter<&mut W>) -> Result<(), std::io::Error>,
+{
+    let header_position = writer.stream_position()?;
+    writer.write_all(&[0; SECTION_HEADER_SIZE])?;
+    let mut digest_writer = DigestWriter::new(&mut writer);
+    write_payload(&mut digest_writer)?;
+    let header = SectionHeader { tag, digest: digest_writer.digest() };
+    writer.seek(SeekFrom::Start(header_position))?;
+    writer.write_all(&header.to_bytes())?;
+    writer.seek(SeekFrom::End(0))?;
+    Ok(())
+}
+/// Write the last section of which payload is written by `write_payload`,
+/// and then write the digest after the payload (for the writer that cannot seek).
+fn write_last_section_with_trailing_digest<W, F>(
+    mut writer: W,
+    tag: [u8; 8],
+    write_payload: F,
+) -> Result<(), std::io::Error> where
+    W: Write,
+    F: FnOnce(&mut DigestWriter<&mut W>) -> Result<(), std::io::Error>,
+{
+    let header = SectionHeader { tag, digest: Digest { length: TRAILING_DIGEST, crc32: 0 } };
+    writer.write_all(&header.to_bytes())?;
+    let mut digest_writer = DigestWriter::new(&mut writer);
+    write_payload(&mut digest_writer)?;
+    let digest = digest_writer.digest();
+    writer.write_all(&digest_to_bytes(&digest))
+}
+
+impl Reference {
+    pub(super) fn save_sections<W: Write>(&self, mut writer: W) -> Result<(), std::io::Error> {
+        self.save_sections_before_raw_reference(&mut writer)?;
+        write_last_section_with_trailing_digest(writer, RAW_REFERENCE_SECTION, |section_writer| {
+            self.raw_reference.save_to(section_writer)
+        })
+    }
+    pub(super) fn save_sections_by_seeking<W: Write + Seek>(&self, mut writer: W) -> Result<(), std::io::Error> {
+        self.save_sections_before_raw_reference(&mut writer)?;
+        write_section_by_seeking(writer, RAW_REFERENCE_SECTION, |section_writer| {
+            self.raw_reference.save_to(section_writer)
+        })
+    }
+    fn save_sections_before_raw_reference<W: Write>(&self, mut writer: W) -> Result<(), std::io::Error> {
+        write_section(&mut writer, METADATA_SECTION, &serde_json::to_vec(&self.metadata)?)?;
+        let excluded_target_indices = self.get_excluded_target_indices();
+        if !excluded_target_indices.is_empty() {
+            write_section(&mut writer, EXCLUDED_TARGETS_SECTION, &Self::encode_excluded_targets(&excluded_target_indices)?)?;
+        }
+        Ok(())
+    }
+    pub(super) fn load_sections<R: Read>(mut reader: R) -> Result<Self, ReferenceLoadError> {
+        let mut sections = LoadedSections::default();
+        loop {
+            let header = SectionHeader::load_from(&mut reader)?;
+            if header.tag != RAW_REFERENCE_SECTION {
+                let payload = header.read_payload(&mut reader)?;
+                sections.add(header.tag, &payload)?;
+                continue
+            }
+
+            if header.has_trailing_digest() {
+                let mut section_reader = DigestReader::new(&mut reader);
+                let loaded = RawReference::load_from(&mut section_reader);
+                let digest = section_reader.digest();
+                let loaded = match loaded {
+                    Ok(loaded) => loaded,
+                    Err(error) => {
+                        // Report the truncation, if the length in the trailing digest is longer.
+                        let (trailing_digest, remained_digest) = read_to_trailing_digest(reader)?;
+                        trailing_digest.check_length(digest.length + remained_digest.length)?;
+                        return Err(error.into())
+                    },
+                };
+                let mut trailing_digest = Vec::with_capacity(DIGEST_SIZE);
+                (&mut reader).take(DIGEST_SIZE as u64).read_to_end(&mut trailing_digest)?;
+                if trailing_digest.len() < DIGEST_SIZE {
+                    return Err(ReferenceLoadError::Truncated {
+                        expected: DIGEST_SIZE as u64,
+                        actual: trailing_digest.len() as u64,
+                    })
+                }
+                let trailing_digest = digest_from_bytes(&trailing_digest);
+                trailing_digest.check(digest)?;
+                check_end_of_file(reader, trailing_digest.length)?;
+                return sections.into_reference(loaded)
+            }
+
+            let mut section_reader = DigestReader::new((&mut reader).take(header.digest.length));
+            let loaded = RawReference::load_from(&mut section_reader);
+            if loaded.is_err() {
+                std::io::copy(&mut section_reader, &mut std::io::sink())?;
+            }
+            header.digest.check(section_reader.digest())?;
+            check_end_of_file(reader, header.digest.length)?;
+
+            return sections.into_reference(loaded?)
+        }
+    }
+    pub(super) fn load_sections_in_place(
+        mmap: memmap2::Mmap,
+        mut offset: usize,
+    ) -> Result<Self, ReferenceLoadError> {
+        let mut sections = LoadedSections::default();
+        loop {
+            let header = SectionHeader::from_bytes(&mmap[offset.min(mmap.len())..])?;
+            offset += SECTION_HEADER_SIZE;
+            let remained_length = (mmap.len() - offset) as u64;
+            if header.tag == RAW_REFERENCE_SECTION {
+                if header.has_trailing_digest() {
+                    if remained_length < DIGEST_SIZE as u64 {
+                        return Err(ReferenceLoadError::Truncated {
+                            expected: DIGEST_SIZE as u64,
+                            actual: remained_length,
+                        })
+                    }
+                    let trailing_digest = digest_from_bytes(&mmap[mmap.len() - DIGEST_SIZE..]);
+                    trailing_digest.check_length(remained_length - DIGEST_SIZE as u64)?;
+                } else {
+                    header.digest.check_length(remained_length)?;
+                }
+                break
+            }
+            if remained_length < header.digest.length {
+                return Err(ReferenceLoadError::Truncated {
+                    expected: header.digest.length,
+                    actual: remained_length,
+                })
+            }
+            let payload = &mmap[offset..offset + header.digest.length as usize];
+            header.digest.check(Digest::of(payload))?;
+            sections.add(header.tag, payload)?;
+            offset += payload.len();
+        }
+
+        let bytes: SharedBytes = Arc::new(mmap);
+        let raw_reference = RawReference::load_in_place(&bytes, &mut offset)?;
+        sections.into_reference(raw_reference)
+    }
+    pub(super) fn verify_sections<R: Read>(mut reader: R) -> Result<ReferenceMetadata, ReferenceLoadError> {
+        let mut sections = LoadedSections::default();
+        loop {
+            let header = SectionHeader::load_from(&mut reader)?;
+            if header.tag != RAW_REFERENCE_SECTION {
+                let payload = header.read_payload(&mut reader)?;
+                sections.add(header.tag, &payload)?;
+                continue
+            }
+
+            if header.has_trailing_digest() {
+                let (trailing_digest, digest) = read_to_trailing_digest(reader)?;
+                trailing_digest.check(digest)?;
+                return Ok(sections.metadata)
+            }
+
+            let mut digest_writer = DigestWriter::new(std::io::sink());
+            std::io::copy(&mut (&mut reader).take(header.digest.length), &mut digest_writer)?;
+            header.digest.check(digest_writer.digest())?;
+            check_end_of_file(reader, header.digest.length)?;
+
+            return Ok(sections.metadata)
+        }
+    }
+    pub(super) fn encode_excluded_targets(excluded_target_indices: &[u32]) -> Result<Vec<u8>, std::io::Error> {
+        let mut payload = Vec::new();
+        excluded_target_indices.save_to(&mut payload)?;
+        Ok(payload)
+    }
+}
+
+/// Sections read before the raw reference.
+#[derive(Default)]
+struct LoadedSections {
+    metadata: ReferenceMetadata,
+    excluded_target_indices: Vec<u32>,
+}
+
+impl LoadedSections {
+    fn add(&mut self, tag: [u8; 8], payload: &[u8]) -> Result<(), ReferenceLoadError> {
+        match tag {
+            METADATA_SECTION => {
+                self.metadata = ReferenceMetadata::from_json(payload)?;
+            },
+            EXCLUDED_TARGETS_SECTION => {
+                self.excluded_target_indices = Vec::load_from(Cursor::new(payload))?;
+            },
+            // Added by the later versions
+            _ => {},
+        }
+        Ok(())
+    }
+    fn into_reference(
+        self,
+        raw_reference: RawReference<DynamicLfi, InMemoryStorage>,
+    ) -> Result<Reference, ReferenceLoadError> {
+        let mut reference = Reference::from_raw_reference_excluding(raw_reference, &self.excluded_target_indices)?;
+        reference.metadata = self.metadata;
+        Ok(reference)
+    }
+}
+
+/// Read to the end of file, returning the trailing digest and the digest of the bytes before it.
+fn read_to_trailing_digest<R: Read>(mut reader: R) -> Result<(Digest, Digest), ReferenceLoadError> {
+    let mut digest_writer = DigestWriter::new(std::io::sink());
+    let mut buffer = vec![0; 1 << 16];
+    // The last bytes read, which can be the trailing digest
+    let mut tail: Vec<u8> = Vec::with_capacity(buffer.len() + DIGEST_SIZE);
+    loop {
+        let read = match reader.read(&mut buffer) {
+            Ok(0) => break,
+            Ok(read) => read,
+            Err(error) if error.kind() == std::io::ErrorKind::Interrupted => continue,
+            Err(error) => return Err(error.into()),
+        };
+        tail.extend_from_slice(&buffer[..read]);
+        if tail.len() > DIGEST_SIZE {
+            let payload_length = tail.len() - DIGEST_SIZE;
+            digest_writer.write_all(&tail[..payload_length])?;
+            tail.drain(..payload_length);
+        }
+    }
+    if tail.len() < DIGEST_SIZE {
+        return Err(ReferenceLoadError::Truncated {
+            expected: DIGEST_SIZE as u64,
+            actual: tail.len() as u64,
+        })
+    }
+    Ok((digest_from_bytes(&tail), digest_writer.digest()))
+}
+
+/// The raw reference section must end at the end of file.
+fn check_end_of_file<R: Read>(mut reader: R, section_length: u64) -> Result<(), ReferenceLoadError> {
+    let extra_length = std::io::copy(&mut reader, &mut std::io::sink())?;
+    if extra_length != 0 {
+        return Err(ReferenceLoadError::LengthMismatch {
+            expected: section_length,
+            actual: section_length + extra_length,
+        })
+    }
+    Ok(())
+}
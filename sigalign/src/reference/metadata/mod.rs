@@ -0,0 +1,151 @@
+use std::io::{Read, Write};
+
+use serde::{Deserialize, Serialize};
+
+use super::{
+    Reference,
+    ReferenceLoadError,
+};
+
+/// Provenance of `Reference`, kept in the saved file.
+///  - Built by `ReferenceBuilder`: all fields are recorded.
+///  - Loaded from a file saved by the previous versions: empty (`Default`).
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReferenceMetadata {
+    /// Unix timestamp (in seconds) when the `Reference` was built.
+    pub created_at: Option<u64>,
+    /// Configuration of `ReferenceBuilder` used to build the `Reference`.
+    pub build_configuration: Option<BuildConfiguration>,
+    /// FASTA files added by `ReferenceBuilder::add_fasta_file`, in order.
+    ///  - The files of targets added by `Reference::append_targets` follow.
+    pub source_files: Vec<SourceFile>,
+}
+
+/// Configuration of `ReferenceBuilder`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BuildConfiguration {
+    pub uppercase: bool,
+    pub ignored_bases: Vec<u8>,
+    /// The alphabet and the base replacing the characters not in the alphabet (None if not set).
+    #[serde(default)]
+    pub alphabet: Option<(Vec<u8>, u8)>,
+    pub suffix_array_sampling_ratio: u64,
+    /// None if not set (the size is decided automatically or by `lookup_table_max_bytes_size`).
+    pub lookup_table_kmer_size: Option<u32>,
+    /// None if not set (the size is decided automatically or by `lookup_table_kmer_size`).
+    pub lookup_table_max_bytes_size: Option<u64>,
+    /// Number of bits in a BWT block (64 or 128).
+    pub bwt_block_size: u32,
+    pub use_safe_guard: bool,
+}
+
+/// Digest of a source FASTA file.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SourceFile {
+    /// Path as given to `ReferenceBuilder::add_fasta_file`.
+    pub path: String,
+    pub size_in_bytes: u64,
+    pub crc32: u32,
+}
+
+impl ReferenceMetadata {
+    pub(super) fn from_json(bytes: &[u8]) -> Result<Self, ReferenceLoadError> {
+        serde_json::from_slice(bytes).map_err(|error| {
+            ReferenceLoadError::InvalidMetadata(error.to_string())
+        })
+    }
+}
+
+impl Reference {
+    /// Get the metadata (creation time, build configuration and source files) of `Reference`.
+    pub fn get_metadata(&self) -> &ReferenceMetadata {
+        &self.metadata
+    }
+}
+
+/// Length and CRC32 of the bytes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) struct Digest {
+    pub length: u64,
+    pub crc32: u32,
+}
+
+impl Digest {
+    pub fn of(bytes: &[u8]) -> Self {
+        Self { length: bytes.len() as u64, crc32: crc32fast::hash(bytes) }
+    }
+    /// Compare the digest of the bytes read with the expected one (`self`).
+    pub fn check(&self, digest: Digest) -> Result<(), ReferenceLoadError> {
+        self.check_length(digest.length)?;
+        if digest.crc32 != self.crc32 {
+            return Err(ReferenceLoadError::ChecksumMismatch {
+                expected: self.crc32,
+                actual: digest.crc32,
+            })
+        }
+        Ok(())
+    }
+    pub fn check_length(&self, length: u64) -> Result<(), ReferenceLoadError> {
+        let expected = self.length;
+        if length < expected {
+            Err(ReferenceLoadError::Truncated { expected, actual: length })
+        } else if length > expected {
+            Err(ReferenceLoadError::LengthMismatch { expected, actual: length })
+        } else {
+            Ok(())
+        }
+    }
+}
+
+/// Writer computing the digest of the bytes written.
+pub(super) struct DigestWriter<W: Write> {
+    inner: W,
+    hasher: crc32fast::Hasher,
+    length: u64,
+}
+
+impl<W: Write> DigestWriter<W> {
+    pub fn new(inner: W) -> Self {
+        Self { inner, hasher: crc32fast::Hasher::new(), length: 0 }
+    }
+    pub fn digest(&self) -> Digest {
+        Digest { length: self.length, crc32: self.hasher.clone().finalize() }
+    }
+}
+
+impl<W: Write> Write for DigestWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        let written = self.inner.write(buf)?;
+        self.hasher.update(&buf[..written]);
+        self.length += written as u64;
+        Ok(written)
+    }
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+/// Reader computing the digest of the bytes read.
+pub(super) struct DigestReader<R: Read> {
+    inner: R,
+    hasher: crc32fast::Hasher,
+    length: u64,
+}
+
+impl<R: Read> DigestReader<R> {
+    pub fn new(inner: R) -> Self {
+        Self { inner, hasher: crc32fast::Hasher::new(), length: 0 }
+    }
+    pub fn digest(&self) -> Digest {
+        Digest { length: self.length, crc32: self.hasher.clone().finalize() }
+    }
+}
+
+impl<R: Read> Read for DigestReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let read = self.inner.read(buf)?;
+        self.hasher.update(&buf[..read]);
+        self.length += read as u64;
+        Ok(read)
+    }
+}
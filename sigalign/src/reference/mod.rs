@@ -1,20 +1,35 @@
 use sigalign_core::reference::{
     Reference as RawReference,
+    SequenceStorage as _,
+    SequenceBuffer as _,
     extensions::EstimateSize as _,
 };
 use sigalign_impl::{
     pattern_index::dynamic_lfi::DynamicLfi,
     sequence_storage::in_memory::{InMemoryStorage, InMemoryBuffer},
 };
+use sigalign_utils::sequence_manipulation::reverse_complementary::reverse_complement_of_dna_sequence;
+use crate::aligner::Strand;
 use crate::results::{
     QueryAlignment, TargetAlignment, LabeledQueryAlignment, LabeledTargetAlignment,
+    StrandedQueryAlignment, LabeledStrandedQueryAlignment,
+    Alignment, PairwiseTextOption,
 };
 
 mod io;
 pub use io::ReferenceLoadError;
 mod debug;
 mod builder;
-pub use builder::{ReferenceBuilder, ReferenceBuildError};
+pub use builder::{ReferenceBuilder, ReferenceBuildError, PatternIndexInfo, PROTEIN_ALPHABET};
+pub use sigalign_impl::pattern_index::dynamic_lfi::BwtBlockSize;
+mod target_subset;
+pub use target_subset::{TargetSubset, TargetSubsetError};
+mod append;
+mod target_exclusion;
+mod metadata;
+pub use metadata::{ReferenceMetadata, BuildConfiguration, SourceFile};
+mod sharded;
+pub use sharded::{ShardedReference, ShardedReferenceBuilder, ShardedReferenceError, ShardLoading};
 
 pub type DefaultSequenceBuffer = InMemoryBuffer;
 /// A database for multiple target sequences.
@@ -22,6 +37,7 @@ pub type DefaultSequenceBuffer = InMemoryBuffer;
 pub struct Reference {
     raw_reference: RawReference<DynamicLfi, InMemoryStorage>,
     full_sorted_target_indices: Vec<u32>,
+    metadata: ReferenceMetadata,
 }
 
 impl AsRef<RawReference<DynamicLfi, InMemoryStorage>> for Reference {
@@ -40,24 +56,51 @@ impl Reference {
     pub fn get_label(&self, target_index: u32) -> Option<String> {
         self.as_ref().get_sequence_storage().get_label_safely(target_index)
     }
+    /// Get the length of the target. None if the target index is out of range.
+    pub fn get_sequence_length(&self, target_index: u32) -> Option<u32> {
+        if target_index >= self.get_num_targets() {
+            return None
+        }
+        let mut sequence_buffer = Self::get_sequence_buffer();
+        self.fill_sequence_buffer(target_index, &mut sequence_buffer);
+        Some(sequence_buffer.buffered_sequence().len() as u32)
+    }
     /// Get the number of targets.
     pub fn get_num_targets(&self) -> u32 {
         self.as_ref().num_targets()
     }
     /// Get the total length of all targets (in base pairs).
-    pub fn get_total_length(&self) -> u32 {
+    pub fn get_total_length(&self) -> u64 {
         self.as_ref().get_sequence_storage().get_total_length()
     }
     /// Get estimated size in bytes. (This is an estimate, not the exact size.)
     pub fn get_estimated_size_in_bytes(&self) -> usize {
         self.as_ref().serialized_size()
     }
+    /// Get the structure of the pattern index, to tune the index size against the speed.
+    pub fn get_pattern_index_info(&self) -> PatternIndexInfo {
+        let pattern_index = self.as_ref().get_pattern_index();
+        let info = pattern_index.get_info();
+        PatternIndexInfo {
+            bits_per_character: info.bits_per_character,
+            num_characters: info.num_characters,
+            bwt_block_size: info.bwt_block_size,
+            suffix_array_sampling_ratio: info.suffix_array_sampling_ratio,
+            lookup_table_kmer_size: info.lookup_table_kmer_size,
+            size_in_bytes: pattern_index.serialized_size(),
+        }
+    }
 
     /* Access Resources */
     /// Get sequence buffer for alignment.
     pub fn get_sequence_buffer() -> InMemoryBuffer {
         InMemoryBuffer::new()
     }
+    /// Fill the sequence buffer with the target sequence.
+    /// The target index must be in range.
+    pub(crate) fn fill_sequence_buffer(&self, target_index: u32, sequence_buffer: &mut InMemoryBuffer) {
+        self.as_ref().get_sequence_storage().fill_buffer(target_index, sequence_buffer);
+    }
     /// Get the full sorted target indices
     pub fn get_full_sorted_target_indices(&self) -> &[u32] {
         &self.full_sorted_target_indices
@@ -71,6 +114,13 @@ impl Reference {
         }).collect();
         LabeledQueryAlignment(labeled_target_alignments)
     }
+    /// Label the stranded query alignment.
+    pub fn label_stranded_query_alignment(&self, stranded_query_alignment: StrandedQueryAlignment) -> LabeledStrandedQueryAlignment {
+        LabeledStrandedQueryAlignment {
+            forward: self.label_query_alignment(stranded_query_alignment.forward),
+            reverse: self.label_query_alignment(stranded_query_alignment.reverse),
+        }
+    }
     /// Label the target alignment.
     #[inline]
     pub fn label_target_alignment(&self, target_alignment: TargetAlignment) -> LabeledTargetAlignment {
@@ -82,6 +132,37 @@ impl Reference {
             alignments: target_alignment.alignments,
         }
     }
+    /// Render the alignment to the target as pairwise text.
+    ///  - None if the target index is out of range, or the alignment is not in range of the query and target.
+    ///  - `query`: The forward query.
+    ///  - `strand`: `Strand::Reverse` if the alignment is of the reverse strand (following the convention of `StrandedQueryAlignment`).
+    ///    Then, the reverse complement of the query is rendered with its own positions.
+    pub fn alignment_to_pairwise_text(
+        &self,
+        query: &[u8],
+        target_index: u32,
+        alignment: &Alignment,
+        strand: Strand,
+        option: &PairwiseTextOption,
+    ) -> Option<String> {
+        if target_index >= self.get_num_targets() {
+            return None
+        }
+        let mut sequence_buffer = Self::get_sequence_buffer();
+        self.fill_sequence_buffer(target_index, &mut sequence_buffer);
+        let target = sequence_buffer.buffered_sequence();
+
+        if strand.is_reverse_orientation() {
+            let reverse_complement = reverse_complement_of_dna_sequence(query);
+            let query_length = query.len() as u32;
+            let (start, end) = alignment.position.query;
+            let mut alignment = alignment.clone();
+            alignment.position.query = (query_length.checked_sub(end)?, query_length.checked_sub(start)?);
+            alignment.to_pairwise_text(&reverse_complement, target, option)
+        } else {
+            alignment.to_pairwise_text(query, target, option)
+        }
+    }
 }
 
 impl Into<RawReference<DynamicLfi, InMemoryStorage>> for Reference {
@@ -95,6 +176,7 @@ impl From<RawReference<DynamicLfi, InMemoryStorage>> for Reference {
         Self {
             raw_reference,
             full_sorted_target_indices,
+            metadata: ReferenceMetadata::default(),
         }
     }
 }
@@ -0,0 +1,322 @@
+use std::{
+    fs::File,
+    io::{BufRead, BufReader, BufWriter, Write},
+    path::{Path, PathBuf},
+};
+use thiserror::Error;
+
+use sigalign_utils::sequence_reader::{
+    SeqRecord as _,
+    IdRecord as _,
+    fasta::FastaReader,
+};
+use super::{
+    Reference,
+    ReferenceBuilder,
+    ReferenceBuildError,
+    ReferenceLoadError,
+};
+
+const MANIFEST_HEADER: &str = "SIGALIGN_SHARDED_REFERENCE";
+const MANIFEST_VERSION: &str = "1";
+
+/// A set of `Reference`s (shards) split from one FASTA, aligned as if it were one `Reference`.
+///
+/// - Generated from `ShardedReferenceBuilder`, and saved as a manifest file with shard files.
+/// - The target indices are global: the index of the record in the original FASTA.
+/// - Shards are kept in memory or loaded when needed, as defined by `ShardLoading`.
+/// - Aligned by `Aligner::align_sharded` and `Aligner::align_sharded_batch`.
+#[derive(Clone)]
+pub struct ShardedReference {
+    shards: Vec<Shard>,
+    shard_loading: ShardLoading,
+}
+
+/// How the shards of `ShardedReference` are kept.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ShardLoading {
+    /// All shards are loaded once and kept in memory.
+    #[default]
+    Resident,
+    /// Each shard is loaded (with the sequences memory-mapped) when it is aligned, and released right after.
+    ///  - Only one shard is in memory at once.
+    ///  - `Aligner::align_sharded` reloads every shard for each query.
+    ///  - Use `Aligner::align_sharded_batch` to load each shard once for multiple queries.
+    Lazy,
+}
+
+#[derive(Clone)]
+struct Shard {
+    file_path: PathBuf,
+    target_offset: u32,
+    num_targets: u32,
+    total_length: u64,
+    reference: Option<Reference>,
+}
+
+/// Builder for `ShardedReference`.
+///
+/// - Default configuration:
+///   - Same as `ReferenceBuilder` for the bases of each shard.
+///   - Maximum shard length: 1 Gbp
+///     - A record longer than this is in a shard of its own.
+///   - Shard loading: `ShardLoading::Resident`
+#[derive(Clone)]
+pub struct ShardedReferenceBuilder {
+    uppercase: bool,
+    to_ignore_bases: Vec<u8>,
+    max_shard_length: u32,
+    shard_loading: ShardLoading,
+}
+
+/// Error for building, loading and aligning `ShardedReference`.
+#[derive(Debug, Error)]
+pub enum ShardedReferenceError {
+    #[error(transparent)]
+    BuildError(#[from] ReferenceBuildError),
+    #[error(transparent)]
+    LoadError(#[from] ReferenceLoadError),
+    #[error("Invalid manifest of sharded reference: {0}")]
+    InvalidManifest(String),
+    #[error(transparent)]
+    IoError(#[from] std::io::Error),
+}
+
+impl ShardedReferenceBuilder {
+    /// Make a new `ShardedReferenceBuilder`.
+    pub fn new() -> Self {
+        Self {
+            uppercase: true,
+            to_ignore_bases: Vec::new(),
+            max_shard_length: 1_000_000_000,
+            shard_loading: ShardLoading::default(),
+        }
+    }
+    /* Configuration */
+    /// Set all letters to uppercase when building.
+    pub fn set_uppercase(mut self, uppercase: bool) -> Self {
+        self.uppercase = uppercase;
+        self
+    }
+    /// Set the base that never match to any other bases.
+    pub fn ignore_base(mut self, base: u8) -> Self {
+        self.to_ignore_bases.push(base);
+        self
+    }
+    /// Set the bases that never match to any other bases (multiple).
+    pub fn ignore_bases(mut self, bases: &[u8]) -> Self {
+        self.to_ignore_bases.extend_from_slice(bases);
+        self
+    }
+    /// Set the maximum total length of targets in one shard.
+    pub fn set_max_shard_length(mut self, max_shard_length: u32) -> Self {
+        self.max_shard_length = max_shard_length;
+        self
+    }
+    /// Set how the shards of built `ShardedReference` are kept.
+    pub fn set_shard_loading(mut self, shard_loading: ShardLoading) -> Self {
+        self.shard_loading = shard_loading;
+        self
+    }
+
+    /// Build shards from the FASTA file, and save them with the manifest.
+    ///  - Shard files are written next to the manifest as `{manifest_file_name}.{shard_index}`.
+    ///  - Records are read one by one, so only one shard is in memory while building.
+    pub fn build_from_fasta_file<P1, P2>(
+        self,
+        fasta_file_path: P1,
+        manifest_file_path: P2,
+    ) -> Result<ShardedReference, ShardedReferenceError> where
+        P1: AsRef<Path>,
+        P2: AsRef<Path>,
+    {
+        let manifest_file_path = manifest_file_path.as_ref();
+        let mut fasta_reader = FastaReader::from_path(fasta_file_path)?;
+        let mut shards = Vec::new();
+
+        let mut builder = self.new_reference_builder();
+        let mut current_length: u64 = 0;
+        let mut label = String::new();
+        let mut sequence = Vec::new();
+        while let Some(mut record) = fasta_reader.next() {
+            label.clear();
+            sequence.clear();
+            record.extend_id_string(&mut label).map_err(|_| ReferenceBuildError::InvalidSequence(
+                "Invalid UTF-8 in the ID of FASTA record".to_string()
+            ))?;
+            record.extend_seq_buf(&mut sequence);
+
+            if current_length != 0 && current_length + sequence.len() as u64 > self.max_shard_length as u64 {
+                let filled_builder = std::mem::replace(&mut builder, self.new_reference_builder());
+                shards.push(self.save_shard(filled_builder, manifest_file_path, &shards)?);
+                current_length = 0;
+            }
+            current_length += sequence.len() as u64;
+            builder = builder.add_target(&label, &sequence);
+        }
+        if current_length != 0 || shards.is_empty() {
+            shards.push(self.save_shard(builder, manifest_file_path, &shards)?);
+        }
+
+        let sharded_reference = ShardedReference {
+            shards,
+            shard_loading: self.shard_loading,
+        };
+        sharded_reference.write_manifest(manifest_file_path)?;
+        Ok(sharded_reference)
+    }
+    fn new_reference_builder(&self) -> ReferenceBuilder {
+        ReferenceBuilder::new()
+            .set_uppercase(self.uppercase)
+            .ignore_bases(&self.to_ignore_bases)
+    }
+    fn save_shard(
+        &self,
+        builder: ReferenceBuilder,
+        manifest_file_path: &Path,
+        previous_shards: &[Shard],
+    ) -> Result<Shard, ShardedReferenceError> {
+        let reference = builder.build()?;
+        let file_path = shard_file_path(manifest_file_path, previous_shards.len());
+        reference.save_to_seekable(BufWriter::new(File::create(&file_path)?))?;
+
+        let target_offset = previous_shards.last().map(|shard| {
+            shard.target_offset + shard.num_targets
+        }).unwrap_or(0);
+        Ok(Shard {
+            file_path,
+            target_offset,
+            num_targets: reference.get_num_targets(),
+            total_length: reference.get_total_length(),
+            reference: match self.shard_loading {
+                ShardLoading::Resident => Some(reference),
+                ShardLoading::Lazy => None,
+            },
+        })
+    }
+}
+
+impl Default for ShardedReferenceBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ShardedReference {
+    /// Load `ShardedReference` from the manifest file written by `ShardedReferenceBuilder`.
+    ///  - Shard files are expected in the same directory as the manifest.
+    pub fn load_from_manifest<P: AsRef<Path>>(
+        manifest_file_path: P,
+        shard_loading: ShardLoading,
+    ) -> Result<Self, ShardedReferenceError> {
+        let manifest_file_path = manifest_file_path.as_ref();
+        let directory = manifest_file_path.parent().unwrap_or(Path::new(""));
+        let mut lines = BufReader::new(File::open(manifest_file_path)?).lines();
+
+        match lines.next().transpose()? {
+            Some(header) if header == format!("{}\t{}", MANIFEST_HEADER, MANIFEST_VERSION) => {},
+            _ => return Err(ShardedReferenceError::InvalidManifest("unknown header".to_string())),
+        }
+        let mut shards: Vec<Shard> = Vec::new();
+        for line in lines {
+            let line = line?;
+            if line.is_empty() {
+                continue
+            }
+            let invalid_line = || ShardedReferenceError::InvalidManifest(line.clone());
+            let fields: Vec<&str> = line.split('\t').collect();
+            let [file_name, num_targets, total_length] = fields[..] else {
+                return Err(invalid_line());
+            };
+            let target_offset = shards.last().map(|shard| {
+                shard.target_offset + shard.num_targets
+            }).unwrap_or(0);
+            shards.push(Shard {
+                file_path: directory.join(file_name),
+                target_offset,
+                num_targets: num_targets.parse().map_err(|_| invalid_line())?,
+                total_length: total_length.parse().map_err(|_| invalid_line())?,
+                reference: None,
+            });
+        }
+
+        let mut sharded_reference = Self { shards, shard_loading: ShardLoading::Lazy };
+        sharded_reference.set_shard_loading(shard_loading)?;
+        Ok(sharded_reference)
+    }
+    /// Change how the shards are kept.
+    ///  - Changing to `Resident` loads all shards.
+    pub fn set_shard_loading(&mut self, shard_loading: ShardLoading) -> Result<(), ShardedReferenceError> {
+        for shard in self.shards.iter_mut() {
+            match shard_loading {
+                ShardLoading::Resident => if shard.reference.is_none() {
+                    shard.reference = Some(shard.load()?);
+                },
+                ShardLoading::Lazy => shard.reference = None,
+            }
+        }
+        self.shard_loading = shard_loading;
+        Ok(())
+    }
+    pub fn get_shard_loading(&self) -> ShardLoading {
+        self.shard_loading
+    }
+    /// Get the number of shards.
+    pub fn get_num_shards(&self) -> usize {
+        self.shards.len()
+    }
+    /// Get the number of targets of all shards.
+    pub fn get_num_targets(&self) -> u32 {
+        self.shards.iter().map(|shard| shard.num_targets).sum()
+    }
+    /// Get the total length of all targets of all shards (in base pairs).
+    pub fn get_total_length(&self) -> u64 {
+        self.shards.iter().map(|shard| shard.total_length).sum()
+    }
+    /// Get the range of global target indices in the shard. None if the shard index is out of range.
+    pub fn get_target_range_of_shard(&self, shard_index: usize) -> Option<std::ops::Range<u32>> {
+        self.shards.get(shard_index).map(|shard| {
+            shard.target_offset..shard.target_offset + shard.num_targets
+        })
+    }
+    /// Run the function with each shard and the global index of its first target.
+    pub(crate) fn for_each_shard<F>(&self, mut f: F) -> Result<(), ShardedReferenceError> where
+        F: FnMut(&Reference, u32),
+    {
+        for shard in &self.shards {
+            match &shard.reference {
+                Some(reference) => f(reference, shard.target_offset),
+                None => f(&shard.load()?, shard.target_offset),
+            }
+        }
+        Ok(())
+    }
+
+    fn write_manifest(&self, manifest_file_path: &Path) -> Result<(), std::io::Error> {
+        let mut writer = BufWriter::new(File::create(manifest_file_path)?);
+        writeln!(writer, "{}\t{}", MANIFEST_HEADER, MANIFEST_VERSION)?;
+        for shard in &self.shards {
+            let file_name = shard.file_path.file_name().unwrap_or_default().to_string_lossy();
+            writeln!(writer, "{}\t{}\t{}", file_name, shard.num_targets, shard.total_length)?;
+        }
+        writer.flush()
+    }
+}
+
+impl Shard {
+    fn load(&self) -> Result<Reference, ShardedReferenceError> {
+        let reference = Reference::load_with_mapped_sequences(&self.file_path)?;
+        if reference.get_num_targets() != self.num_targets {
+            return Err(ShardedReferenceError::InvalidManifest(format!(
+                "the number of targets in {} is different", self.file_path.display(),
+            )));
+        }
+        Ok(reference)
+    }
+}
+
+fn shard_file_path(manifest_file_path: &Path, shard_index: usize) -> PathBuf {
+    let mut file_path = manifest_file_path.as_os_str().to_owned();
+    file_path.push(format!(".{}", shard_index));
+    PathBuf::from(file_path)
+}
@@ -0,0 +1,56 @@
+use super::{
+    Reference,
+    TargetSubsetError,
+};
+
+impl Reference {
+    /// Exclude the targets from the alignment, without re-indexing.
+    ///  - Excluded targets are not aligned by the methods aligning to the whole `Reference` (e.g., `Aligner::align`),
+    ///    and not included in the `TargetSubset` made after the exclusion.
+    ///  - The indices and labels of all targets are not changed.
+    ///  - The exclusions are kept when saved by `save_to`.
+    pub fn exclude_targets(&mut self, target_indices: &[u32]) -> Result<(), TargetSubsetError> {
+        self.check_target_indices(target_indices)?;
+        let mut to_exclude = target_indices.to_vec();
+        to_exclude.sort_unstable();
+        self.full_sorted_target_indices.retain(|index| to_exclude.binary_search(index).is_err());
+        Ok(())
+    }
+    /// Include the excluded targets again.
+    pub fn include_targets(&mut self, target_indices: &[u32]) -> Result<(), TargetSubsetError> {
+        self.check_target_indices(target_indices)?;
+        self.full_sorted_target_indices.extend_from_slice(target_indices);
+        self.full_sorted_target_indices.sort_unstable();
+        self.full_sorted_target_indices.dedup();
+        Ok(())
+    }
+    /// Include all excluded targets.
+    pub fn include_all_targets(&mut self) {
+        self.full_sorted_target_indices = (0..self.get_num_targets()).collect();
+    }
+    /// Get the sorted indices of excluded targets.
+    pub fn get_excluded_target_indices(&self) -> Vec<u32> {
+        let mut included = self.full_sorted_target_indices.iter().peekable();
+        (0..self.get_num_targets()).filter(|index| {
+            if included.peek() == Some(&index) {
+                included.next();
+                false
+            } else {
+                true
+            }
+        }).collect()
+    }
+    /// Check if the target is excluded.
+    pub fn is_excluded(&self, target_index: u32) -> bool {
+        target_index < self.get_num_targets()
+        && self.full_sorted_target_indices.binary_search(&target_index).is_err()
+    }
+
+    fn check_target_indices(&self, target_indices: &[u32]) -> Result<(), TargetSubsetError> {
+        let num_targets = self.get_num_targets();
+        match target_indices.iter().find(|&&index| index >= num_targets) {
+            Some(&index) => Err(TargetSubsetError::IndexOutOfRange { index, num_targets }),
+            None => Ok(()),
+        }
+    }
+}
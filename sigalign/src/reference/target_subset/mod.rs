@@ -0,0 +1,85 @@
+use std::collections::HashMap;
+use thiserror::Error;
+
+use super::Reference;
+
+/// A subset of targets in `Reference` to restrict the alignment.
+///
+/// - Generated from `Reference` by target indices or labels.
+/// - Indices are validated, sorted, and deduplicated once when the subset is made.
+///   - Targets excluded from `Reference` (`Reference::exclude_targets`) are left out.
+/// - Can be reused for multiple alignments with the `Reference` it was made from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TargetSubset {
+    sorted_target_indices: Vec<u32>,
+}
+
+/// Error for making `TargetSubset`.
+#[derive(Debug, Error)]
+pub enum TargetSubsetError {
+    #[error("Target index {index} is out of range. The number of targets is {num_targets}.")]
+    IndexOutOfRange {
+        index: u32,
+        num_targets: u32,
+    },
+    #[error("Target labeled '{0}' does not exist in reference.")]
+    UnknownLabel(String),
+}
+
+impl TargetSubset {
+    /// Get the sorted and deduplicated target indices.
+    pub fn get_sorted_target_indices(&self) -> &[u32] {
+        &self.sorted_target_indices
+    }
+    /// Get the number of targets in the subset.
+    pub fn get_num_targets(&self) -> u32 {
+        self.sorted_target_indices.len() as u32
+    }
+    /// Check if the target is in the subset.
+    pub fn contains(&self, target_index: u32) -> bool {
+        self.sorted_target_indices.binary_search(&target_index).is_ok()
+    }
+}
+
+impl Reference {
+    /// Make a `TargetSubset` from the target indices.
+    /// The indices do not need to be sorted or unique.
+    pub fn get_target_subset_by_indices(
+        &self,
+        target_indices: &[u32],
+    ) -> Result<TargetSubset, TargetSubsetError> {
+        let num_targets = self.get_num_targets();
+        if let Some(&index) = target_indices.iter().find(|&&index| index >= num_targets) {
+            return Err(TargetSubsetError::IndexOutOfRange { index, num_targets });
+        }
+        let mut sorted_target_indices = target_indices.to_vec();
+        sorted_target_indices.sort_unstable();
+        sorted_target_indices.dedup();
+        sorted_target_indices.retain(|&index| !self.is_excluded(index));
+        Ok(TargetSubset { sorted_target_indices })
+    }
+    /// Make a `TargetSubset` from the target labels.
+    /// If the same label is assigned to multiple targets, all of them are included.
+    pub fn get_target_subset_by_labels<T: AsRef<str>>(
+        &self,
+        target_labels: &[T],
+    ) -> Result<TargetSubset, TargetSubsetError> {
+        let mut indices_by_label: HashMap<String, Vec<u32>> = HashMap::new();
+        for target_index in 0..self.get_num_targets() {
+            let label = self.as_ref().label_of_target_unchecked(target_index);
+            indices_by_label.entry(label).or_default().push(target_index);
+        }
+
+        let mut sorted_target_indices = Vec::with_capacity(target_labels.len());
+        for label in target_labels {
+            match indices_by_label.get(label.as_ref()) {
+                Some(indices) => sorted_target_indices.extend_from_slice(indices),
+                None => return Err(TargetSubsetError::UnknownLabel(label.as_ref().to_string())),
+            }
+        }
+        sorted_target_indices.sort_unstable();
+        sorted_target_indices.dedup();
+        sorted_target_indices.retain(|&index| !self.is_excluded(index));
+        Ok(TargetSubset { sorted_target_indices })
+    }
+}
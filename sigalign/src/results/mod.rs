@@ -49,12 +49,41 @@ pub use sigalign_core::results::{
     AlignmentPosition,
     AlignmentOperations,
     AlignmentOperation,
+    PairwiseTextOption,
 };
 // Export labeled results
 pub use labeled::{
     LabeledQueryAlignment,
     LabeledTargetAlignment,
 };
+// Export stranded results
+mod stranded;
+pub use stranded::{
+    StrandedQueryAlignment,
+    LabeledStrandedQueryAlignment,
+    StrandedTargetAlignment,
+    StrandedAlignment,
+};
+// Export translated results
+mod translated;
+pub use translated::{
+    TranslatedQueryAlignment,
+    TranslatedTargetAlignment,
+    TranslatedAlignment,
+};
 
 mod to_json;
+mod to_sam;
+pub use to_sam::{
+    SamWriter,
+    SamCigarStyle,
+};
+mod to_paf;
+mod to_blast_tabular;
+pub use to_blast_tabular::{
+    BlastTabularFormat,
+    BlastTabularFormatError,
+    BlastColumn,
+    BitscoreModel,
+};
 mod count_alignments;
@@ -0,0 +1,91 @@
+use serde::{Deserialize, Serialize};
+
+use crate::aligner::Strand;
+use super::{
+    QueryAlignment,
+    LabeledQueryAlignment,
+    Alignment,
+};
+
+/// Alignments of the query separated by the strand.
+///
+/// - `forward`: Alignments of the query as is.
+/// - `reverse`: Alignments of the reverse complement of the query.
+///    - `position.query` is converted to the coordinates of the forward query.
+///    - `operations` follow the direction of the target (i.e., the reverse complement of the query).
+///
+/// Each container is a plain `QueryAlignment`, so the methods of `QueryAlignment` (and `LabeledQueryAlignment`)
+/// taking the `Strand` (e.g., `write_as_paf`) are used for each strand as they are.
+/// To keep the orientation on each alignment, use `into_stranded_target_alignments`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "short_key", serde(rename = "StrQryAln"))]
+pub struct StrandedQueryAlignment {
+    #[cfg_attr(feature = "short_key", serde(rename = "fwd"))]
+    pub forward: QueryAlignment,
+    #[cfg_attr(feature = "short_key", serde(rename = "rev"))]
+    pub reverse: QueryAlignment,
+}
+
+/// `StrandedQueryAlignment` with the labels of targets.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "short_key", serde(rename = "LblStrQryAln"))]
+pub struct LabeledStrandedQueryAlignment {
+    #[cfg_attr(feature = "short_key", serde(rename = "fwd"))]
+    pub forward: LabeledQueryAlignment,
+    #[cfg_attr(feature = "short_key", serde(rename = "rev"))]
+    pub reverse: LabeledQueryAlignment,
+}
+
+/// Alignments of both strands to a target.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "short_key", serde(rename = "StrTgtAln"))]
+pub struct StrandedTargetAlignment {
+    #[cfg_attr(feature = "short_key", serde(rename = "idx"))]
+    pub index: u32,
+    #[cfg_attr(feature = "short_key", serde(rename = "aln"))]
+    pub alignments: Vec<StrandedAlignment>,
+}
+
+/// Alignment with the strand of the query.
+///
+/// - `strand`: `Forward` for the query and `Reverse` for the reverse complement of the query.
+/// - `alignment`: The same as the alignment in `StrandedQueryAlignment`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "short_key", serde(rename = "StrAln"))]
+pub struct StrandedAlignment {
+    #[cfg_attr(feature = "short_key", serde(rename = "str"))]
+    pub strand: Strand,
+    #[cfg_attr(feature = "short_key", serde(rename = "aln"))]
+    pub alignment: Alignment,
+}
+
+impl StrandedQueryAlignment {
+    pub fn count_alignments(&self) -> usize {
+        self.forward.count_alignments() + self.reverse.count_alignments()
+    }
+    /// Merge the alignments of both strands by target, keeping the strand on each alignment.
+    ///  - Targets are sorted by index, and the forward alignments come first in each target.
+    pub fn into_stranded_target_alignments(self) -> Vec<StrandedTargetAlignment> {
+        let mut target_alignments: Vec<StrandedTargetAlignment> = Vec::new();
+        for (strand, query_alignment) in [(Strand::Forward, self.forward), (Strand::Reverse, self.reverse)] {
+            for target_alignment in query_alignment.0 {
+                let alignments = target_alignment.alignments.into_iter().map(|alignment| {
+                    StrandedAlignment { strand, alignment }
+                });
+                match target_alignments.binary_search_by_key(&target_alignment.index, |x| x.index) {
+                    Ok(position) => target_alignments[position].alignments.extend(alignments),
+                    Err(position) => target_alignments.insert(position, StrandedTargetAlignment {
+                        index: target_alignment.index,
+                        alignments: alignments.collect(),
+                    }),
+                }
+            }
+        }
+        target_alignments
+    }
+}
+impl LabeledStrandedQueryAlignment {
+    pub fn count_alignments(&self) -> usize {
+        self.forward.count_alignments() + self.reverse.count_alignments()
+    }
+}
@@ -0,0 +1,348 @@
+use std::io::{Write, Error};
+use std::str::FromStr;
+use thiserror::Error;
+
+use crate::{
+    aligner::Strand,
+    reference::Reference,
+};
+use super::{
+    LabeledQueryAlignment,
+    LabeledStrandedQueryAlignment,
+    Alignment,
+    AlignmentOperation,
+    to_sam::reference_name,
+};
+
+/// Column of the BLAST tabular format (`-outfmt 6`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BlastColumn {
+    /// Query label
+    Qseqid,
+    /// Target label
+    Sseqid,
+    /// Percentage of identical matches
+    Pident,
+    /// Alignment length
+    Length,
+    /// Number of mismatches
+    Mismatch,
+    /// Number of gap openings
+    Gapopen,
+    /// Start of alignment in query (1-based)
+    Qstart,
+    /// End of alignment in query (1-based, inclusive)
+    Qend,
+    /// Start of alignment in target (1-based). Greater than `Send` in the reverse strand.
+    Sstart,
+    /// End of alignment in target (1-based, inclusive)
+    Send,
+    /// Expect value
+    Evalue,
+    /// Bit score
+    Bitscore,
+    /// Query length
+    Qlen,
+    /// Target length
+    Slen,
+    /// Number of identical matches
+    Nident,
+    /// Total number of gaps
+    Gaps,
+}
+
+/// Error for parsing the BLAST tabular format.
+#[derive(Debug, Error)]
+pub enum BlastTabularFormatError {
+    #[error("Only the tabular format (6) is supported, but '{0}' is given.")]
+    UnsupportedFormat(String),
+    #[error("Unknown column '{0}'.")]
+    UnknownColumn(String),
+}
+
+/// Model to convert the alignment into the raw score, bit score and E-value.
+///
+/// - Raw score: `match_score` × (number of matches) − penalty
+/// - Bit score: (λ × raw score − ln K) / ln 2
+/// - E-value: (query length) × (total length of reference) × 2^(−bit score)
+///
+/// The default values (match score 1, λ 1.28, K 0.46) are of the BLASTN megablast.
+/// They are only approximate for the penalties of SigAlign, so set the values for the penalties in use.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BitscoreModel {
+    pub match_score: f64,
+    pub lambda: f64,
+    pub k: f64,
+}
+
+/// Format of the BLAST tabular output.
+///
+/// - The default columns are the standard 12 columns of `-outfmt 6`
+///   (`qseqid sseqid pident length mismatch gapopen qstart qend sstart send evalue bitscore`).
+/// - The columns can be selected as `-outfmt` of BLAST (e.g., `"6 qseqid sseqid evalue"`).
+#[derive(Debug, Clone, PartialEq)]
+pub struct BlastTabularFormat {
+    columns: Vec<BlastColumn>,
+    bitscore_model: BitscoreModel,
+}
+
+impl FromStr for BlastColumn {
+    type Err = BlastTabularFormatError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let column = match s {
+            "qseqid" => Self::Qseqid,
+            "sseqid" => Self::Sseqid,
+            "pident" => Self::Pident,
+            "length" => Self::Length,
+            "mismatch" => Self::Mismatch,
+            "gapopen" => Self::Gapopen,
+            "qstart" => Self::Qstart,
+            "qend" => Self::Qend,
+            "sstart" => Self::Sstart,
+            "send" => Self::Send,
+            "evalue" => Self::Evalue,
+            "bitscore" => Self::Bitscore,
+            "qlen" => Self::Qlen,
+            "slen" => Self::Slen,
+            "nident" => Self::Nident,
+            "gaps" => Self::Gaps,
+            _ => return Err(BlastTabularFormatError::UnknownColumn(s.to_string())),
+        };
+        Ok(column)
+    }
+}
+
+impl Default for BitscoreModel {
+    fn default() -> Self {
+        Self {
+            match_score: 1.0,
+            lambda: 1.28,
+            k: 0.46,
+        }
+    }
+}
+
+impl BitscoreModel {
+    pub fn new(match_score: f64, lambda: f64, k: f64) -> Self {
+        Self { match_score, lambda, k }
+    }
+    #[inline]
+    fn bitscore(&self, match_count: u32, penalty: u32) -> f64 {
+        let raw_score = self.match_score * match_count as f64 - penalty as f64;
+        (self.lambda * raw_score - self.k.ln()) / std::f64::consts::LN_2
+    }
+    #[inline]
+    fn evalue(&self, bitscore: f64, query_length: u32, total_target_length: u64) -> f64 {
+        query_length as f64 * total_target_length as f64 * (-bitscore).exp2()
+    }
+}
+
+impl Default for BlastTabularFormat {
+    fn default() -> Self {
+        Self {
+            columns: vec![
+                BlastColumn::Qseqid,
+                BlastColumn::Sseqid,
+                BlastColumn::Pident,
+                BlastColumn::Length,
+                BlastColumn::Mismatch,
+                BlastColumn::Gapopen,
+                BlastColumn::Qstart,
+                BlastColumn::Qend,
+                BlastColumn::Sstart,
+                BlastColumn::Send,
+                BlastColumn::Evalue,
+                BlastColumn::Bitscore,
+            ],
+            bitscore_model: BitscoreModel::default(),
+        }
+    }
+}
+
+impl BlastTabularFormat {
+    /// Make a format with the columns and the default `BitscoreModel`.
+    pub fn new(columns: Vec<BlastColumn>) -> Self {
+        Self {
+            columns,
+            bitscore_model: BitscoreModel::default(),
+        }
+    }
+    /// Parse the `-outfmt` string of BLAST (e.g., `"6 qseqid sseqid evalue"`).
+    /// If only `"6"` is given, the default columns are used.
+    pub fn from_outfmt(outfmt: &str) -> Result<Self, BlastTabularFormatError> {
+        let mut words = outfmt.split_whitespace();
+        match words.next() {
+            Some("6") => {},
+            other => return Err(BlastTabularFormatError::UnsupportedFormat(
+                other.unwrap_or_default().to_string()
+            )),
+        }
+        let columns = words.map(BlastColumn::from_str).collect::<Result<Vec<_>, _>>()?;
+        if columns.is_empty() {
+            Ok(Self::default())
+        } else {
+            Ok(Self::new(columns))
+        }
+    }
+    pub fn set_bitscore_model(&mut self, bitscore_model: BitscoreModel) {
+        self.bitscore_model = bitscore_model;
+    }
+    pub fn get_columns(&self) -> &[BlastColumn] {
+        &self.columns
+    }
+    pub fn get_bitscore_model(&self) -> &BitscoreModel {
+        &self.bitscore_model
+    }
+}
+
+impl LabeledQueryAlignment {
+    /// Convert to BLAST tabular lines, one line per alignment.
+    /// - `strand`: `Strand::Reverse` if the alignments are from the reverse complement of the query.
+    ///   (following the convention of `StrandedQueryAlignment`)
+    /// - `reference`: the `Reference` used for the alignment to get the target lengths.
+    pub fn to_blast_tabular(
+        &self,
+        query_name: &str,
+        query_length: u32,
+        strand: Strand,
+        reference: &Reference,
+        format: &BlastTabularFormat,
+    ) -> String {
+        let mut buffer = Vec::new();
+        self.write_as_blast_tabular(&mut buffer, query_name, query_length, strand, reference, format).unwrap();
+        String::from_utf8(buffer).unwrap()
+    }
+    /// Write as BLAST tabular lines, one line per alignment.
+    pub fn write_as_blast_tabular<W: Write>(
+        &self,
+        mut writer: W,
+        query_name: &str,
+        query_length: u32,
+        strand: Strand,
+        reference: &Reference,
+        format: &BlastTabularFormat,
+    ) -> Result<(), Error> {
+        let total_target_length = reference.get_total_length();
+        for target_alignment in &self.0 {
+            let target_name = reference_name(target_alignment.index, Some(&target_alignment.label));
+            let target_length = reference.get_sequence_length(target_alignment.index).unwrap_or_default();
+            for alignment in &target_alignment.alignments {
+                let statistics = AlignmentStatistics::new(alignment);
+                let bitscore = format.bitscore_model.bitscore(statistics.nident, alignment.penalty);
+
+                let fields: Vec<String> = format.columns.iter().map(|column| match column {
+                    BlastColumn::Qseqid => query_name.to_string(),
+                    BlastColumn::Sseqid => target_name.clone(),
+                    BlastColumn::Pident => format!("{:.3}", statistics.pident()),
+                    BlastColumn::Length => statistics.length.to_string(),
+                    BlastColumn::Mismatch => statistics.mismatch.to_string(),
+                    BlastColumn::Gapopen => statistics.gapopen.to_string(),
+                    BlastColumn::Qstart => (alignment.position.query.0 + 1).to_string(),
+                    BlastColumn::Qend => alignment.position.query.1.to_string(),
+                    BlastColumn::Sstart => if strand.is_reverse_orientation() {
+                        alignment.position.target.1
+                    } else {
+                        alignment.position.target.0 + 1
+                    }.to_string(),
+                    BlastColumn::Send => if strand.is_reverse_orientation() {
+                        alignment.position.target.0 + 1
+                    } else {
+                        alignment.position.target.1
+                    }.to_string(),
+                    BlastColumn::Evalue => format_evalue(
+                        format.bitscore_model.evalue(bitscore, query_length, total_target_length)
+                    ),
+                    BlastColumn::Bitscore => format!("{:.1}", bitscore),
+                    BlastColumn::Qlen => query_length.to_string(),
+                    BlastColumn::Slen => target_length.to_string(),
+                    BlastColumn::Nident => statistics.nident.to_string(),
+                    BlastColumn::Gaps => statistics.gaps.to_string(),
+                }).collect();
+                writeln!(writer, "{}", fields.join("\t"))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl LabeledStrandedQueryAlignment {
+    /// Convert to BLAST tabular lines of both strands.
+    pub fn to_blast_tabular(
+        &self,
+        query_name: &str,
+        query_length: u32,
+        reference: &Reference,
+        format: &BlastTabularFormat,
+    ) -> String {
+        let mut buffer = Vec::new();
+        self.write_as_blast_tabular(&mut buffer, query_name, query_length, reference, format).unwrap();
+        String::from_utf8(buffer).unwrap()
+    }
+    /// Write as BLAST tabular lines of both strands.
+    pub fn write_as_blast_tabular<W: Write>(
+        &self,
+        mut writer: W,
+        query_name: &str,
+        query_length: u32,
+        reference: &Reference,
+        format: &BlastTabularFormat,
+    ) -> Result<(), Error> {
+        self.forward.write_as_blast_tabular(&mut writer, query_name, query_length, Strand::Forward, reference, format)?;
+        self.reverse.write_as_blast_tabular(&mut writer, query_name, query_length, Strand::Reverse, reference, format)?;
+        Ok(())
+    }
+}
+
+struct AlignmentStatistics {
+    length: u32,
+    nident: u32,
+    mismatch: u32,
+    gapopen: u32,
+    gaps: u32,
+}
+
+impl AlignmentStatistics {
+    fn new(alignment: &Alignment) -> Self {
+        let mut statistics = Self { length: 0, nident: 0, mismatch: 0, gapopen: 0, gaps: 0 };
+        alignment.operations.iter().for_each(|ops| {
+            statistics.length += ops.count;
+            match ops.operation {
+                AlignmentOperation::Match => statistics.nident += ops.count,
+                AlignmentOperation::Subst => statistics.mismatch += ops.count,
+                AlignmentOperation::Insertion | AlignmentOperation::Deletion => {
+                    statistics.gapopen += 1;
+                    statistics.gaps += ops.count;
+                },
+            }
+        });
+        statistics
+    }
+    fn pident(&self) -> f64 {
+        if self.length == 0 {
+            0.0
+        } else {
+            100.0 * self.nident as f64 / self.length as f64
+        }
+    }
+}
+
+/// Format E-value like BLAST (e.g., `0.0`, `3.2e-45`, `0.001`, `12`).
+fn format_evalue(evalue: f64) -> String {
+    if evalue < 1.0e-180 {
+        "0.0".to_string()
+    } else if evalue < 1.0e-3 {
+        let formatted = format!("{:.1e}", evalue);
+        // Pad the exponent to two digits as BLAST does.
+        match formatted.split_once("e-") {
+            Some((mantissa, exponent)) if exponent.len() == 1 => format!("{}e-0{}", mantissa, exponent),
+            _ => formatted,
+        }
+    } else if evalue < 1.0 {
+        format!("{:.3}", evalue)
+    } else if evalue < 10.0 {
+        format!("{:.1}", evalue)
+    } else {
+        format!("{:.0}", evalue)
+    }
+}
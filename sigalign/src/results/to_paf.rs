@@ -0,0 +1,132 @@
+use std::io::{Write, Error};
+
+use crate::{
+    aligner::Strand,
+    reference::Reference,
+};
+use super::{
+    LabeledQueryAlignment,
+    LabeledStrandedQueryAlignment,
+    Alignment,
+    AlignmentOperation,
+    SamCigarStyle,
+    to_sam::{cigar_of_operations, edit_distance, reference_name},
+};
+
+const MAPQ_UNAVAILABLE: u8 = 255;
+
+/*
+PAF columns:
+    query name, query length, query start, query end, strand (+/-),
+    target name, target length, target start, target end,
+    number of residue matches, alignment block length, mapping quality
+Tags:
+    NM:i (edit distance), AS:i (negative penalty), cg:Z (CIGAR with `M`)
+*/
+
+impl LabeledQueryAlignment {
+    /// Convert to PAF lines, one line per alignment.
+    /// - `strand`: `Strand::Reverse` if the alignments are from the reverse complement of the query.
+    ///   (following the convention of `StrandedQueryAlignment`)
+    /// - `reference`: the `Reference` used for the alignment to get the target lengths.
+    pub fn to_paf(
+        &self,
+        query_name: &str,
+        query_length: u32,
+        strand: Strand,
+        reference: &Reference,
+    ) -> String {
+        let mut buffer = Vec::new();
+        self.write_as_paf(&mut buffer, query_name, query_length, strand, reference).unwrap();
+        String::from_utf8(buffer).unwrap()
+    }
+    /// Write as PAF lines, one line per alignment.
+    pub fn write_as_paf<W: Write>(
+        &self,
+        mut writer: W,
+        query_name: &str,
+        query_length: u32,
+        strand: Strand,
+        reference: &Reference,
+    ) -> Result<(), Error> {
+        for target_alignment in &self.0 {
+            let target_length = reference.get_sequence_length(target_alignment.index).unwrap_or_default();
+            for alignment in &target_alignment.alignments {
+                write_paf_line(
+                    &mut writer,
+                    query_name,
+                    query_length,
+                    strand,
+                    &reference_name(target_alignment.index, Some(&target_alignment.label)),
+                    target_length,
+                    alignment,
+                )?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl LabeledStrandedQueryAlignment {
+    /// Convert to PAF lines of both strands.
+    pub fn to_paf(
+        &self,
+        query_name: &str,
+        query_length: u32,
+        reference: &Reference,
+    ) -> String {
+        let mut buffer = Vec::new();
+        self.write_as_paf(&mut buffer, query_name, query_length, reference).unwrap();
+        String::from_utf8(buffer).unwrap()
+    }
+    /// Write as PAF lines of both strands.
+    pub fn write_as_paf<W: Write>(
+        &self,
+        mut writer: W,
+        query_name: &str,
+        query_length: u32,
+        reference: &Reference,
+    ) -> Result<(), Error> {
+        self.forward.write_as_paf(&mut writer, query_name, query_length, Strand::Forward, reference)?;
+        self.reverse.write_as_paf(&mut writer, query_name, query_length, Strand::Reverse, reference)?;
+        Ok(())
+    }
+}
+
+#[inline]
+fn write_paf_line<W: Write>(
+    writer: &mut W,
+    query_name: &str,
+    query_length: u32,
+    strand: Strand,
+    target_label: &str,
+    target_length: u32,
+    alignment: &Alignment,
+) -> Result<(), Error> {
+    let (residue_matches, block_length) = alignment.operations.iter().fold((0, 0), |(matches, block), ops| {
+        if ops.operation == AlignmentOperation::Match {
+            (matches + ops.count, block + ops.count)
+        } else {
+            (matches, block + ops.count)
+        }
+    });
+    writeln!(
+        writer,
+        "{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\tNM:i:{}\tAS:i:{}\tcg:Z:{}",
+        query_name,
+        query_length,
+        alignment.position.query.0,
+        alignment.position.query.1,
+        if strand.is_reverse_orientation() { '-' } else { '+' },
+        target_label,
+        target_length,
+        alignment.position.target.0,
+        alignment.position.target.1,
+        residue_matches,
+        block_length,
+        MAPQ_UNAVAILABLE,
+        edit_distance(&alignment.operations),
+        -(alignment.penalty as i64),
+        cigar_of_operations(&alignment.operations, SamCigarStyle::Match),
+    )
+}
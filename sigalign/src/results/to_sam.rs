@@ -1,48 +1,436 @@
+use std::io::{Write, Error, ErrorKind};
+
+use sigalign_core::reference::SequenceBuffer as _;
+use sigalign_utils::sequence_manipulation::reverse_complementary::reverse_complement_of_dna_sequence;
+
+use crate::{
+    aligner::Strand,
+    reference::{Reference, DefaultSequenceBuffer},
+};
 use super::{
-    AnchorAlignmentResult,
+    QueryAlignment,
+    LabeledQueryAlignment,
+    StrandedQueryAlignment,
+    LabeledStrandedQueryAlignment,
+    Alignment,
     AlignmentOperations,
     AlignmentOperation,
 };
 
-impl AnchorAlignmentResult {
-    #[inline]
-    pub fn to_sam_record(
-        &self,
-        qname: &str,
-        rname: &str,
-        is_forward: bool,
-    ) -> String {
-        let cigar: String = self.operations.iter()
-            .map(|op| op.to_cigar())
+const SAM_VERSION: &str = "1.6";
+const PROGRAM_NAME: &str = "sigalign";
+const PROGRAM_VERSION: &str = env!("CARGO_PKG_VERSION");
+const MAPQ_UNAVAILABLE: u8 = 255;
+
+const FLAG_UNMAPPED: u16 = 0x4;
+const FLAG_REVERSE: u16 = 0x10;
+const FLAG_SECONDARY: u16 = 0x100;
+const FLAG_SUPPLEMENTARY: u16 = 0x800;
+
+/// Style of the CIGAR string in SAM records.
+///
+/// - `Extended`: Match and mismatch are written as `=` and `X` (default).
+///   The equivalent bases of the substitution matrix (e.g., IUPAC codes) are also written as `=`.
+/// - `Match`: Both of match and mismatch are written as `M`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum SamCigarStyle {
+    #[default]
+    Extended,
+    Match,
+}
+
+/// Writer of the alignment results in SAM format.
+///
+/// - The header (`@HD`, `@SQ`, and `@PG`) is written from the `Reference`.
+/// - The records of one query are written at once:
+///     - The alignment with the lowest penalty is the primary record.
+///     - The alignments overlapping the primary in the query are secondary (`0x100`).
+///     - The others cover the different parts of the query and are supplementary (`0x800`).
+///     - If there is no alignment, an unmapped record (`0x4`) is written.
+/// - `MAPQ` is always 255 (unavailable).
+/// - Tags:
+///     - `NM`: Edit distance to the target.
+///     - `MD`: Mismatched and deleted bases of the target.
+///     - Both are from the bases, so an ambiguity code aligned as a match to the other base is a mismatch.
+///     - `AS`: Negative penalty of the alignment (higher is better).
+///
+/// The reverse strand alignment must follow the convention of `StrandedQueryAlignment`.
+pub struct SamWriter<'a, W: Write> {
+    writer: W,
+    reference: &'a Reference,
+    cigar_style: SamCigarStyle,
+    sequence_buffer: DefaultSequenceBuffer,
+}
+
+struct SamEntry<'b> {
+    target_index: u32,
+    label: Option<&'b str>,
+    alignment: &'b Alignment,
+    strand: Strand,
+}
+
+impl<'a, W: Write> SamWriter<'a, W> {
+    /// Make a new `SamWriter` with the `Reference` used for the alignment.
+    pub fn new(writer: W, reference: &'a Reference) -> Self {
+        Self {
+            writer,
+            reference,
+            cigar_style: SamCigarStyle::default(),
+            sequence_buffer: Reference::get_sequence_buffer(),
+        }
+    }
+    /// Set the style of the CIGAR string.
+    pub fn set_cigar_style(&mut self, cigar_style: SamCigarStyle) {
+        self.cigar_style = cigar_style;
+    }
+    /// Get the inner writer.
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+
+    /// Write the header lines.
+    pub fn write_header(&mut self) -> Result<(), Error> {
+        writeln!(self.writer, "@HD\tVN:{}\tSO:unsorted", SAM_VERSION)?;
+        for target_index in 0..self.reference.get_num_targets() {
+            let label = self.reference.get_label(target_index).unwrap_or_default();
+            let length = self.reference.get_sequence_length(target_index).unwrap_or_default();
+            writeln!(
+                self.writer,
+                "@SQ\tSN:{}\tLN:{}",
+                reference_name(target_index, Some(&label)),
+                length,
+            )?;
+        }
+        writeln!(
+            self.writer,
+            "@PG\tID:{}\tPN:{}\tVN:{}",
+            PROGRAM_NAME, PROGRAM_NAME, PROGRAM_VERSION,
+        )?;
+        Ok(())
+    }
+
+    /// Write the records of the forward strand alignment.
+    /// `quality` is written as is, if exists.
+    pub fn write_query_alignment(
+        &mut self,
+        query_name: &str,
+        query: &[u8],
+        quality: Option<&[u8]>,
+        query_alignment: &QueryAlignment,
+    ) -> Result<(), Error> {
+        let entries = entries_of_query_alignment(query_alignment, Strand::Forward).collect();
+        self.write_entries(query_name, query, quality, entries)
+    }
+    /// Write the records of the stranded alignment.
+    /// `query` and `quality` are of the forward strand.
+    pub fn write_stranded_query_alignment(
+        &mut self,
+        query_name: &str,
+        query: &[u8],
+        quality: Option<&[u8]>,
+        stranded_query_alignment: &StrandedQueryAlignment,
+    ) -> Result<(), Error> {
+        let entries = entries_of_query_alignment(&stranded_query_alignment.forward, Strand::Forward)
+            .chain(entries_of_query_alignment(&stranded_query_alignment.reverse, Strand::Reverse))
+            .collect();
+        self.write_entries(query_name, query, quality, entries)
+    }
+    /// Write the records of the labeled forward strand alignment.
+    pub fn write_labeled_query_alignment(
+        &mut self,
+        query_name: &str,
+        query: &[u8],
+        quality: Option<&[u8]>,
+        labeled_query_alignment: &LabeledQueryAlignment,
+    ) -> Result<(), Error> {
+        let entries = entries_of_labeled_query_alignment(labeled_query_alignment, Strand::Forward).collect();
+        self.write_entries(query_name, query, quality, entries)
+    }
+    /// Write the records of the labeled stranded alignment.
+    pub fn write_labeled_stranded_query_alignment(
+        &mut self,
+        query_name: &str,
+        query: &[u8],
+        quality: Option<&[u8]>,
+        labeled_stranded_query_alignment: &LabeledStrandedQueryAlignment,
+    ) -> Result<(), Error> {
+        let entries = entries_of_labeled_query_alignment(&labeled_stranded_query_alignment.forward, Strand::Forward)
+            .chain(entries_of_labeled_query_alignment(&labeled_stranded_query_alignment.reverse, Strand::Reverse))
             .collect();
-        let flag: u8 = if is_forward { 0 } else { 16 };
+        self.write_entries(query_name, query, quality, entries)
+    }
+
+    fn write_entries(
+        &mut self,
+        query_name: &str,
+        query: &[u8],
+        quality: Option<&[u8]>,
+        entries: Vec<SamEntry>,
+    ) -> Result<(), Error> {
+        if let Some(quality) = quality {
+            if quality.len() != query.len() {
+                return Err(Error::new(
+                    ErrorKind::InvalidInput,
+                    "The length of quality is different from the length of query.",
+                ));
+            }
+        }
+        let qname = if query_name.is_empty() { "*" } else { query_name };
+
+        // Unmapped
+        if entries.is_empty() {
+            writeln!(
+                self.writer,
+                "{}\t{}\t*\t0\t0\t*\t*\t0\t0\t{}\t{}",
+                qname,
+                FLAG_UNMAPPED,
+                bytes_or_asterisk(query),
+                bytes_or_asterisk(quality.unwrap_or_default()),
+            )?;
+            return Ok(())
+        }
+
+        let primary_index = entries.iter().enumerate().min_by(|(_, a), (_, b)| {
+            a.alignment.penalty.cmp(&b.alignment.penalty)
+                .then(b.alignment.length.cmp(&a.alignment.length))
+        }).map(|(index, _)| index).unwrap();
+        let primary_query_position = entries[primary_index].alignment.position.query;
+
+        let mut reverse_query: Option<(Vec<u8>, Option<Vec<u8>>)> = None;
+        for (index, entry) in entries.iter().enumerate() {
+            let mut flag = 0;
+            if entry.strand.is_reverse_orientation() {
+                flag |= FLAG_REVERSE;
+            }
+            if index != primary_index {
+                let (start, end) = entry.alignment.position.query;
+                if start < primary_query_position.1 && primary_query_position.0 < end {
+                    flag |= FLAG_SECONDARY;
+                } else {
+                    flag |= FLAG_SUPPLEMENTARY;
+                }
+            }
 
-        let record = format!(
-            "{}\t{}\t{}\t{}\t255\t{}\t*\t0\t0\t*\t*",
-            qname,
-            flag,
-            rname,
-            self.position.target.0 + 1,
-            cigar,
-        );
-        record
+            let (seq, qual) = if entry.strand.is_reverse_orientation() {
+                let (seq, qual) = reverse_query.get_or_insert_with(|| {
+                    let seq = reverse_complement_of_dna_sequence(query);
+                    let qual = quality.map(|q| q.iter().rev().copied().collect());
+                    (seq, qual)
+                });
+                (&seq[..], qual.as_deref())
+            } else {
+                (query, quality)
+            };
+
+            let rname = match entry.label {
+                Some(label) => reference_name(entry.target_index, Some(label)),
+                None => reference_name(
+                    entry.target_index,
+                    self.reference.get_label(entry.target_index).as_deref(),
+                ),
+            };
+            self.reference.fill_sequence_buffer(entry.target_index, &mut self.sequence_buffer);
+            let target = self.sequence_buffer.buffered_sequence();
+            let alignment = entry.alignment;
+            let (edit_distance, md) = edit_distance_and_md(
+                &alignment.operations,
+                &seq[leading_clip(alignment, query.len() as u32, entry.strand) as usize..],
+                &target[alignment.position.target.0 as usize..],
+            );
+
+            writeln!(
+                self.writer,
+                "{}\t{}\t{}\t{}\t{}\t{}\t*\t0\t0\t{}\t{}\tNM:i:{}\tMD:Z:{}\tAS:i:{}",
+                qname,
+                flag,
+                rname,
+                alignment.position.target.0 + 1,
+                MAPQ_UNAVAILABLE,
+                cigar_string(alignment, query.len() as u32, entry.strand, self.cigar_style),
+                bytes_or_asterisk(seq),
+                bytes_or_asterisk(qual.unwrap_or_default()),
+                edit_distance,
+                md,
+                -(alignment.penalty as i64),
+            )?;
+        }
+        Ok(())
+    }
+}
+
+fn entries_of_query_alignment(
+    query_alignment: &QueryAlignment,
+    strand: Strand,
+) -> impl Iterator<Item = SamEntry<'_>> {
+    query_alignment.0.iter().flat_map(move |target_alignment| {
+        target_alignment.alignments.iter().map(move |alignment| SamEntry {
+            target_index: target_alignment.index,
+            label: None,
+            alignment,
+            strand,
+        })
+    })
+}
+fn entries_of_labeled_query_alignment(
+    labeled_query_alignment: &LabeledQueryAlignment,
+    strand: Strand,
+) -> impl Iterator<Item = SamEntry<'_>> {
+    labeled_query_alignment.0.iter().flat_map(move |target_alignment| {
+        target_alignment.alignments.iter().map(move |alignment| SamEntry {
+            target_index: target_alignment.index,
+            label: Some(target_alignment.label.as_str()),
+            alignment,
+            strand,
+        })
+    })
+}
+
+/// Unlabeled target is named by its index.
+#[inline]
+pub(super) fn reference_name(target_index: u32, label: Option<&str>) -> String {
+    match label {
+        Some(label) if !label.is_empty() => label.to_string(),
+        _ => target_index.to_string(),
+    }
+}
+
+#[inline]
+fn bytes_or_asterisk(bytes: &[u8]) -> String {
+    if bytes.is_empty() {
+        "*".to_string()
+    } else {
+        String::from_utf8_lossy(bytes).into_owned()
+    }
+}
+
+fn cigar_string(
+    alignment: &Alignment,
+    query_length: u32,
+    strand: Strand,
+    cigar_style: SamCigarStyle,
+) -> String {
+    let leading_clip = leading_clip(alignment, query_length, strand);
+    let trailing_clip = query_length - leading_clip - (alignment.position.query.1 - alignment.position.query.0);
+
+    let mut cigar = String::new();
+    if leading_clip != 0 {
+        cigar.push_str(&format!("{}S", leading_clip));
+    }
+    cigar.push_str(&cigar_of_operations(&alignment.operations, cigar_style));
+    if trailing_clip != 0 {
+        cigar.push_str(&format!("{}S", trailing_clip));
     }
+    cigar
 }
 
-impl AlignmentOperations {
-    #[inline]
-    fn to_cigar(&self) -> String {
-        format!("{}{}", self.count, self.operation.to_cigar_code())
+/// Unaligned bases before the alignment in SEQ (reverse complemented if the strand is reverse).
+#[inline]
+fn leading_clip(alignment: &Alignment, query_length: u32, strand: Strand) -> u32 {
+    if strand.is_reverse_orientation() {
+        query_length - alignment.position.query.1
+    } else {
+        alignment.position.query.0
     }
 }
 
-impl AlignmentOperation {
-    #[inline]
-    fn to_cigar_code(&self) -> u8 {
-        match self {
-            AlignmentOperation::Match | AlignmentOperation::Subst => b'M',
-            AlignmentOperation::Insertion => b'I',
-            AlignmentOperation::Deletion => b'D',
+/// CIGAR of the operations without clipping.
+pub(super) fn cigar_of_operations(
+    operations: &[AlignmentOperations],
+    cigar_style: SamCigarStyle,
+) -> String {
+    let mut cigar = String::new();
+    let mut last: Option<(u8, u32)> = None;
+    for ops in operations {
+        let code = cigar_code(&ops.operation, cigar_style);
+        match last.as_mut() {
+            Some((last_code, count)) if *last_code == code => *count += ops.count,
+            _ => {
+                if let Some((last_code, count)) = last {
+                    cigar.push_str(&format!("{}{}", count, last_code as char));
+                }
+                last = Some((code, ops.count));
+            },
         }
     }
+    if let Some((last_code, count)) = last {
+        cigar.push_str(&format!("{}{}", count, last_code as char));
+    }
+    cigar
+}
+
+#[inline]
+pub(super) fn edit_distance(operations: &[AlignmentOperations]) -> u32 {
+    operations.iter().filter(|x| x.operation != AlignmentOperation::Match).map(|x| x.count).sum()
+}
+
+/// `query` and `target` start from the first aligned base.
+///  - `Match` of the different bases (e.g., ambiguity codes) is counted as a mismatch.
+fn edit_distance_and_md(
+    operations: &[AlignmentOperations],
+    query: &[u8],
+    target: &[u8],
+) -> (u32, String) {
+    let mut edit_distance = 0;
+    let mut md = String::new();
+    let mut match_count = 0;
+    let mut query_index = 0;
+    let mut target_index = 0;
+    for operations in operations {
+        let count = operations.count as usize;
+        match operations.operation {
+            AlignmentOperation::Match | AlignmentOperation::Subst => {
+                query[query_index..query_index+count].iter()
+                    .zip(&target[target_index..target_index+count])
+                    .for_each(|(query_base, &target_base)| {
+                        if query_base.eq_ignore_ascii_case(&target_base) {
+                            match_count += 1;
+                        } else {
+                            edit_distance += 1;
+                            md.push_str(&match_count.to_string());
+                            md.push(md_base(target_base));
+                            match_count = 0;
+                        }
+                    });
+                query_index += count;
+                target_index += count;
+            },
+            AlignmentOperation::Deletion => {
+                edit_distance += operations.count;
+                md.push_str(&match_count.to_string());
+                md.push('^');
+                target[target_index..target_index+count].iter().for_each(|&base| {
+                    md.push(md_base(base));
+                });
+                match_count = 0;
+                target_index += count;
+            },
+            AlignmentOperation::Insertion => {
+                edit_distance += operations.count;
+                query_index += count;
+            },
+        }
+    }
+    md.push_str(&match_count.to_string());
+    (edit_distance, md)
+}
+
+/// The base in MD tag must be an alphabet. Others (e.g., ignored bases) are written as `N`.
+#[inline]
+fn md_base(base: u8) -> char {
+    if base.is_ascii_alphabetic() {
+        base.to_ascii_uppercase() as char
+    } else {
+        'N'
+    }
+}
+
+#[inline]
+fn cigar_code(operation: &AlignmentOperation, cigar_style: SamCigarStyle) -> u8 {
+    match (operation, cigar_style) {
+        (AlignmentOperation::Match, SamCigarStyle::Extended) => b'=',
+        (AlignmentOperation::Subst, SamCigarStyle::Extended) => b'X',
+        (AlignmentOperation::Match | AlignmentOperation::Subst, SamCigarStyle::Match) => b'M',
+        (AlignmentOperation::Insertion, _) => b'I',
+        (AlignmentOperation::Deletion, _) => b'D',
+    }
 }
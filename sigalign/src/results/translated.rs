@@ -0,0 +1,42 @@
+use serde::{Deserialize, Serialize};
+
+use super::Alignment;
+
+/// Alignments of the nucleotide query translated in six frames.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "short_key", serde(rename = "TrnQryAln"))]
+pub struct TranslatedQueryAlignment(
+    pub Vec<TranslatedTargetAlignment>
+);
+
+/// Alignments of the translated query to a target, for all frames.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "short_key", serde(rename = "TrnTgtAln"))]
+pub struct TranslatedTargetAlignment {
+    #[cfg_attr(feature = "short_key", serde(rename = "idx"))]
+    pub index: u32,
+    #[cfg_attr(feature = "short_key", serde(rename = "aln"))]
+    pub alignments: Vec<TranslatedAlignment>,
+}
+
+/// Alignment of a frame of the translated query.
+///
+/// - `frame`: 1, 2, 3 for the query and -1, -2, -3 for the reverse complement of the query.
+///    - The absolute value minus one is the offset of the first codon.
+/// - `alignment`:
+///    - `position.query` is the coordinates of the nucleotide query (forward).
+///    - `position.target`, `length`, `penalty`, and `operations` are of the amino acids.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "short_key", serde(rename = "TrnAln"))]
+pub struct TranslatedAlignment {
+    #[cfg_attr(feature = "short_key", serde(rename = "frm"))]
+    pub frame: i8,
+    #[cfg_attr(feature = "short_key", serde(rename = "aln"))]
+    pub alignment: Alignment,
+}
+
+impl TranslatedQueryAlignment {
+    pub fn count_alignments(&self) -> usize {
+        self.0.iter().map(|target_alignment| target_alignment.alignments.len()).sum()
+    }
+}
@@ -12,9 +12,12 @@ mod count_matches;
 use count_matches::count_the_consecutive_match;
 mod fasta_reader;
 use fasta_reader::read_fasta_file;
+mod pattern_index_locate;
+use pattern_index_locate::locate_patterns_with_pattern_indices;
 
 criterion_group!(
     benches,
     read_fasta_file,
+    locate_patterns_with_pattern_indices,
 );
 criterion_main!(benches);
\ No newline at end of file
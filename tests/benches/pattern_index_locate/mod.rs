@@ -0,0 +1,79 @@
+use criterion::{
+    black_box, Criterion,
+};
+
+use sigalign_core::reference::{PatternIndex, SequenceStorage};
+use sigalign_impl::{
+    pattern_index::{
+        dynamic_lfi::{DynamicLfi, DynamicLfiOption, BwtBlockSize},
+        kmer_hash::{KmerHashIndex, KmerHashIndexOption},
+        suffix_array::{SuffixArrayIndex, SuffixArrayIndexOption},
+    },
+    sequence_storage::in_memory::InMemoryStorage,
+};
+
+const PATTERN_SIZE: usize = 20;
+const PATTERN_COUNT: usize = 1000;
+
+fn get_concatenated_test_sequence() -> Vec<u8> {
+    use sigalign_tests::common::test_data::DataForValidation;
+
+    let (fasta_file, _) = DataForValidation::Default.get_data_paths();
+    let mut storage = InMemoryStorage::new();
+    storage.add_fasta(std::fs::File::open(fasta_file).unwrap()).unwrap();
+    storage.get_concatenated_sequence_with_boundaries_of_targets().0
+}
+
+pub fn locate_patterns_with_pattern_indices(c: &mut Criterion) {
+    let mut group = c.benchmark_group("locate_patterns_with_pattern_indices");
+
+    let sequence = get_concatenated_test_sequence();
+    let step = (sequence.len() / PATTERN_COUNT).max(1);
+    let patterns: Vec<&[u8]> = (0..sequence.len() - PATTERN_SIZE)
+        .step_by(step)
+        .map(|start| &sequence[start..start + PATTERN_SIZE])
+        .collect();
+
+    let dynamic_lfi = DynamicLfi::new(sequence.clone(), DynamicLfiOption {
+        suffix_array_sampling_ratio: 1,
+        lookup_table_max_bytes_size: 200_000,
+        lookup_table_kmer_size: None,
+        use_safe_guard: true,
+        bwt_block_size: BwtBlockSize::V64,
+    }).unwrap();
+    let kmer_hash_index = KmerHashIndex::new(
+        sequence.clone(), KmerHashIndexOption::new(vec![PATTERN_SIZE as u32]),
+    ).unwrap();
+    let suffix_array_index = SuffixArrayIndex::new(
+        sequence.clone(), SuffixArrayIndexOption::default(),
+    ).unwrap();
+
+    group.bench_function(
+        "dynamic_lfi",
+        |b| b.iter(|| {
+            patterns.iter().map(|pattern| {
+                dynamic_lfi.get_sorted_positions(black_box(pattern)).len()
+            }).sum::<usize>()
+        }
+    ));
+
+    group.bench_function(
+        "kmer_hash_index",
+        |b| b.iter(|| {
+            patterns.iter().map(|pattern| {
+                kmer_hash_index.get_sorted_positions(black_box(pattern)).len()
+            }).sum::<usize>()
+        }
+    ));
+
+    group.bench_function(
+        "suffix_array_index",
+        |b| b.iter(|| {
+            patterns.iter().map(|pattern| {
+                suffix_array_index.get_sorted_positions(black_box(pattern)).len()
+            }).sum::<usize>()
+        }
+    ));
+
+    group.finish();
+}
@@ -0,0 +1,66 @@
+use crate::common::init_logger;
+
+use sigalign_utils::sequence_manipulation::reverse_complementary::reverse_complement_of_dna_sequence;
+use sigalign::{
+    Aligner,
+    ReferenceBuilder,
+    Strand,
+    algorithms::Local,
+    results::{
+        BlastTabularFormat,
+        BlastTabularFormatError,
+        BlastColumn,
+        BitscoreModel,
+    },
+};
+
+#[test]
+fn test_blast_tabular_line_of_known_alignment() {
+    init_logger();
+
+    let reference = ReferenceBuilder::new()
+        .add_target("target_1", b"TTAGTTGTGCCGCAGCGAAGTAGTGCTTGAAATATGCGACCCCTAAGTAGGAGCGTATGCGCCCAGTAACCAATGCCTGTTGAGATGCCAGACGCGTAACCAAAACATAGAAACCATCAATAGACAGGTCATAATCGGTC")
+        .build().unwrap();
+    let mut aligner = Aligner::new(Local::new(4, 6, 2, 20, 0.2).unwrap());
+
+    // One substitution and one deleted base
+    let query = b"TTTCGCAGCGAAGGAGTGCTTGAAATATGCGACCCCAAGTAGGAGCGTATGCGCCCAGTAACGG";
+    let result = reference.label_query_alignment(aligner.align(query, &reference));
+
+    // Default 12 columns
+    let format = BlastTabularFormat::default();
+    let m8 = result.to_blast_tabular("query", query.len() as u32, Strand::Forward, &reference, &format);
+    assert_eq!(
+        m8,
+        "query\ttarget_1\t96.667\t60\t1\t1\t4\t62\t11\t70\t1.1e-22\t86.1\n",
+    );
+
+    // Custom columns and bitscore model
+    let mut format = BlastTabularFormat::from_outfmt("6 sseqid nident gaps qlen slen bitscore").unwrap();
+    assert_eq!(format.get_columns()[0], BlastColumn::Sseqid);
+    format.set_bitscore_model(BitscoreModel::new(2.0, 0.625, 0.41));
+    let m8 = result.to_blast_tabular("query", query.len() as u32, Strand::Forward, &reference, &format);
+    assert_eq!(m8, "target_1\t58\t1\t64\t140\t95.1\n");
+
+    // Reverse strand: `sstart` is greater than `send`
+    let reverse_complement = reverse_complement_of_dna_sequence(query);
+    let stranded_result = reference.label_stranded_query_alignment(
+        aligner.align_stranded(&reverse_complement, &reference, Strand::Both)
+    );
+    let format = BlastTabularFormat::from_outfmt("6 qstart qend sstart send").unwrap();
+    assert_eq!(
+        stranded_result.to_blast_tabular("query", query.len() as u32, &reference, &format),
+        "3\t61\t70\t11\n",
+    );
+
+    // Invalid formats
+    assert!(matches!(
+        BlastTabularFormat::from_outfmt("7 qseqid"),
+        Err(BlastTabularFormatError::UnsupportedFormat(_)),
+    ));
+    assert!(matches!(
+        BlastTabularFormat::from_outfmt("6 qseqid staxids"),
+        Err(BlastTabularFormatError::UnknownColumn(_)),
+    ));
+    assert_eq!(BlastTabularFormat::from_outfmt("6").unwrap(), BlastTabularFormat::default());
+}
@@ -0,0 +1,210 @@
+use sigalign::{
+    algorithms::SubstitutionMatrix,
+    results::{
+        Alignment, AlignmentOperation, AlignmentOperations, AlignmentPosition,
+    },
+};
+
+const PREC_SCALE: u32 = 100_000;
+const INF: u32 = u32::MAX >> 2;
+
+type PenaltyMatrix = Vec<Vec<u32>>;
+
+pub fn dp_global_to_target(
+    query: &[u8],
+    target: &[u8],
+    mismatch_penalty: u32,
+    gap_open_penalty: u32,
+    gap_extend_penalty: u32,
+    min_length: u32,
+    max_penalty_per_length: f32,
+) -> Vec<Alignment> {
+    dp_global_with_substitution_penalty(
+        query,
+        target,
+        |_, _| mismatch_penalty,
+        gap_open_penalty,
+        gap_extend_penalty,
+        min_length,
+        max_penalty_per_length,
+    )
+}
+
+pub fn dp_global_with_substitution_matrix_to_target(
+    query: &[u8],
+    target: &[u8],
+    substitution_matrix: &SubstitutionMatrix,
+    gap_open_penalty: u32,
+    gap_extend_penalty: u32,
+    min_length: u32,
+    max_penalty_per_length: f32,
+) -> Vec<Alignment> {
+    dp_global_with_substitution_penalty(
+        query,
+        target,
+        |query_base, target_base| substitution_matrix.get_penalty(query_base, target_base),
+        gap_open_penalty,
+        gap_extend_penalty,
+        min_length,
+        max_penalty_per_length,
+    )
+}
+
+// Penalty of the optimal global alignment without the cutoff
+pub fn dp_global_penalty_with_substitution_matrix(
+    query: &[u8],
+    target: &[u8],
+    substitution_matrix: &SubstitutionMatrix,
+    gap_open_penalty: u32,
+    gap_extend_penalty: u32,
+) -> u32 {
+    let (best_mat, _, _) = fill_global_matrices(
+        query,
+        target,
+        |query_base, target_base| substitution_matrix.get_penalty(query_base, target_base),
+        gap_open_penalty,
+        gap_extend_penalty,
+    );
+    best_mat[query.len()][target.len()]
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Matrix {
+    Best,
+    Ins,
+    Del,
+}
+
+// The optimal global alignment (Gotoh), if it satisfies the cutoff
+//  - `substitution_penalty` is called only for the different bytes
+fn dp_global_with_substitution_penalty<F: Fn(u8, u8) -> u32>(
+    query: &[u8],
+    target: &[u8],
+    substitution_penalty: F,
+    gap_open_penalty: u32,
+    gap_extend_penalty: u32,
+    min_length: u32,
+    max_penalty_per_length: f32,
+) -> Vec<Alignment> {
+    let (len1, len2) = (query.len(), target.len());
+    let diagonal_penalty = |i: usize, j: usize| -> u32 {
+        if query[i-1] == target[j-1] {
+            0
+        } else {
+            substitution_penalty(query[i-1], target[j-1])
+        }
+    };
+
+    let (best_mat, ins_mat, del_mat) = fill_global_matrices(
+        query, target, &substitution_penalty, gap_open_penalty, gap_extend_penalty,
+    );
+
+    // Backtrace from the end of both sequences
+    let mut reversed_operation: Vec<AlignmentOperation> = Vec::new();
+    let (mut i, mut j) = (len1, len2);
+    let mut matrix = Matrix::Best;
+    while i > 0 || j > 0 {
+        match matrix {
+            Matrix::Best => {
+                if i > 0 && j > 0 && best_mat[i][j] == best_mat[i-1][j-1] + diagonal_penalty(i, j) {
+                    if query[i-1] == target[j-1] {
+                        reversed_operation.push(AlignmentOperation::Match);
+                    } else {
+                        reversed_operation.push(AlignmentOperation::Subst);
+                    }
+                    i -= 1;
+                    j -= 1;
+                } else if best_mat[i][j] == ins_mat[i][j] {
+                    matrix = Matrix::Ins;
+                } else {
+                    matrix = Matrix::Del;
+                }
+            },
+            Matrix::Ins => {
+                reversed_operation.push(AlignmentOperation::Insertion);
+                if ins_mat[i][j] != ins_mat[i-1][j] + gap_extend_penalty {
+                    matrix = Matrix::Best;
+                }
+                i -= 1;
+            },
+            Matrix::Del => {
+                reversed_operation.push(AlignmentOperation::Deletion);
+                if del_mat[i][j] != del_mat[i][j-1] + gap_extend_penalty {
+                    matrix = Matrix::Best;
+                }
+                j -= 1;
+            },
+        }
+    }
+
+    let penalty = best_mat[len1][len2];
+    let length = reversed_operation.len() as u32;
+    let is_valid = (
+        length >= min_length
+    ) && (
+        penalty * PREC_SCALE <= (length * (max_penalty_per_length * PREC_SCALE as f32) as u32)
+    );
+    if !is_valid {
+        return Vec::new();
+    }
+
+    let mut operations: Vec<AlignmentOperations> = Vec::new();
+    for operation in reversed_operation.into_iter().rev() {
+        match operations.last_mut() {
+            Some(last) if last.operation == operation => last.count += 1,
+            _ => operations.push(AlignmentOperations { operation, count: 1 }),
+        }
+    }
+    vec![Alignment {
+        penalty,
+        length,
+        position: AlignmentPosition {
+            query: (0, len1 as u32),
+            target: (0, len2 as u32),
+        },
+        operations,
+    }]
+}
+
+// Fill the matrices of (best, insertion, deletion)
+//   - The gaps at both ends are penalized
+fn fill_global_matrices<F: Fn(u8, u8) -> u32>(
+    query: &[u8],
+    target: &[u8],
+    substitution_penalty: F,
+    gap_open_penalty: u32,
+    gap_extend_penalty: u32,
+) -> (PenaltyMatrix, PenaltyMatrix, PenaltyMatrix) {
+    let (len1, len2) = (query.len(), target.len());
+    let diagonal_penalty = |i: usize, j: usize| -> u32 {
+        if query[i-1] == target[j-1] {
+            0
+        } else {
+            substitution_penalty(query[i-1], target[j-1])
+        }
+    };
+    let mut best_mat = vec![vec![INF; len2+1]; len1+1];
+    let mut ins_mat = vec![vec![INF; len2+1]; len1+1];
+    let mut del_mat = vec![vec![INF; len2+1]; len1+1];
+    best_mat[0][0] = 0;
+    for i in 0..=len1 {
+        for j in 0..=len2 {
+            if i == 0 && j == 0 {
+                continue
+            }
+            if i > 0 {
+                ins_mat[i][j] = (best_mat[i-1][j] + gap_open_penalty).min(ins_mat[i-1][j]) + gap_extend_penalty;
+            }
+            if j > 0 {
+                del_mat[i][j] = (best_mat[i][j-1] + gap_open_penalty).min(del_mat[i][j-1]) + gap_extend_penalty;
+            }
+            let from_diagonal = if i > 0 && j > 0 {
+                best_mat[i-1][j-1] + diagonal_penalty(i, j)
+            } else {
+                INF
+            };
+            best_mat[i][j] = from_diagonal.min(ins_mat[i][j]).min(del_mat[i][j]);
+        }
+    }
+    (best_mat, ins_mat, del_mat)
+}
@@ -22,6 +22,14 @@ pub use semi_global::{
     dp_semi_global_to_pattern_existing_targets,
     dp_semi_global_to_ref_file,
     dp_semi_global_to_target,
+    dp_semi_global_with_substitution_matrix_to_target,
+};
+
+mod global;
+pub use global::{
+    dp_global_to_target,
+    dp_global_with_substitution_matrix_to_target,
+    dp_global_penalty_with_substitution_matrix,
 };
 
 mod local_with_one_matrix;
@@ -29,6 +37,7 @@ pub use local_with_one_matrix::{
     dp_local_with_one_mat_to_pattern_existing_targets,
     dp_local_with_one_mat_to_ref_file,
     dp_local_with_one_mat_to_target,
+    dp_local_with_substitution_matrix_to_target,
 };
 
 mod local_with_all_substring;
@@ -4,6 +4,7 @@ use super::{
     target_indices_having_matched_pattern,
 };
 use sigalign::{
+    algorithms::SubstitutionMatrix,
     results::{
         Alignment, QueryAlignment, TargetAlignment
     },
@@ -119,3 +120,23 @@ pub fn dp_semi_global_to_target(
 
     alignments
 }
+
+pub fn dp_semi_global_with_substitution_matrix_to_target(
+    query: &[u8],
+    target: &[u8],
+    substitution_matrix: &SubstitutionMatrix,
+    gap_open_penalty: u32,
+    gap_extend_penalty: u32,
+    min_length: u32,
+    max_penalty_per_length: f32,
+) -> Vec<Alignment> {
+    let dp_matrix = DpMatrix::new_with_substitution_matrix(
+        query.to_vec(),
+        target.to_vec(),
+        substitution_matrix,
+        gap_open_penalty,
+        gap_extend_penalty,
+    );
+
+    parse_valid_semi_global_result_from_dpm(&dp_matrix, min_length, max_penalty_per_length)
+}
@@ -1,6 +1,7 @@
 use super::{
     DpMatrix, Cell, BacktraceMarker,
 };
+use sigalign::algorithms::SubstitutionMatrix;
 impl Cell {
     fn new() -> Self {
         Self { penalty: 0, btm: BacktraceMarker::FromDiag }
@@ -13,6 +14,37 @@ impl DpMatrix {
         mismatch_penalty: u32,
         gap_open_penalty: u32,
         gap_extend_penalty: u32,
+    ) -> Self {
+        Self::new_with_substitution_penalty(
+            query,
+            target,
+            |_, _| mismatch_penalty,
+            gap_open_penalty,
+            gap_extend_penalty,
+        )
+    }
+    pub fn new_with_substitution_matrix(
+        query: Vec<u8>,
+        target: Vec<u8>,
+        substitution_matrix: &SubstitutionMatrix,
+        gap_open_penalty: u32,
+        gap_extend_penalty: u32,
+    ) -> Self {
+        Self::new_with_substitution_penalty(
+            query,
+            target,
+            |query_base, target_base| substitution_matrix.get_penalty(query_base, target_base),
+            gap_open_penalty,
+            gap_extend_penalty,
+        )
+    }
+    // `substitution_penalty` is called only for the different bytes
+    fn new_with_substitution_penalty<F: Fn(u8, u8) -> u32>(
+        query: Vec<u8>,
+        target: Vec<u8>,
+        substitution_penalty: F,
+        gap_open_penalty: u32,
+        gap_extend_penalty: u32,
     ) -> Self {
         let len1 = query.len();
         let len2 = target.len();
@@ -65,7 +97,7 @@ impl DpMatrix {
                     if query[i-1] == target[j-1] {
                         (p_from_dp, true)
                     } else {
-                        (p_from_dp + mismatch_penalty, false)
+                        (p_from_dp + substitution_penalty(query[i-1], target[j-1]), false)
                     }
                 };
                 let min_p = p_from_diag.min(p_from_del.min(p_from_ins));
@@ -16,9 +16,14 @@ pub use alignment::{
     dp_semi_global_to_pattern_existing_targets,
     dp_semi_global_to_ref_file,
     dp_semi_global_to_target,
+    dp_semi_global_with_substitution_matrix_to_target,
+    dp_global_to_target,
+    dp_global_with_substitution_matrix_to_target,
+    dp_global_penalty_with_substitution_matrix,
     dp_local_with_one_mat_to_pattern_existing_targets,
     dp_local_with_one_mat_to_ref_file,
     dp_local_with_one_mat_to_target,
+    dp_local_with_substitution_matrix_to_target,
     dp_local_with_all_subs_to_pattern_existing_targets,
     dp_local_with_all_subs_to_ref_file,
     dp_local_with_all_subs_to_target,
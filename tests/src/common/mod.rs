@@ -15,11 +15,17 @@ pub mod test_data;
 pub mod random_text_and_pattern;
 pub mod random_regulator;
 
+// Options of the pattern indices
+pub mod pattern_index_option;
+
 // Result of stable version of sigalign
 pub mod result_converter_of_v03;
 
 // DP matrix to generate the answer result
 pub mod dynamic_programming_matrix;
 
+// Validation of the alignment with the substitution matrix
+pub mod substitution_matrix_validation;
+
 // Results conversion
 pub mod tsv_results;
\ No newline at end of file
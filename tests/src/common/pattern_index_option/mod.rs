@@ -0,0 +1,11 @@
+use sigalign_impl::pattern_index::dynamic_lfi::{DynamicLfiOption, BwtBlockSize};
+
+pub fn get_dynamic_lfi_option(suffix_array_sampling_ratio: u64) -> DynamicLfiOption {
+    DynamicLfiOption {
+        suffix_array_sampling_ratio,
+        lookup_table_max_bytes_size: 64 * 1024,
+        lookup_table_kmer_size: None,
+        use_safe_guard: true,
+        bwt_block_size: BwtBlockSize::V64,
+    }
+}
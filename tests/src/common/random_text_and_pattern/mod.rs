@@ -16,7 +16,10 @@ pub fn gen_rand_chr_list(chr_count: usize) -> Vec<u8> {
 }
 // Return the length of min(chr_list.len(), min_len). At least one chr in chr_list is included in text.
 pub fn gen_rand_text(chr_list: &[u8], min_len: usize, max_len: usize) -> Vec<u8> {
-    let mut rng = rand::thread_rng();
+    gen_rand_text_with_rng(&mut rand::thread_rng(), chr_list, min_len, max_len)
+}
+// Same as `gen_rand_text` with the given random number generator (e.g., seeded for the reproducible tests).
+pub fn gen_rand_text_with_rng<R: Rng>(rng: &mut R, chr_list: &[u8], min_len: usize, max_len: usize) -> Vec<u8> {
     let text_len = rng.gen_range(min_len..max_len+1);
     let chr_count = chr_list.len();
     let mut text = chr_list.to_vec();
@@ -24,7 +27,7 @@ pub fn gen_rand_text(chr_list: &[u8], min_len: usize, max_len: usize) -> Vec<u8>
         let chr = chr_list[rng.gen_range(0..chr_count)];
         text.push(chr);
     }
-    text.shuffle(&mut rng);
+    text.shuffle(rng);
     text
 }
 pub fn gen_rand_pattern(text: &[u8], min_len: usize, max_len: usize) -> Vec<u8> {
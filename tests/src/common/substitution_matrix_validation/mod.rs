@@ -0,0 +1,68 @@
+use crate::common::dynamic_programming_matrix::dp_global_penalty_with_substitution_matrix;
+
+use sigalign::{
+    algorithms::SubstitutionMatrix,
+    results::{Alignment, AlignmentOperation},
+};
+
+const PREC_SCALE: u32 = 100_000;
+
+// Check the alignment with the substitution matrix
+//  - Match: zero penalty pair (the same or the equivalent bytes)
+//  - The penalty is recalculated from the operations, and optimal in the aligned region.
+pub fn assert_alignment_is_valid(
+    alignment: &Alignment,
+    query: &[u8],
+    target: &[u8],
+    substitution_matrix: &SubstitutionMatrix,
+    (gap_open_penalty, gap_extend_penalty): (u32, u32),
+    minimum_length: u32,
+    maximum_penalty_per_length: f32,
+) {
+    let query = &query[alignment.position.query.0 as usize..alignment.position.query.1 as usize];
+    let target = &target[alignment.position.target.0 as usize..alignment.position.target.1 as usize];
+
+    // Penalty of the operations
+    let (mut query_index, mut target_index) = (0, 0);
+    let mut penalty = 0;
+    let mut length = 0;
+    for operations in alignment.operations.iter() {
+        let count = operations.count as usize;
+        match operations.operation {
+            AlignmentOperation::Match => {
+                for _ in 0..count {
+                    assert_eq!(substitution_matrix.get_penalty(query[query_index], target[target_index]), 0);
+                    query_index += 1;
+                    target_index += 1;
+                }
+            },
+            AlignmentOperation::Subst => {
+                for _ in 0..count {
+                    let substitution_penalty = substitution_matrix.get_penalty(query[query_index], target[target_index]);
+                    assert_ne!(substitution_penalty, 0);
+                    penalty += substitution_penalty;
+                    query_index += 1;
+                    target_index += 1;
+                }
+            },
+            AlignmentOperation::Insertion => {
+                penalty += gap_open_penalty + gap_extend_penalty * count as u32;
+                query_index += count;
+            },
+            AlignmentOperation::Deletion => {
+                penalty += gap_open_penalty + gap_extend_penalty * count as u32;
+                target_index += count;
+            },
+        }
+        length += count as u32;
+    }
+    assert_eq!((query_index, target_index), (query.len(), target.len()));
+    assert_eq!(alignment.penalty, penalty);
+    assert_eq!(alignment.length, length);
+    assert!(length >= minimum_length);
+    assert!(penalty * PREC_SCALE <= length * (maximum_penalty_per_length * PREC_SCALE as f32) as u32);
+
+    // Optimal in the aligned region
+    let optimal_penalty = dp_global_penalty_with_substitution_matrix(query, target, substitution_matrix, gap_open_penalty, gap_extend_penalty);
+    assert_eq!(optimal_penalty, penalty);
+}
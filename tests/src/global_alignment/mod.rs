@@ -0,0 +1,171 @@
+use crate::common::{
+    init_logger,
+    random_text_and_pattern::gen_rand_text_with_rng,
+    dynamic_programming_matrix::{
+        dp_global_to_target,
+        dp_global_with_substitution_matrix_to_target,
+    },
+    substitution_matrix_validation::assert_alignment_is_valid,
+};
+
+use rand::{Rng, SeedableRng, rngs::StdRng};
+use sigalign::{
+    Aligner,
+    Reference,
+    ReferenceBuilder,
+    algorithms::{Algorithm, Global, SubstitutionMatrix},
+    results::{Alignment, AlignmentPosition},
+};
+
+const NUCLEOTIDES: &[u8] = b"ACGT";
+const LOCUS_COUNT: usize = 6;
+const ALLELE_COUNT: usize = 5;
+const QUERY_COUNT: usize = 40;
+const SEED: u64 = 25;
+
+
+// Sequence with the substitutions, the indels, and the trimmed ends
+fn gen_variant(rng: &mut StdRng, sequence: &[u8], substitution_rate: f64, max_indel_count: usize) -> Vec<u8> {
+    let mut variant: Vec<u8> = sequence.iter().map(|&base| {
+        if rng.gen_bool(substitution_rate) {
+            NUCLEOTIDES[rng.gen_range(0..NUCLEOTIDES.len())]
+        } else {
+            base
+        }
+    }).collect();
+    for _ in 0..rng.gen_range(0..=max_indel_count) {
+        let position = rng.gen_range(0..variant.len());
+        let indel_length = rng.gen_range(1..4);
+        if rng.gen_bool(0.5) {
+            variant.drain(position..(position + indel_length).min(variant.len()));
+        } else {
+            // Each base is generated at least once, so cut to the length
+            let mut inserted = gen_rand_text_with_rng(rng, NUCLEOTIDES, indel_length, indel_length);
+            inserted.truncate(indel_length);
+            variant.splice(position..position, inserted);
+        }
+    }
+    if rng.gen_bool(0.2) {
+        let trimmed_length = rng.gen_range(1..6);
+        variant.drain(..trimmed_length);
+    }
+    variant
+}
+
+// Targets are the alleles of the loci, and queries are the variants of the alleles
+fn gen_targets_and_queries() -> (Vec<Vec<u8>>, Vec<Vec<u8>>) {
+    let mut rng = StdRng::seed_from_u64(SEED);
+    let targets: Vec<Vec<u8>> = (0..LOCUS_COUNT).flat_map(|_| {
+        let length = rng.gen_range(100..250);
+        let locus = gen_rand_text_with_rng(&mut rng, NUCLEOTIDES, length, length);
+        (0..ALLELE_COUNT).map(|_| gen_variant(&mut rng, &locus, 0.01, 1)).collect::<Vec<_>>()
+    }).collect();
+    let queries = (0..QUERY_COUNT).map(|_| {
+        let target = &targets[rng.gen_range(0..targets.len())];
+        gen_variant(&mut rng, target, 0.01, 1)
+    }).collect();
+    (targets, queries)
+}
+
+fn build_reference(targets: &[Vec<u8>]) -> Reference {
+    targets.iter().enumerate().fold(ReferenceBuilder::new(), |builder, (index, target)| {
+        builder.add_target(&format!("allele_{}", index), target)
+    }).build().unwrap()
+}
+
+fn assert_global_alignments_are_equal_to_dpm<A: Algorithm, F>(
+    algorithm: A,
+    substitution_matrix: &SubstitutionMatrix,
+    (gap_open_penalty, gap_extend_penalty, minimum_length, maximum_penalty_per_length): (u32, u32, u32, f32),
+    dp_global: F,
+) where
+    F: Fn(&[u8], &[u8]) -> Vec<Alignment>,
+{
+    let (targets, queries) = gen_targets_and_queries();
+    let reference = build_reference(&targets);
+    let mut aligner = Aligner::new(algorithm);
+
+    let mut alignment_count = 0;
+    for query in queries.iter() {
+        let result = aligner.align(query, &reference);
+        for (target_index, target) in targets.iter().enumerate() {
+            let alignments = result.0.iter()
+                .find(|target_alignment| target_alignment.index == target_index as u32)
+                .map(|target_alignment| target_alignment.alignments.clone())
+                .unwrap_or_default();
+            // At most one alignment consuming both sequences
+            assert!(alignments.len() <= 1);
+            alignments.iter().for_each(|alignment| {
+                assert_eq!(
+                    alignment.position,
+                    AlignmentPosition { query: (0, query.len() as u32), target: (0, target.len() as u32) },
+                );
+                assert_alignment_is_valid(
+                    alignment, query, target, substitution_matrix,
+                    (gap_open_penalty, gap_extend_penalty), minimum_length, maximum_penalty_per_length,
+                );
+            });
+            alignment_count += alignments.len();
+
+            // The alignment of DPM is found with the same penalty
+            let dpm_alignments = dp_global(query, target);
+            if let Some(dpm_alignment) = dpm_alignments.first() {
+                let alignment = alignments.first().unwrap_or_else(|| panic!(
+                    "Alignment of DPM is not found in target {}: {:?}", target_index, dpm_alignment,
+                ));
+                assert_eq!(alignment.penalty, dpm_alignment.penalty);
+            }
+        }
+    }
+    assert!(alignment_count > 0);
+}
+
+#[test]
+fn test_global_is_equal_to_dpm() {
+    init_logger();
+
+    for (px, po, pe, minl, maxp) in [
+        (4, 6, 2, 50, 0.1),
+        (4, 6, 2, 100, 0.05),
+        (3, 5, 1, 80, 0.08),
+    ] {
+        assert_global_alignments_are_equal_to_dpm(
+            Global::new(px, po, pe, minl, maxp).unwrap(),
+            &SubstitutionMatrix::new(px).unwrap(),
+            (po, pe, minl, maxp),
+            |query, target| dp_global_to_target(query, target, px, po, pe, minl, maxp),
+        );
+    }
+}
+
+#[test]
+fn test_global_with_substitution_matrix_is_equal_to_dpm() {
+    init_logger();
+
+    let substitution_matrix = SubstitutionMatrix::dna_transition_transversion(2, 4).unwrap();
+    let (po, pe, minl, maxp) = (6, 2, 50, 0.1);
+    assert_global_alignments_are_equal_to_dpm(
+        Global::with_substitution_matrix(substitution_matrix.clone(), po, pe, minl, maxp).unwrap(),
+        &substitution_matrix,
+        (po, pe, minl, maxp),
+        |query, target| dp_global_with_substitution_matrix_to_target(query, target, &substitution_matrix, po, pe, minl, maxp),
+    );
+}
+
+#[test]
+fn test_global_requires_both_ends() {
+    init_logger();
+
+    let mut rng = StdRng::seed_from_u64(SEED);
+    let target = gen_rand_text_with_rng(&mut rng, NUCLEOTIDES, 200, 200);
+    let reference = build_reference(&[target.clone()]);
+    let mut aligner = Aligner::new(Global::new(4, 6, 2, 50, 0.1).unwrap());
+
+    // Identical sequence
+    let result = aligner.align(&target, &reference);
+    assert_eq!(result.0[0].alignments[0].penalty, 0);
+
+    // Substring shorter than the target cannot satisfy the cutoff with the deletions
+    let result = aligner.align(&target[20..180], &reference);
+    assert!(result.0.is_empty());
+}
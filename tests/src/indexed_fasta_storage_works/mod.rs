@@ -0,0 +1,172 @@
+use crate::common::{
+    init_logger,
+    test_data::DataForValidation,
+    directory_path::get_target_dir,
+    random_text_and_pattern::gen_rand_text,
+};
+
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use sigalign_utils::sequence_reader::{
+    fasta::FastaReader,
+    SeqRecord as _,
+};
+use sigalign_core::{
+    aligner::{AlignmentRegulator, local::LocalAligner},
+    reference::{
+        Reference as RawReference,
+        SequenceStorage,
+        SequenceBuffer as _,
+        extensions::{Serialize, LabelStorage},
+    },
+};
+use sigalign_impl::{
+    pattern_index::dynamic_lfi::{DynamicLfi, DynamicLfiOption, BwtBlockSize},
+    sequence_storage::{
+        in_memory::InMemoryStorage,
+        indexed_fasta::IndexedFastaStorage,
+    },
+};
+
+const QUERY_COUNT: usize = 30;
+
+fn write_fasta_with_line_width(
+    file_path: &Path,
+    records: &[(String, Vec<u8>)],
+    line_width: usize,
+    line_terminator: &str,
+) {
+    let mut file = std::fs::File::create(file_path).unwrap();
+    for (label, sequence) in records {
+        write!(file, ">{} description{}", label, line_terminator).unwrap();
+        for line in sequence.chunks(line_width) {
+            write!(file, "{}{}", String::from_utf8_lossy(line), line_terminator).unwrap();
+        }
+    }
+}
+
+fn in_memory_storage_of_file(file_path: &Path) -> InMemoryStorage {
+    let mut in_memory_storage = InMemoryStorage::new();
+    in_memory_storage.add_fasta(std::fs::File::open(file_path).unwrap()).unwrap();
+    in_memory_storage
+}
+
+fn assert_same_sequences_and_labels(
+    in_memory_storage: &InMemoryStorage,
+    indexed_fasta_storage: &IndexedFastaStorage,
+) {
+    assert_eq!(in_memory_storage.num_targets(), indexed_fasta_storage.num_targets());
+    assert_eq!(in_memory_storage.get_total_length(), indexed_fasta_storage.get_total_length());
+    let mut buffer = indexed_fasta_storage.get_buffer();
+    for target_index in 0..in_memory_storage.num_targets() {
+        indexed_fasta_storage.fill_buffer(target_index, &mut buffer);
+        assert_eq!(
+            in_memory_storage.get_sequence_safely(target_index).unwrap(),
+            buffer.buffered_sequence(),
+        );
+        assert_eq!(
+            in_memory_storage.label_of_target_unchecked(target_index),
+            indexed_fasta_storage.label_of_target_unchecked(target_index),
+        );
+    }
+    assert_eq!(indexed_fasta_storage.get_sequence_safely(in_memory_storage.num_targets()), None);
+}
+
+#[test]
+fn test_indexed_fasta_storage_provides_same_sequences() {
+    init_logger();
+
+    let chr_list = b"ACGTNacgt";
+    let records: Vec<(String, Vec<u8>)> = [0, 1, 59, 60, 61, 120, 500, 1234].iter().enumerate().map(|(index, &length)| {
+        (format!("record_{}", index), gen_rand_text(chr_list, length, length))
+    }).collect();
+
+    let target_dir = get_target_dir().unwrap();
+    for (line_width, line_terminator) in [(60, "\n"), (60, "\r\n"), (7, "\n"), (10000, "\n")] {
+        let file_path = target_dir.join(format!(
+            "indexed_fasta_storage_{}_{}.fa", line_width, line_terminator.len(),
+        ));
+        write_fasta_with_line_width(&file_path, &records, line_width, line_terminator);
+
+        let in_memory_storage = in_memory_storage_of_file(&file_path);
+        let indexed_fasta_storage = IndexedFastaStorage::index_fasta_file(&file_path).unwrap();
+        assert_same_sequences_and_labels(&in_memory_storage, &indexed_fasta_storage);
+
+        // Load from the written `.fai` file
+        let loaded = IndexedFastaStorage::new(&file_path).unwrap();
+        assert_eq!(indexed_fasta_storage, loaded);
+
+        // `.fai` is readable by other tools
+        let mut indexed_reader = bio::io::fasta::IndexedReader::from_file(&file_path).unwrap();
+        let mut sequence = Vec::new();
+        for (label, expected) in &records {
+            indexed_reader.fetch_all(label).unwrap();
+            indexed_reader.read(&mut sequence).unwrap();
+            assert_eq!(expected, &sequence);
+        }
+
+        // Serialization keeps only the index
+        let mut buffer = Vec::new();
+        indexed_fasta_storage.save_to(&mut buffer).unwrap();
+        assert!(buffer.len() < in_memory_storage.get_total_length() as usize);
+        let deserialized = IndexedFastaStorage::load_from(&buffer[..]).unwrap();
+        assert_eq!(indexed_fasta_storage, deserialized);
+        assert_same_sequences_and_labels(&in_memory_storage, &deserialized);
+    }
+}
+
+#[test]
+fn test_indexing_rejects_inconsistent_line_length() {
+    init_logger();
+
+    let file_path = get_target_dir().unwrap().join("indexed_fasta_storage_invalid.fa");
+    std::fs::write(&file_path, b">a\nACGT\nAC\nACGT\n").unwrap();
+    assert!(IndexedFastaStorage::index_fasta_file(&file_path).is_err());
+    std::fs::write(&file_path, b">a\nACGT\nACGTA\n").unwrap();
+    assert!(IndexedFastaStorage::index_fasta_file(&file_path).is_err());
+}
+
+#[test]
+fn test_alignment_with_indexed_fasta_storage() {
+    init_logger();
+
+    let (ref_file, qry_file) = DataForValidation::Default.get_data_paths();
+    // Copy not to write the index in the test data directory
+    let file_path: PathBuf = get_target_dir().unwrap().join("indexed_fasta_storage_reference.fa");
+    std::fs::copy(&ref_file, &file_path).unwrap();
+
+    let in_memory_storage = in_memory_storage_of_file(&file_path);
+    let indexed_fasta_storage = IndexedFastaStorage::index_fasta_file(&file_path).unwrap();
+    let pattern_index_option = DynamicLfiOption {
+        suffix_array_sampling_ratio: 1,
+        lookup_table_max_bytes_size: 64 * 1024,
+        lookup_table_kmer_size: None,
+        use_safe_guard: true,
+        bwt_block_size: BwtBlockSize::V64,
+    };
+    let in_memory_reference: RawReference<DynamicLfi, InMemoryStorage> = RawReference::new(
+        in_memory_storage, pattern_index_option.clone(),
+    ).unwrap();
+    let indexed_fasta_reference: RawReference<DynamicLfi, IndexedFastaStorage> = RawReference::new(
+        indexed_fasta_storage, pattern_index_option,
+    ).unwrap();
+
+    let regulator = AlignmentRegulator::new(4, 6, 2, 50, 0.1).unwrap();
+    let mut aligner = LocalAligner::new(regulator);
+    let target_indices: Vec<u32> = (0..in_memory_reference.num_targets()).collect();
+    let mut in_memory_buffer = in_memory_reference.get_sequence_storage().get_buffer();
+    let mut indexed_fasta_buffer = indexed_fasta_reference.get_sequence_storage().get_buffer();
+
+    let mut fasta_reader = FastaReader::from_path(&qry_file).unwrap();
+    let mut query = Vec::new();
+    for _ in 0..QUERY_COUNT {
+        let Some(mut record) = fasta_reader.next() else { break };
+        query.clear();
+        record.extend_seq_buf(&mut query);
+        let mut expected = aligner.align(&query, &in_memory_reference, &mut in_memory_buffer, &target_indices);
+        let mut result = aligner.align(&query, &indexed_fasta_reference, &mut indexed_fasta_buffer, &target_indices);
+        expected.0.sort_by_key(|x| x.index);
+        result.0.sort_by_key(|x| x.index);
+        assert_eq!(format!("{:?}", expected), format!("{:?}", result));
+    }
+}
@@ -0,0 +1,289 @@
+use crate::common::{
+    init_logger,
+    random_text_and_pattern::gen_rand_text_with_rng,
+    test_data::DataForValidation,
+    dynamic_programming_matrix::{
+        dp_local_with_substitution_matrix_to_target,
+        dp_semi_global_with_substitution_matrix_to_target,
+    },
+    substitution_matrix_validation::assert_alignment_is_valid,
+};
+
+use rand::{Rng, SeedableRng, rngs::StdRng};
+use sigalign_utils::sequence_reader::{
+    fasta::FastaReader,
+    SeqRecord as _,
+};
+use sigalign::{
+    Aligner,
+    Reference,
+    ReferenceBuilder,
+    algorithms::{Algorithm, Local, SemiGlobal, SubstitutionMatrix},
+    results::{Alignment, AlignmentOperation, QueryAlignment},
+};
+
+const QUERY_COUNT: usize = 20;
+const QUERY_AMBIGUITY_INTERVAL: usize = 11;
+const REFERENCE_AMBIGUITY_INTERVAL: usize = 47;
+const PARAMS: (u32, u32, u32, u32, f32) = (4, 6, 2, 50, 0.1);
+const NUCLEOTIDES: &[u8] = b"ACGT";
+const SEED: u64 = 22;
+
+fn get_reference() -> Reference {
+    let (ref_file, _) = DataForValidation::Default.get_data_paths();
+    ReferenceBuilder::new()
+        .add_fasta_file(&ref_file).unwrap()
+        .build().unwrap()
+}
+
+fn get_queries() -> Vec<Vec<u8>> {
+    let (_, qry_file) = DataForValidation::Default.get_data_paths();
+    let mut fasta_reader = FastaReader::from_path(&qry_file).unwrap();
+    let mut queries = Vec::new();
+    while let Some(mut record) = fasta_reader.next() {
+        if queries.len() == QUERY_COUNT {
+            break
+        }
+        let mut query = Vec::new();
+        record.extend_seq_buf(&mut query);
+        queries.push(query);
+    }
+    queries
+}
+
+// Replace the bases periodically to the ambiguity codes containing the bases
+fn to_ambiguous_sequence(sequence: &[u8], interval: usize) -> Vec<u8> {
+    sequence.iter().enumerate().map(|(index, &base)| {
+        if index % interval != interval / 2 {
+            return base
+        }
+        match base {
+            b'A' => b'R',
+            b'C' => b'Y',
+            b'G' => b'S',
+            b'T' => b'W',
+            _ => base,
+        }
+    }).collect()
+}
+
+// Replace the bases periodically to `N`
+fn to_masked_sequence(sequence: &[u8], interval: usize) -> Vec<u8> {
+    sequence.iter().enumerate().map(|(index, &base)| {
+        if index % interval == interval / 2 { b'N' } else { base }
+    }).collect()
+}
+
+
+fn get_results<A: Algorithm>(algorithm: A, reference: &Reference, queries: &[Vec<u8>]) -> Vec<String> {
+    let mut aligner = Aligner::new(algorithm);
+    queries.iter().map(|query| {
+        let mut result = aligner.align(query, reference);
+        result.0.sort_by_key(|x| x.index);
+        format!("{:?}", result)
+    }).collect()
+}
+
+// Return (alignment count, count of the matched pairs of different bytes)
+fn validate_results(
+    result: QueryAlignment,
+    query: &[u8],
+    reference: &Reference,
+    substitution_matrix: &SubstitutionMatrix,
+) -> (usize, usize) {
+    let (_, po, pe, minl, maxp) = PARAMS;
+    let mut alignment_count = 0;
+    let mut equivalent_match_count = 0;
+    for target_alignment in result.0 {
+        let target = reference.get_sequence(target_alignment.index).unwrap();
+        for alignment in target_alignment.alignments {
+            assert_alignment_is_valid(&alignment, query, &target, substitution_matrix, (po, pe), minl, maxp);
+            alignment_count += 1;
+
+            let (mut query_index, mut target_index) = (alignment.position.query.0 as usize, alignment.position.target.0 as usize);
+            for operations in alignment.operations.iter() {
+                let count = operations.count as usize;
+                match operations.operation {
+                    AlignmentOperation::Match => {
+                        equivalent_match_count += (0..count).filter(|offset| {
+                            query[query_index + offset] != target[target_index + offset]
+                        }).count();
+                        query_index += count;
+                        target_index += count;
+                    },
+                    AlignmentOperation::Subst => {
+                        query_index += count;
+                        target_index += count;
+                    },
+                    AlignmentOperation::Insertion => query_index += count,
+                    AlignmentOperation::Deletion => target_index += count,
+                }
+            }
+        }
+    }
+    (alignment_count, equivalent_match_count)
+}
+
+#[test]
+fn test_iupac_matrix_with_mismatch_penalty_gives_same_results() {
+    init_logger();
+
+    let reference = get_reference();
+    let queries = get_queries();
+    let (px, po, pe, minl, maxp) = PARAMS;
+    let substitution_matrix = SubstitutionMatrix::iupac(px, px).unwrap();
+    assert_eq!(
+        get_results(Local::with_substitution_matrix(substitution_matrix, po, pe, minl, maxp).unwrap(), &reference, &queries),
+        get_results(Local::new(px, po, pe, minl, maxp).unwrap(), &reference, &queries),
+    );
+}
+
+#[test]
+fn test_ambiguity_codes_in_query_are_matched() {
+    init_logger();
+
+    let reference = get_reference();
+    let queries: Vec<Vec<u8>> = get_queries().iter().map(|query| to_ambiguous_sequence(query, QUERY_AMBIGUITY_INTERVAL)).collect();
+    let (px, po, pe, minl, maxp) = PARAMS;
+    let substitution_matrix = SubstitutionMatrix::iupac(px, 0).unwrap();
+
+    let mut local_aligner = Aligner::new(
+        Local::with_substitution_matrix(substitution_matrix.clone(), po, pe, minl, maxp).unwrap()
+    );
+    let mut semi_global_aligner = Aligner::new(
+        SemiGlobal::with_substitution_matrix(substitution_matrix.clone(), po, pe, minl, maxp).unwrap()
+    );
+    let mut total_alignment_count = 0;
+    let mut total_equivalent_match_count = 0;
+    for query in queries.iter() {
+        for result in [
+            local_aligner.align(query, &reference),
+            semi_global_aligner.align(query, &reference),
+        ] {
+            let (alignment_count, equivalent_match_count) = validate_results(result, query, &reference, &substitution_matrix);
+            total_alignment_count += alignment_count;
+            total_equivalent_match_count += equivalent_match_count;
+        }
+    }
+    assert!(total_alignment_count > 0);
+    assert!(total_equivalent_match_count > 0);
+}
+
+#[test]
+fn test_ambiguity_codes_in_reference_are_aligned() {
+    init_logger();
+
+    let plain_reference = get_reference();
+    let reference = (0..plain_reference.get_num_targets()).fold(ReferenceBuilder::new(), |builder, target_index| {
+        let label = plain_reference.get_label(target_index).unwrap();
+        let sequence = plain_reference.get_sequence(target_index).unwrap();
+        builder.add_target(&label, &to_ambiguous_sequence(&sequence, REFERENCE_AMBIGUITY_INTERVAL))
+    }).build().unwrap();
+    let queries = get_queries();
+    let (px, po, pe, minl, maxp) = PARAMS;
+
+    for ambiguity_penalty in [0, 2] {
+        let substitution_matrix = SubstitutionMatrix::iupac(px, ambiguity_penalty).unwrap();
+        let mut aligner = Aligner::new(
+            Local::with_substitution_matrix(substitution_matrix.clone(), po, pe, minl, maxp).unwrap()
+        );
+        let mut total_alignment_count = 0;
+        let mut total_equivalent_match_count = 0;
+        for query in queries.iter() {
+            let result = aligner.align(query, &reference);
+            let (alignment_count, equivalent_match_count) = validate_results(result, query, &reference, &substitution_matrix);
+            total_alignment_count += alignment_count;
+            total_equivalent_match_count += equivalent_match_count;
+        }
+        assert!(total_alignment_count > 0);
+        assert_eq!(total_equivalent_match_count > 0, ambiguity_penalty == 0);
+    }
+}
+
+#[test]
+fn test_zero_ambiguity_penalty_finds_all_alignments_of_dpm() {
+    init_logger();
+
+    let mut rng = StdRng::seed_from_u64(SEED);
+    let (px, po, pe, minl, maxp) = PARAMS;
+    let substitution_matrix = SubstitutionMatrix::iupac(px, 0).unwrap();
+    let mut local_aligner = Aligner::new(
+        Local::with_substitution_matrix(substitution_matrix.clone(), po, pe, minl, maxp).unwrap()
+    );
+    let mut semi_global_aligner = Aligner::new(
+        SemiGlobal::with_substitution_matrix(substitution_matrix.clone(), po, pe, minl, maxp).unwrap()
+    );
+
+    // (query, region of the target): ambiguity codes in the reference, in the query, and in both
+    let sequence = gen_rand_text_with_rng(&mut rng, NUCLEOTIDES, 120, 120);
+    // Too many patterns are matched to the run of `N`
+    let mut sequence_with_masked_run = sequence.clone();
+    sequence_with_masked_run[40..80].fill(b'N');
+    let cases = [
+        (sequence.clone(), to_masked_sequence(&sequence, 8)),
+        (to_masked_sequence(&sequence, 6), sequence.clone()),
+        (to_masked_sequence(&sequence, 6), to_ambiguous_sequence(&sequence, 7)),
+        (to_ambiguous_sequence(&sequence, 5), to_masked_sequence(&sequence, 9)),
+        (sequence_with_masked_run, to_ambiguous_sequence(&sequence, 7)),
+    ];
+    for (query, region) in cases {
+        let mut target = gen_rand_text_with_rng(&mut rng, NUCLEOTIDES, 30, 30);
+        target.extend_from_slice(&region);
+        target.extend(gen_rand_text_with_rng(&mut rng, NUCLEOTIDES, 30, 30));
+        let reference = ReferenceBuilder::new().add_target("target", &target).build().unwrap();
+
+        let local_alignments = get_alignments(local_aligner.align(&query, &reference));
+        let dpm_alignments = dp_local_with_substitution_matrix_to_target(&query, &target, &substitution_matrix, po, pe, minl, maxp);
+        assert_all_dpm_alignments_are_found(&local_alignments, &dpm_alignments);
+
+        let semi_global_alignments = get_alignments(semi_global_aligner.align(&query, &reference));
+        let dpm_alignments = dp_semi_global_with_substitution_matrix_to_target(&query, &target, &substitution_matrix, po, pe, minl, maxp);
+        assert_all_dpm_alignments_are_found(&semi_global_alignments, &dpm_alignments);
+    }
+}
+
+fn get_alignments(result: QueryAlignment) -> Vec<Alignment> {
+    result.0.into_iter().flat_map(|target_alignment| target_alignment.alignments).collect()
+}
+
+fn assert_all_dpm_alignments_are_found(alignments: &[Alignment], dpm_alignments: &[Alignment]) {
+    assert!(!dpm_alignments.is_empty());
+    dpm_alignments.iter().for_each(|dpm_alignment| {
+        assert!(
+            alignments.iter().any(|alignment| {
+                alignment.position == dpm_alignment.position && alignment.penalty == dpm_alignment.penalty
+            }),
+            "Alignment of DPM is not found: {:?}\nAlignments: {:?}", dpm_alignment, alignments,
+        );
+    });
+}
+
+#[test]
+fn test_run_of_n_in_query_is_not_seeded() {
+    init_logger();
+
+    let mut rng = StdRng::seed_from_u64(SEED);
+    let (px, po, pe, minl, maxp) = PARAMS;
+    let substitution_matrix = SubstitutionMatrix::iupac(px, 0).unwrap();
+    let mut aligner = Aligner::new(
+        Local::with_substitution_matrix(substitution_matrix.clone(), po, pe, minl, maxp).unwrap()
+    );
+
+    let target = gen_rand_text_with_rng(&mut rng, NUCLEOTIDES, 2000, 2000);
+    let reference = ReferenceBuilder::new().add_target("target", &target).build().unwrap();
+
+    // Only `N`: matched to everywhere, but not seedable
+    let masked_query = vec![b'N'; 200];
+    assert!(aligner.align(&masked_query, &reference).0.is_empty());
+
+    // The flanks of the run are seeded, and the run is aligned as matches
+    let mut query = target[500..800].to_vec();
+    query[100..200].fill(b'N');
+    let result = aligner.align(&query, &reference);
+    let (alignment_count, equivalent_match_count) = validate_results(result.clone(), &query, &reference, &substitution_matrix);
+    assert!(alignment_count > 0);
+    assert!(equivalent_match_count >= 100);
+    assert!(get_alignments(result).iter().any(|alignment| {
+        alignment.position.query == (0, 300) && alignment.position.target == (500, 800)
+    }));
+}
@@ -0,0 +1,103 @@
+use crate::common::{
+    init_logger,
+    test_data::DataForValidation,
+    pattern_index_option::get_dynamic_lfi_option,
+    random_text_and_pattern::gen_rand_text,
+};
+
+use sigalign_utils::sequence_reader::{
+    fasta::FastaReader,
+    SeqRecord as _,
+};
+use sigalign_core::{
+    aligner::{AlignmentRegulator, local::LocalAligner},
+    reference::{
+        Reference as RawReference,
+        PatternIndex,
+        SequenceStorage,
+        extensions::{Serialize, EstimateSize},
+    },
+};
+use sigalign_impl::{
+    pattern_index::{
+        dynamic_lfi::DynamicLfi,
+        kmer_hash::{KmerHashIndex, KmerHashIndexOption},
+    },
+    sequence_storage::in_memory::InMemoryStorage,
+};
+
+const QUERY_COUNT: usize = 30;
+
+#[test]
+fn test_kmer_hash_index_locates_same_positions() {
+    init_logger();
+
+    let text = gen_rand_text(b"ACGT", 20_000, 20_000);
+    let dynamic_lfi = DynamicLfi::new(text.clone(), get_dynamic_lfi_option(1)).unwrap();
+    let kmer_hash_index = KmerHashIndex::new(
+        text.clone(), KmerHashIndexOption::new(vec![12, 8, 8, 0]),
+    ).unwrap();
+    assert_eq!(kmer_hash_index.get_pattern_sizes(), vec![8, 12]);
+
+    // Indexed (8, 12), longer (9, 15, 30) and shorter (3, 5) sizes
+    for pattern_size in [3, 5, 8, 9, 12, 15, 30] {
+        for start in (0..text.len() - pattern_size).step_by(311) {
+            let pattern = &text[start..start + pattern_size];
+            assert_eq!(
+                dynamic_lfi.get_sorted_positions(pattern),
+                kmer_hash_index.get_sorted_positions(pattern),
+            );
+        }
+        // Not existing pattern
+        let pattern = vec![b'N'; pattern_size];
+        assert!(kmer_hash_index.get_sorted_positions(&pattern).is_empty());
+    }
+
+    // Serialization
+    let mut buffer = Vec::new();
+    kmer_hash_index.save_to(&mut buffer).unwrap();
+    assert_eq!(buffer.len(), kmer_hash_index.serialized_size());
+    let loaded = KmerHashIndex::load_from(&buffer[..]).unwrap();
+    assert_eq!(kmer_hash_index, loaded);
+}
+
+#[test]
+fn test_kmer_hash_index_requires_pattern_size() {
+    let result = KmerHashIndex::new(b"ACGT".to_vec(), KmerHashIndexOption::new(vec![0]));
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_alignment_with_kmer_hash_index() {
+    init_logger();
+
+    let (ref_file, qry_file) = DataForValidation::Default.get_data_paths();
+    let mut in_memory_storage = InMemoryStorage::new();
+    in_memory_storage.add_fasta(std::fs::File::open(ref_file).unwrap()).unwrap();
+
+    let regulator = AlignmentRegulator::new(4, 6, 2, 50, 0.1).unwrap();
+    let lfi_reference: RawReference<DynamicLfi, InMemoryStorage> = RawReference::new(
+        in_memory_storage.clone(), get_dynamic_lfi_option(1),
+    ).unwrap();
+    let kmer_reference: RawReference<KmerHashIndex, InMemoryStorage> = RawReference::new(
+        in_memory_storage, KmerHashIndexOption::new(vec![regulator.get_pattern_size()]),
+    ).unwrap();
+
+    let mut aligner = LocalAligner::new(regulator);
+    let target_indices: Vec<u32> = (0..lfi_reference.num_targets()).collect();
+    let mut lfi_buffer = lfi_reference.get_sequence_storage().get_buffer();
+    let mut kmer_buffer = kmer_reference.get_sequence_storage().get_buffer();
+
+    let mut fasta_reader = FastaReader::from_path(&qry_file).unwrap();
+    let mut query = Vec::new();
+    for _ in 0..QUERY_COUNT {
+        let Some(mut record) = fasta_reader.next() else { break };
+        query.clear();
+        record.extend_seq_buf(&mut query);
+        let mut expected = aligner.align(&query, &lfi_reference, &mut lfi_buffer, &target_indices);
+        let mut result = aligner.align(&query, &kmer_reference, &mut kmer_buffer, &target_indices);
+        expected.0.sort_by_key(|x| x.index);
+        result.0.sort_by_key(|x| x.index);
+        assert_eq!(format!("{:?}", expected), format!("{:?}", result));
+    }
+}
@@ -0,0 +1,104 @@
+use crate::common::{
+    init_logger,
+    test_data::DataForValidation,
+    pattern_index_option::get_dynamic_lfi_option,
+};
+
+use sigalign_utils::sequence_reader::{
+    fasta::FastaReader,
+    SeqRecord as _,
+};
+use sigalign_core::{
+    aligner::{AlignmentRegulator, local::LocalAligner},
+    reference::{
+        Reference as RawReference,
+        PatternIndex,
+        SequenceStorage,
+        extensions::Serialize,
+    },
+};
+use sigalign_impl::{
+    pattern_index::{
+        static_lfi::{Lfi32B3V64, Lfi64B3V64, LfiOption},
+        dynamic_lfi::{DynamicLfi, DynamicLfi64},
+    },
+    sequence_storage::in_memory::InMemoryStorage,
+};
+
+const QUERY_COUNT: usize = 30;
+
+fn get_in_memory_storage() -> InMemoryStorage {
+    let (ref_file, _) = DataForValidation::Default.get_data_paths();
+    let mut in_memory_storage = InMemoryStorage::new();
+    in_memory_storage.add_fasta(std::fs::File::open(ref_file).unwrap()).unwrap();
+    in_memory_storage
+}
+#[test]
+fn test_64_bit_index_locates_same_positions() {
+    init_logger();
+
+    let text = b"ACGTACGTTTGACCAGTACGATCAGCATCGACTACGACGTACGATCAGCTAGCTAG".repeat(20);
+    let lfi_option = LfiOption::new(2, 1024, true);
+    let lfi_32 = Lfi32B3V64::new(text.clone(), lfi_option.clone()).unwrap();
+    let lfi_64 = Lfi64B3V64::new(text.clone(), lfi_option).unwrap();
+    let dynamic_lfi_32 = DynamicLfi::new(text.clone(), get_dynamic_lfi_option(2)).unwrap();
+    let dynamic_lfi_64 = DynamicLfi64::new(text.clone(), get_dynamic_lfi_option(2)).unwrap();
+
+    for pattern_length in [1, 3, 8, 20] {
+        for start in (0..text.len() - pattern_length).step_by(37) {
+            let pattern = &text[start..start + pattern_length];
+            let expected = lfi_32.get_sorted_positions(pattern);
+            let as_u64: Vec<u64> = expected.iter().map(|&x| x as u64).collect();
+            assert_eq!(as_u64, lfi_64.get_sorted_positions(pattern));
+            assert_eq!(expected, dynamic_lfi_32.get_sorted_positions(pattern));
+            assert_eq!(as_u64, dynamic_lfi_64.get_sorted_positions(pattern));
+        }
+    }
+
+    // Serialized indices are distinguished
+    let mut buffer = Vec::new();
+    dynamic_lfi_64.save_to(&mut buffer).unwrap();
+    assert!(DynamicLfi::load_from(&buffer[..]).is_err());
+    let loaded = DynamicLfi64::load_from(&buffer[..]).unwrap();
+    assert_eq!(
+        dynamic_lfi_64.get_sorted_positions(b"ACGT"),
+        loaded.get_sorted_positions(b"ACGT"),
+    );
+}
+
+#[test]
+fn test_alignment_with_64_bit_reference() {
+    init_logger();
+
+    let (_, qry_file) = DataForValidation::Default.get_data_paths();
+    let reference_32: RawReference<DynamicLfi, InMemoryStorage> = RawReference::new(
+        get_in_memory_storage(), get_dynamic_lfi_option(2),
+    ).unwrap();
+    let reference_64: RawReference<DynamicLfi64, InMemoryStorage> = RawReference::new(
+        get_in_memory_storage(), get_dynamic_lfi_option(2),
+    ).unwrap();
+
+    // Save and load keep the 64-bit positions
+    let mut buffer = Vec::new();
+    reference_64.save_to(&mut buffer).unwrap();
+    let reference_64: RawReference<DynamicLfi64, InMemoryStorage> = RawReference::load_from(&buffer[..]).unwrap();
+
+    let regulator = AlignmentRegulator::new(4, 6, 2, 50, 0.1).unwrap();
+    let mut aligner = LocalAligner::new(regulator);
+    let target_indices: Vec<u32> = (0..reference_32.num_targets()).collect();
+    let mut buffer_32 = reference_32.get_sequence_storage().get_buffer();
+    let mut buffer_64 = reference_64.get_sequence_storage().get_buffer();
+
+    let mut fasta_reader = FastaReader::from_path(&qry_file).unwrap();
+    let mut query = Vec::new();
+    for _ in 0..QUERY_COUNT {
+        let Some(mut record) = fasta_reader.next() else { break };
+        query.clear();
+        record.extend_seq_buf(&mut query);
+        let mut expected = aligner.align(&query, &reference_32, &mut buffer_32, &target_indices);
+        let mut result = aligner.align(&query, &reference_64, &mut buffer_64, &target_indices);
+        expected.0.sort_by_key(|x| x.index);
+        result.0.sort_by_key(|x| x.index);
+        assert_eq!(format!("{:?}", expected), format!("{:?}", result));
+    }
+}
@@ -17,3 +17,29 @@ pub mod result_validation_with_dynamic_programming_matrix;
 // Reference acts expectedly
 mod reference_gives_correct_data;
 mod reference_save_and_load;
+mod target_subset_restricts_alignment;
+mod strand_option_works;
+mod sam_output_is_valid;
+mod paf_output_works;
+mod blast_tabular_output_works;
+mod pairwise_text_rendering;
+mod parallel_aligner_keeps_order;
+mod sequence_file_alignment_works;
+mod reference_mmap_load;
+mod indexed_fasta_storage_works;
+mod packed_dna_storage_works;
+mod large_position_index_works;
+mod sharded_reference_works;
+mod kmer_hash_index_works;
+mod suffix_array_index_works;
+mod reference_index_tuning;
+mod reference_append_targets;
+mod reference_target_exclusion;
+mod reference_metadata_and_verify;
+mod reference_file_upgrade;
+mod substitution_matrix_alignment;
+mod iupac_matching;
+mod protein_alignment;
+mod translated_search;
+mod global_alignment;
+mod wave_front_edge_cases;
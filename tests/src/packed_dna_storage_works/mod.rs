@@ -0,0 +1,147 @@
+use crate::common::{
+    init_logger,
+    test_data::DataForValidation,
+};
+
+use rand::{Rng, SeedableRng, rngs::StdRng};
+use sigalign_utils::sequence_reader::{
+    fasta::FastaReader,
+    SeqRecord as _,
+};
+use sigalign_core::{
+    aligner::{AlignmentRegulator, local::LocalAligner},
+    reference::{
+        Reference as RawReference,
+        SequenceStorage,
+        extensions::{Serialize, EstimateSize, LabelStorage},
+    },
+};
+use sigalign_impl::{
+    pattern_index::dynamic_lfi::{DynamicLfi, DynamicLfiOption, BwtBlockSize},
+    sequence_storage::{
+        in_memory::InMemoryStorage,
+        packed_dna::PackedDnaStorage,
+    },
+};
+
+const QUERY_COUNT: usize = 30;
+
+/// Sequence of `ACGT` with soft-masked regions, runs of `N` and IUPAC codes.
+fn gen_dna_with_exceptions(rng: &mut StdRng, length: usize) -> Vec<u8> {
+    let mut sequence = Vec::with_capacity(length);
+    while sequence.len() < length {
+        let run_length = rng.gen_range(1..30).min(length - sequence.len());
+        match rng.gen_range(0..10) {
+            0 => sequence.resize(sequence.len() + run_length, b'N'),
+            1 => sequence.extend((0..run_length).map(|_| b"RYKMSWBDHVn-"[rng.gen_range(0..12)])),
+            2 | 3 => sequence.extend((0..run_length).map(|_| b"acgt"[rng.gen_range(0..4)])),
+            _ => sequence.extend((0..run_length).map(|_| b"ACGT"[rng.gen_range(0..4)])),
+        }
+    }
+    sequence
+}
+
+#[test]
+fn test_packed_dna_storage_restores_sequences() {
+    init_logger();
+
+    let mut rng = StdRng::seed_from_u64(0);
+    let mut in_memory_storage = InMemoryStorage::new();
+    let mut packed_dna_storage = PackedDnaStorage::new();
+    for (index, length) in [0, 1, 3, 4, 5, 100, 1001, 5000].into_iter().enumerate() {
+        let sequence = gen_dna_with_exceptions(&mut rng, length);
+        let label = format!("target_{}", index);
+        in_memory_storage.add_target(&label, &sequence);
+        packed_dna_storage.add_target(&label, &sequence);
+    }
+
+    let assert_same = |packed_dna_storage: &PackedDnaStorage| {
+        assert_eq!(in_memory_storage.num_targets(), packed_dna_storage.num_targets());
+        assert_eq!(in_memory_storage.get_total_length(), packed_dna_storage.get_total_length());
+        for target_index in 0..in_memory_storage.num_targets() {
+            assert_eq!(
+                in_memory_storage.get_sequence_safely(target_index),
+                packed_dna_storage.get_sequence_safely(target_index),
+            );
+            assert_eq!(
+                in_memory_storage.label_of_target_unchecked(target_index),
+                packed_dna_storage.label_of_target_unchecked(target_index),
+            );
+        }
+        assert_eq!(packed_dna_storage.get_sequence_safely(in_memory_storage.num_targets()), None);
+        assert_eq!(
+            in_memory_storage.get_concatenated_sequence_with_boundaries_of_targets(),
+            packed_dna_storage.get_concatenated_sequence_with_boundaries_of_targets(),
+        );
+    };
+    assert_same(&packed_dna_storage);
+
+    // Serialization
+    let mut buffer = Vec::new();
+    packed_dna_storage.save_to(&mut buffer).unwrap();
+    assert_eq!(buffer.len(), packed_dna_storage.serialized_size());
+    let deserialized = PackedDnaStorage::load_from(&buffer[..]).unwrap();
+    assert_eq!(packed_dna_storage, deserialized);
+    assert_same(&deserialized);
+}
+
+#[test]
+fn test_packed_dna_storage_is_smaller() {
+    init_logger();
+
+    let (ref_file, _) = DataForValidation::Default.get_data_paths();
+    let mut in_memory_storage = InMemoryStorage::new();
+    in_memory_storage.add_fasta(std::fs::File::open(&ref_file).unwrap()).unwrap();
+    let mut packed_dna_storage = PackedDnaStorage::new();
+    packed_dna_storage.add_fasta(std::fs::File::open(&ref_file).unwrap()).unwrap();
+
+    let total_length = in_memory_storage.get_total_length() as usize;
+    let in_memory_sequence_size = in_memory_storage.serialized_size();
+    let packed_sequence_size = packed_dna_storage.serialized_size();
+    assert!(in_memory_sequence_size > total_length);
+    assert!(packed_sequence_size < total_length / 3);
+}
+
+#[test]
+fn test_alignment_with_packed_dna_storage() {
+    init_logger();
+
+    let (ref_file, qry_file) = DataForValidation::Default.get_data_paths();
+    let mut in_memory_storage = InMemoryStorage::new();
+    in_memory_storage.add_fasta(std::fs::File::open(&ref_file).unwrap()).unwrap();
+    let mut packed_dna_storage = PackedDnaStorage::new();
+    packed_dna_storage.add_fasta(std::fs::File::open(&ref_file).unwrap()).unwrap();
+
+    let pattern_index_option = DynamicLfiOption {
+        suffix_array_sampling_ratio: 1,
+        lookup_table_max_bytes_size: 64 * 1024,
+        lookup_table_kmer_size: None,
+        use_safe_guard: true,
+        bwt_block_size: BwtBlockSize::V64,
+    };
+    let in_memory_reference: RawReference<DynamicLfi, InMemoryStorage> = RawReference::new(
+        in_memory_storage, pattern_index_option.clone(),
+    ).unwrap();
+    let packed_dna_reference: RawReference<DynamicLfi, PackedDnaStorage> = RawReference::new(
+        packed_dna_storage, pattern_index_option,
+    ).unwrap();
+
+    let regulator = AlignmentRegulator::new(4, 6, 2, 50, 0.1).unwrap();
+    let mut aligner = LocalAligner::new(regulator);
+    let target_indices: Vec<u32> = (0..in_memory_reference.num_targets()).collect();
+    let mut in_memory_buffer = in_memory_reference.get_sequence_storage().get_buffer();
+    let mut packed_dna_buffer = packed_dna_reference.get_sequence_storage().get_buffer();
+
+    let mut fasta_reader = FastaReader::from_path(&qry_file).unwrap();
+    let mut query = Vec::new();
+    for _ in 0..QUERY_COUNT {
+        let Some(mut record) = fasta_reader.next() else { break };
+        query.clear();
+        record.extend_seq_buf(&mut query);
+        let mut expected = aligner.align(&query, &in_memory_reference, &mut in_memory_buffer, &target_indices);
+        let mut result = aligner.align(&query, &packed_dna_reference, &mut packed_dna_buffer, &target_indices);
+        expected.0.sort_by_key(|x| x.index);
+        result.0.sort_by_key(|x| x.index);
+        assert_eq!(format!("{:?}", expected), format!("{:?}", result));
+    }
+}
@@ -0,0 +1,43 @@
+use crate::common::init_logger;
+
+use sigalign_utils::sequence_manipulation::reverse_complementary::reverse_complement_of_dna_sequence;
+use sigalign::{
+    Aligner,
+    ReferenceBuilder,
+    Strand,
+    algorithms::Local,
+};
+
+#[test]
+fn test_paf_line_of_known_alignment() {
+    init_logger();
+
+    let reference = ReferenceBuilder::new()
+        .add_target("target_1", b"TTAGTTGTGCCGCAGCGAAGTAGTGCTTGAAATATGCGACCCCTAAGTAGGAGCGTATGCGCCCAGTAACCAATGCCTGTTGAGATGCCAGACGCGTAACCAAAACATAGAAACCATCAATAGACAGGTCATAATCGGTC")
+        .build().unwrap();
+    let mut aligner = Aligner::new(Local::new(4, 6, 2, 20, 0.2).unwrap());
+
+    // One substitution and one deleted base
+    let query = b"TTTCGCAGCGAAGGAGTGCTTGAAATATGCGACCCCAAGTAGGAGCGTATGCGCCCAGTAACGG";
+    let result = reference.label_query_alignment(aligner.align(query, &reference));
+    let paf = result.to_paf("query", query.len() as u32, Strand::Forward, &reference);
+    assert_eq!(
+        paf,
+        "query\t64\t3\t62\t+\ttarget_1\t140\t10\t70\t58\t60\t255\tNM:i:2\tAS:i:-12\tcg:Z:33M1D26M\n",
+    );
+
+    let mut buffer = Vec::new();
+    result.write_as_paf(&mut buffer, "query", query.len() as u32, Strand::Forward, &reference).unwrap();
+    assert_eq!(String::from_utf8(buffer).unwrap(), paf);
+
+    // Reverse complement of the query is aligned to the reverse strand.
+    let reverse_complement = reverse_complement_of_dna_sequence(query);
+    let stranded_result = reference.label_stranded_query_alignment(
+        aligner.align_stranded(&reverse_complement, &reference, Strand::Both)
+    );
+    assert_eq!(stranded_result.forward.count_alignments(), 0);
+    assert_eq!(
+        stranded_result.to_paf("query", query.len() as u32, &reference),
+        paf.replace('+', "-").replace("\t3\t62\t", "\t2\t61\t"),
+    );
+}
@@ -0,0 +1,65 @@
+use crate::common::init_logger;
+
+use sigalign_utils::sequence_manipulation::reverse_complementary::reverse_complement_of_dna_sequence;
+use sigalign::{
+    Aligner,
+    ReferenceBuilder,
+    Strand,
+    algorithms::Local,
+    results::PairwiseTextOption,
+};
+
+#[test]
+fn test_pairwise_text_of_known_alignment() {
+    init_logger();
+
+    let reference = ReferenceBuilder::new()
+        .add_target("target_1", b"TTAGTTGTGCCGCAGCGAAGTAGTGCTTGAAATATGCGACCCCTAAGTAGGAGCGTATGCGCCCAGTAACCAATGCCTGTTGAGATGCCAGACGCGTAACCAAAACATAGAAACCATCAATAGACAGGTCATAATCGGTC")
+        .build().unwrap();
+    let mut aligner = Aligner::new(Local::new(4, 6, 2, 20, 0.2).unwrap());
+
+    // One substitution and one deleted base
+    let query = b"TTTCGCAGCGAAGGAGTGCTTGAAATATGCGACCCCAAGTAGGAGCGTATGCGCCCAGTAACGG";
+    let result = aligner.align(query, &reference);
+    let alignment = &result.0[0].alignments[0];
+
+    let option = PairwiseTextOption {
+        line_width: 25,
+        lowercase_mismatches: true,
+    };
+    let text = reference.alignment_to_pairwise_text(query, 0, alignment, Strand::Forward, &option).unwrap();
+    assert_eq!(text, concat!(
+        "Penalty: 12, Length: 60, Identity: 58/60 (96.7%), Gaps: 1/60 (1.7%)\n",
+        "\n",
+        "Query  4  CGCAGCGAAGgAGTGCTTGAAATAT  28\n",
+        "          ||||||||||.||||||||||||||\n",
+        "Target 11 CGCAGCGAAGtAGTGCTTGAAATAT  35\n",
+        "\n",
+        "Query  29 GCGACCCC-AAGTAGGAGCGTATGC  52\n",
+        "          |||||||| ||||||||||||||||\n",
+        "Target 36 GCGACCCCtAAGTAGGAGCGTATGC  60\n",
+        "\n",
+        "Query  53 GCCCAGTAAC  62\n",
+        "          ||||||||||\n",
+        "Target 61 GCCCAGTAAC  70\n",
+    ));
+
+    // Without case-marking, the bases are written as is.
+    let default_text = reference.alignment_to_pairwise_text(query, 0, alignment, Strand::Forward, &PairwiseTextOption::default()).unwrap();
+    assert_eq!(default_text.lines().nth(2).unwrap(), "Query  4  CGCAGCGAAGGAGTGCTTGAAATATGCGACCCC-AAGTAGGAGCGTATGCGCCCAGTAAC  62");
+    assert_eq!(reference.alignment_to_pairwise_text(query, 1, alignment, Strand::Forward, &option), None);
+
+    // Reverse strand of the reverse complement is rendered in the coordinates of its reverse complement (the original query).
+    let reverse_complement = reverse_complement_of_dna_sequence(query);
+    let stranded_result = aligner.align_stranded(&reverse_complement, &reference, Strand::Both);
+    let reverse_alignment = &stranded_result.reverse.0[0].alignments[0];
+    let reverse_text = reference.alignment_to_pairwise_text(&reverse_complement, 0, reverse_alignment, Strand::Reverse, &option).unwrap();
+    assert_eq!(reverse_text, text);
+
+    // The alignment out of range of the sequences is not rendered.
+    assert_eq!(reference.alignment_to_pairwise_text(&query[..30], 0, alignment, Strand::Forward, &option), None);
+    assert_eq!(reference.alignment_to_pairwise_text(&query[..30], 0, alignment, Strand::Reverse, &option), None);
+    let mut overrun_alignment = alignment.clone();
+    overrun_alignment.operations.last_mut().unwrap().count += 1;
+    assert_eq!(reference.alignment_to_pairwise_text(query, 0, &overrun_alignment, Strand::Forward, &option), None);
+}
@@ -0,0 +1,74 @@
+use crate::common::{
+    init_logger,
+    test_data::DataForValidation,
+};
+
+use log::info;
+use sigalign_utils::sequence_reader::{
+    fasta::FastaReader,
+    SeqRecord as _,
+};
+use sigalign::{
+    Aligner,
+    ParallelAligner,
+    ReferenceBuilder,
+    Strand,
+    algorithms::Local,
+};
+
+#[test]
+fn test_parallel_aligner_gives_same_results_in_input_order() {
+    init_logger();
+
+    let (ref_file, qry_file) = DataForValidation::Default.get_data_paths();
+    let reference = ReferenceBuilder::new()
+        .add_fasta_file(&ref_file).unwrap()
+        .build().unwrap();
+
+    let mut queries = Vec::new();
+    let mut fasta_reader = FastaReader::from_path(&qry_file).unwrap();
+    for _ in 0..200 {
+        let Some(mut record) = fasta_reader.next() else { break };
+        let mut query = Vec::new();
+        record.extend_seq_buf(&mut query);
+        queries.push(query);
+    }
+
+    let mut aligner = Aligner::new(Local::new(4, 6, 2, 50, 0.1).unwrap());
+    let mut parallel_aligner = ParallelAligner::new(aligner.clone());
+
+    for (num_threads, chunk_size) in [(1, 1), (4, 7), (3, 100), (8, 0)] {
+        parallel_aligner.set_num_threads(num_threads);
+        parallel_aligner.set_chunk_size(chunk_size);
+        assert_eq!(parallel_aligner.get_num_threads(), num_threads);
+        assert_eq!(parallel_aligner.get_chunk_size(), chunk_size.max(1));
+
+        let mut count = 0;
+        for (index, (query, result)) in parallel_aligner.align_batch(queries.iter(), &reference).enumerate() {
+            assert_eq!(query, &queries[index]);
+            let mut result = result.0;
+            let mut expected = aligner.align(query, &reference).0;
+            result.sort_by_key(|x| x.index);
+            expected.sort_by_key(|x| x.index);
+            assert_eq!(result.len(), expected.len());
+            for (res, exp) in result.iter().zip(expected.iter()) {
+                assert_eq!(res.index, exp.index);
+                assert_eq!(res.alignments, exp.alignments);
+            }
+            count += 1;
+        }
+        assert_eq!(count, queries.len());
+
+        let stranded_count: usize = parallel_aligner.align_stranded_batch(queries.iter(), &reference, Strand::Both)
+            .zip(queries.iter())
+            .map(|((query, result), expected_query)| {
+                assert_eq!(query, expected_query);
+                assert_eq!(result.count_alignments(), aligner.align_stranded(query, &reference, Strand::Both).count_alignments());
+                result.count_alignments()
+            }).sum();
+        info!("{} threads with chunk size {}: {} stranded alignments", num_threads, chunk_size, stranded_count);
+    }
+
+    // Empty input
+    assert_eq!(parallel_aligner.align_batch(Vec::<Vec<u8>>::new(), &reference).count(), 0);
+}
@@ -0,0 +1,125 @@
+use crate::common::{
+    init_logger,
+    random_text_and_pattern::gen_rand_text_with_rng,
+    dynamic_programming_matrix::dp_semi_global_with_substitution_matrix_to_target,
+    substitution_matrix_validation::assert_alignment_is_valid,
+};
+
+use rand::{Rng, SeedableRng, rngs::StdRng};
+use sigalign::{
+    Aligner,
+    Reference,
+    ReferenceBuilder,
+    PROTEIN_ALPHABET,
+    algorithms::{SemiGlobal, SubstitutionMatrix},
+    results::{Alignment, TargetAlignment},
+};
+
+const AMINO_ACIDS: &[u8] = b"ACDEFGHIKLMNPQRSTVWY";
+const TARGET_COUNT: usize = 10;
+const QUERY_COUNT: usize = 30;
+const SEED: u64 = 23;
+
+
+// Substring of the target with the substitutions and an indel
+fn gen_query_from_target(rng: &mut StdRng, target: &[u8]) -> Vec<u8> {
+    let length = rng.gen_range(60..120);
+    let start = rng.gen_range(0..target.len() - length);
+    let mut query: Vec<u8> = target[start..start + length].iter().map(|&residue| {
+        if rng.gen_bool(0.03) {
+            AMINO_ACIDS[rng.gen_range(0..AMINO_ACIDS.len())]
+        } else {
+            residue
+        }
+    }).collect();
+    if rng.gen_bool(0.3) {
+        let position = rng.gen_range(0..query.len());
+        if rng.gen_bool(0.5) {
+            query.remove(position);
+        } else {
+            query.insert(position, AMINO_ACIDS[rng.gen_range(0..AMINO_ACIDS.len())]);
+        }
+    }
+    query
+}
+
+fn gen_targets_and_queries() -> (Vec<Vec<u8>>, Vec<Vec<u8>>) {
+    let mut rng = StdRng::seed_from_u64(SEED);
+    let targets: Vec<Vec<u8>> = (0..TARGET_COUNT).map(|_| {
+        let length = rng.gen_range(200..400);
+        gen_rand_text_with_rng(&mut rng, AMINO_ACIDS, length, length)
+    }).collect();
+    let queries = (0..QUERY_COUNT).map(|_| {
+        let target = &targets[rng.gen_range(0..targets.len())];
+        gen_query_from_target(&mut rng, target)
+    }).collect();
+    (targets, queries)
+}
+
+fn build_protein_reference(targets: &[Vec<u8>]) -> Reference {
+    targets.iter().enumerate().fold(ReferenceBuilder::new_protein(), |builder, (index, target)| {
+        builder.add_target(&format!("protein_{}", index), target)
+    }).build().unwrap()
+}
+
+fn without_operations(mut alignments: Vec<Alignment>) -> Vec<Alignment> {
+    alignments.iter_mut().for_each(|alignment| alignment.operations.clear());
+    alignments.sort_by_key(|alignment| (alignment.position.query, alignment.position.target, alignment.penalty));
+    alignments
+}
+
+#[test]
+fn test_protein_reference_builder_restricts_alphabet() {
+    let reference = ReferenceBuilder::new_protein()
+        .add_target("target_1", b"mkvUAJGOx*1")
+        .build().unwrap();
+    assert_eq!(reference.get_sequence(0).unwrap(), b"MKVXAXGXX*X".to_vec());
+
+    let build_configuration = reference.get_metadata().build_configuration.clone().unwrap();
+    assert_eq!(build_configuration.alphabet, Some((PROTEIN_ALPHABET.to_vec(), b'X')));
+}
+
+#[test]
+fn test_protein_semi_global_is_equal_to_dpm() {
+    init_logger();
+
+    let (targets, queries) = gen_targets_and_queries();
+    let reference = build_protein_reference(&targets);
+    let substitution_matrix = SubstitutionMatrix::blosum62();
+
+    for (po, pe, minl, maxp) in [(11, 1, 30, 0.3), (6, 2, 50, 0.2)] {
+        let mut aligner = Aligner::new(
+            SemiGlobal::with_substitution_matrix(substitution_matrix.clone(), po, pe, minl, maxp).unwrap()
+        );
+        let mut alignment_count = 0;
+        for query in queries.iter() {
+            let result = aligner.align(query, &reference);
+            for target_index in 0..reference.get_num_targets() {
+                let target = reference.get_sequence(target_index).unwrap();
+                let alignments = result.0.iter()
+                    .find(|target_alignment| target_alignment.index == target_index)
+                    .map(|target_alignment| target_alignment.clone().deduplicated().alignments)
+                    .unwrap_or_default();
+                alignments.iter().for_each(|alignment| {
+                    assert_alignment_is_valid(alignment, query, &target, &substitution_matrix, (po, pe), minl, maxp);
+                });
+                alignment_count += alignments.len();
+
+                // All alignments of DPM are found
+                let dpm_alignments = without_operations(dp_semi_global_with_substitution_matrix_to_target(
+                    query, &target, &substitution_matrix, po, pe, minl, maxp,
+                ));
+                let alignments = without_operations(alignments);
+                dpm_alignments.iter().for_each(|dpm_alignment| {
+                    assert!(
+                        alignments.contains(dpm_alignment),
+                        "Alignment of DPM is not found: {:?}\n{:?}",
+                        dpm_alignment,
+                        TargetAlignment { index: target_index, alignments: alignments.clone() },
+                    );
+                });
+            }
+        }
+        assert!(alignment_count > 0);
+    }
+}
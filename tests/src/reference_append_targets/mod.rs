@@ -0,0 +1,213 @@
+use crate::common::{
+    init_logger,
+    test_data::DataForValidation,
+    directory_path::get_target_dir,
+};
+
+use std::fs::File;
+use std::io::{BufWriter, Cursor, Read};
+use sigalign_utils::sequence_reader::{
+    fasta::FastaReader,
+    SeqRecord as _,
+    IdRecord as _,
+};
+use sigalign_core::reference::{
+    Reference as RawReference,
+    extensions::Serialize as _,
+};
+use sigalign_impl::{
+    pattern_index::dynamic_lfi::DynamicLfi,
+    sequence_storage::in_memory::InMemoryStorage,
+};
+use sigalign::{
+    Aligner,
+    Reference,
+    ReferenceBuilder,
+    algorithms::Local,
+};
+
+const QUERY_COUNT: usize = 30;
+
+fn get_records() -> Vec<(String, Vec<u8>)> {
+    let (ref_file, _) = DataForValidation::Default.get_data_paths();
+    let mut fasta_reader = FastaReader::from_path(ref_file).unwrap();
+    let mut records = Vec::new();
+    while let Some(mut record) = fasta_reader.next() {
+        let mut label = String::new();
+        let mut sequence = Vec::new();
+        record.extend_id_string(&mut label).unwrap();
+        record.extend_seq_buf(&mut sequence);
+        records.push((label, sequence));
+    }
+    records
+}
+
+fn builder_of(records: &[(String, Vec<u8>)]) -> ReferenceBuilder {
+    records.iter().fold(ReferenceBuilder::new(), |builder, (label, sequence)| {
+        builder.add_target(label, sequence)
+    })
+}
+
+fn assert_same_results(expected_reference: &Reference, reference: &Reference) {
+    assert_eq!(expected_reference.get_num_targets(), reference.get_num_targets());
+    for target_index in 0..reference.get_num_targets() {
+        assert_eq!(expected_reference.get_label(target_index), reference.get_label(target_index));
+        assert_eq!(expected_reference.get_sequence(target_index), reference.get_sequence(target_index));
+    }
+
+    let (_, qry_file) = DataForValidation::Default.get_data_paths();
+    let mut aligner = Aligner::new(Local::new(4, 6, 2, 50, 0.1).unwrap());
+    let mut fasta_reader = FastaReader::from_path(&qry_file).unwrap();
+    let mut query = Vec::new();
+    for _ in 0..QUERY_COUNT {
+        let Some(mut record) = fasta_reader.next() else { break };
+        query.clear();
+        record.extend_seq_buf(&mut query);
+        let mut expected = aligner.align(&query, expected_reference);
+        let mut result = aligner.align(&query, reference);
+        expected.0.sort_by_key(|x| x.index);
+        result.0.sort_by_key(|x| x.index);
+        assert_eq!(format!("{:?}", expected), format!("{:?}", result));
+    }
+}
+
+#[test]
+fn test_appended_reference_is_same_as_full_reference() {
+    init_logger();
+
+    let records = get_records();
+    let first_split = records.len() / 2;
+    let second_split = records.len() * 3 / 4;
+    let full_reference = builder_of(&records).build().unwrap();
+
+    let mut reference = builder_of(&records[..first_split]).build().unwrap();
+    reference.append_targets(builder_of(&records[first_split..second_split])).unwrap();
+    reference.append_targets(builder_of(&records[second_split..])).unwrap();
+    assert_eq!(reference.get_num_appended_targets() as usize, records.len() - first_split);
+    assert_same_results(&full_reference, &reference);
+
+    // Subset over the main and the appended targets
+    let (_, qry_file) = DataForValidation::Default.get_data_paths();
+    let target_indices: Vec<u32> = (0..reference.get_num_targets()).step_by(2).collect();
+    let full_subset = full_reference.get_target_subset_by_indices(&target_indices).unwrap();
+    let subset = reference.get_target_subset_by_indices(&target_indices).unwrap();
+    let mut aligner = Aligner::new(Local::new(4, 6, 2, 50, 0.1).unwrap());
+    let mut fasta_reader = FastaReader::from_path(&qry_file).unwrap();
+    let mut query = Vec::new();
+    for _ in 0..QUERY_COUNT {
+        let Some(mut record) = fasta_reader.next() else { break };
+        query.clear();
+        record.extend_seq_buf(&mut query);
+        let mut expected = aligner.align_to_subset(&query, &full_reference, &full_subset);
+        let mut result = aligner.align_to_subset(&query, &reference, &subset);
+        expected.0.sort_by_key(|x| x.index);
+        result.0.sort_by_key(|x| x.index);
+        assert_eq!(format!("{:?}", expected), format!("{:?}", result));
+    }
+
+    // Merge
+    reference.merge_appended_targets().unwrap();
+    assert_eq!(reference.get_num_appended_targets(), 0);
+    assert_same_results(&full_reference, &reference);
+}
+
+#[test]
+fn test_merge_keeps_build_configuration() {
+    init_logger();
+
+    let records = get_records();
+    let split = records.len() / 2;
+    let configured_builder = |records: &[(String, Vec<u8>)]| {
+        builder_of(records)
+            .set_lookup_table_kmer_size(2)
+            .set_suffix_array_sampling_ratio(2)
+    };
+    let full_reference = configured_builder(&records).build().unwrap();
+    let mut reference = configured_builder(&records[..split]).build().unwrap();
+    reference.append_targets(configured_builder(&records[split..])).unwrap();
+    reference.merge_appended_targets().unwrap();
+
+    assert_eq!(reference.get_pattern_index_info(), full_reference.get_pattern_index_info());
+    assert_eq!(reference.get_pattern_index_info().lookup_table_kmer_size, 2);
+    assert_same_results(&full_reference, &reference);
+}
+
+#[test]
+fn test_appended_targets_are_saved() {
+    init_logger();
+
+    let records = get_records();
+    let split = records.len() / 2;
+    let full_reference = builder_of(&records).build().unwrap();
+    let mut reference = builder_of(&records[..split]).build().unwrap();
+    reference.append_targets(builder_of(&records[split..])).unwrap();
+
+    let file_path = get_target_dir().unwrap().join("reference_append_targets.sigref");
+    {
+        let writer = BufWriter::new(File::create(&file_path).unwrap());
+        reference.save_to(writer).unwrap();
+    }
+    let loaded = Reference::load_from(File::open(&file_path).unwrap()).unwrap();
+    assert_eq!(loaded.get_num_appended_targets(), reference.get_num_appended_targets());
+    assert_same_results(&full_reference, &loaded);
+
+    let mmap_loaded = Reference::load_with_mapped_sequences(&file_path).unwrap();
+    assert_eq!(mmap_loaded.get_num_appended_targets(), reference.get_num_appended_targets());
+    assert_same_results(&full_reference, &mmap_loaded);
+}
+
+#[test]
+fn test_raw_reference_is_loaded_before_the_following_bytes() {
+    init_logger();
+
+    let records = get_records();
+    let split = records.len() / 2;
+    let full_reference = builder_of(&records).build().unwrap();
+    let base_reference = builder_of(&records[..split]).build().unwrap();
+    let mut appended_reference = builder_of(&records[..split]).build().unwrap();
+    appended_reference.append_targets(builder_of(&records[split..])).unwrap();
+
+    // Two references and a trailer in a row
+    let trailer = b"TRAILER";
+    let mut bytes = Vec::new();
+    base_reference.as_ref().save_to(&mut bytes).unwrap();
+    appended_reference.as_ref().save_to(&mut bytes).unwrap();
+    bytes.extend_from_slice(trailer);
+
+    let mut cursor = Cursor::new(&bytes[..]);
+    let loaded_base = RawReference::<DynamicLfi, InMemoryStorage>::load_from(&mut cursor).unwrap();
+    let loaded_appended = RawReference::<DynamicLfi, InMemoryStorage>::load_from(&mut cursor).unwrap();
+    let mut rest = Vec::new();
+    cursor.read_to_end(&mut rest).unwrap();
+    assert_eq!(rest, trailer);
+    assert_same_results(&base_reference, &Reference::from(loaded_base));
+    assert_same_results(&full_reference, &Reference::from(loaded_appended));
+
+    // Truncated before the flag
+    let mut truncated = Vec::new();
+    base_reference.as_ref().save_to(&mut truncated).unwrap();
+    truncated.pop();
+    assert!(RawReference::<DynamicLfi, InMemoryStorage>::load_from(&truncated[..]).is_err());
+}
+
+#[test]
+fn test_failed_append_does_not_change_reference() {
+    init_logger();
+
+    let records = get_records();
+    let split = records.len() / 2;
+    let expected_reference = builder_of(&records[..split]).build().unwrap();
+    let mut reference = builder_of(&records[..split]).build().unwrap();
+
+    // Over the maximum number of characters of the pattern index
+    let sequence: Vec<u8> = (b'!'..=b'~').collect();
+    let result = reference.append_targets(ReferenceBuilder::new().add_target("invalid", &sequence));
+    assert!(result.is_err());
+    assert_eq!(reference.get_num_appended_targets(), 0);
+    assert_same_results(&expected_reference, &reference);
+
+    // Appending again after the failure
+    let full_reference = builder_of(&records).build().unwrap();
+    reference.append_targets(builder_of(&records[split..])).unwrap();
+    assert_same_results(&full_reference, &reference);
+}
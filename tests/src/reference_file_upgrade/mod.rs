@@ -0,0 +1,190 @@
+use crate::common::{
+    init_logger,
+    test_data::DataForValidation,
+    directory_path::get_target_dir,
+};
+
+use std::fs::File;
+use std::io::BufWriter;
+use std::path::Path;
+use sigalign_utils::sequence_reader::{
+    fasta::FastaReader,
+    SeqRecord as _,
+};
+use sigalign_core::reference::extensions::Serialize as _;
+use sigalign::{
+    Aligner,
+    Reference,
+    ReferenceBuilder,
+    ReferenceLoadError,
+    algorithms::Local,
+};
+
+const QUERY_COUNT: usize = 20;
+// Base64 encoded signatures
+const LEGACY_SIGNATURE: &str = "U0lHQUxJR05fUkVGRVJFTkNFOjAuNC4wOjAuMi4w"; // SIGALIGN_REFERENCE:0.4.0:0.2.0
+const OLDER_LAYOUT_SIGNATURE: &str = "U0lHQUxJR05fUkVGRVJFTkNFOjAuMy4wOjAuMS4w"; // SIGALIGN_REFERENCE:0.3.0:0.1.0
+const LATER_FORMAT_SIGNATURE: &str = "U0lHQUxJR05fUkVGRVJFTkNFOkZPUk1BVDozOjAuMy4w"; // SIGALIGN_REFERENCE:FORMAT:3:0.3.0
+const LEGACY_LAYOUT_IN_FORMAT_SIGNATURE: &str = "U0lHQUxJR05fUkVGRVJFTkNFOkZPUk1BVDoyOjAuMi4w"; // SIGALIGN_REFERENCE:FORMAT:2:0.2.0
+
+fn get_results(reference: &Reference) -> Vec<String> {
+    let (_, qry_file) = DataForValidation::Default.get_data_paths();
+    let mut fasta_reader = FastaReader::from_path(&qry_file).unwrap();
+    let mut aligner = Aligner::new(Local::new(4, 6, 2, 50, 0.1).unwrap());
+    let mut results = Vec::new();
+    for _ in 0..QUERY_COUNT {
+        let Some(mut record) = fasta_reader.next() else { break };
+        let mut query = Vec::new();
+        record.extend_seq_buf(&mut query);
+        let mut result = aligner.align(&query, reference);
+        result.0.sort_by_key(|x| x.index);
+        results.push(format!("{:?}", result));
+    }
+    results
+}
+
+fn signature_bytes(encoded_signature: &str) -> Vec<u8> {
+    let mut bytes = encoded_signature.len().to_ne_bytes().to_vec();
+    bytes.extend_from_slice(encoded_signature.as_bytes());
+    bytes
+}
+
+fn save_reference(reference: &Reference, file_path: &Path) {
+    let writer = BufWriter::new(File::create(file_path).unwrap());
+    reference.save_to_seekable(writer).unwrap();
+}
+
+#[test]
+fn test_legacy_file_is_loaded_and_upgraded() {
+    init_logger();
+
+    let (ref_file, _) = DataForValidation::Default.get_data_paths();
+    let reference = ReferenceBuilder::new()
+        .add_fasta_file(&ref_file).unwrap()
+        .build().unwrap();
+    let expected_results = get_results(&reference);
+
+    // Legacy file: signature and raw reference
+    let legacy_file_path = get_target_dir().unwrap().join("reference_legacy.sigref");
+    {
+        let mut bytes = signature_bytes(LEGACY_SIGNATURE);
+        reference.as_ref().save_to(&mut bytes).unwrap();
+        // The legacy layout has no flag of the appended targets at the end.
+        assert_eq!(bytes.pop(), Some(0));
+        std::fs::write(&legacy_file_path, bytes).unwrap();
+    }
+    assert_eq!(Reference::get_file_format_version(&legacy_file_path).unwrap(), 1);
+    assert!(matches!(
+        Reference::verify(&legacy_file_path),
+        Err(ReferenceLoadError::MissingChecksum),
+    ));
+    let legacy_loaded = Reference::load_from(File::open(&legacy_file_path).unwrap()).unwrap();
+    assert_eq!(get_results(&legacy_loaded), expected_results);
+    let legacy_mmap_loaded = Reference::load_with_mapped_sequences(&legacy_file_path).unwrap();
+    assert_eq!(get_results(&legacy_mmap_loaded), expected_results);
+
+    // Upgrade
+    let upgraded_file_path = get_target_dir().unwrap().join("reference_upgraded.sigref");
+    assert_eq!(Reference::upgrade_file(&legacy_file_path, &upgraded_file_path).unwrap(), 1);
+    assert_eq!(
+        Reference::get_file_format_version(&upgraded_file_path).unwrap(),
+        Reference::FILE_FORMAT_VERSION,
+    );
+    Reference::verify(&upgraded_file_path).unwrap();
+    let upgraded = Reference::load_with_mapped_sequences(&upgraded_file_path).unwrap();
+    assert_eq!(get_results(&upgraded), expected_results);
+    // Same as the file saved by the current version
+    let saved_file_path = get_target_dir().unwrap().join("reference_saved_from_legacy.sigref");
+    save_reference(&legacy_loaded, &saved_file_path);
+    assert_eq!(
+        std::fs::read(&upgraded_file_path).unwrap(),
+        std::fs::read(&saved_file_path).unwrap(),
+    );
+
+    // Upgrade in place
+    assert_eq!(Reference::upgrade_file(&legacy_file_path, &legacy_file_path).unwrap(), 1);
+    assert_eq!(
+        std::fs::read(&legacy_file_path).unwrap(),
+        std::fs::read(&saved_file_path).unwrap(),
+    );
+    // Current file is kept as it is
+    assert_eq!(
+        Reference::upgrade_file(&legacy_file_path, &upgraded_file_path).unwrap(),
+        Reference::FILE_FORMAT_VERSION,
+    );
+    assert_eq!(
+        std::fs::read(&upgraded_file_path).unwrap(),
+        std::fs::read(&saved_file_path).unwrap(),
+    );
+    // Corrupted current file is not copied
+    let mut corrupted_bytes = std::fs::read(&saved_file_path).unwrap();
+    let position = corrupted_bytes.len() / 2;
+    corrupted_bytes[position] ^= 0b1000_0000;
+    let corrupted_file_path = get_target_dir().unwrap().join("reference_corrupted_to_upgrade.sigref");
+    std::fs::write(&corrupted_file_path, corrupted_bytes).unwrap();
+    let corrupted_upgraded_file_path = get_target_dir().unwrap().join("reference_corrupted_upgraded.sigref");
+    let _ = std::fs::remove_file(&corrupted_upgraded_file_path);
+    assert!(matches!(
+        Reference::upgrade_file(&corrupted_file_path, &corrupted_upgraded_file_path),
+        Err(ReferenceLoadError::ChecksumMismatch { .. }),
+    ));
+    assert!(!corrupted_upgraded_file_path.exists());
+}
+
+#[test]
+fn test_sections_of_later_versions_are_skipped() {
+    init_logger();
+
+    let (ref_file, _) = DataForValidation::Default.get_data_paths();
+    let mut reference = ReferenceBuilder::new()
+        .add_fasta_file(&ref_file).unwrap()
+        .build().unwrap();
+    reference.exclude_targets(&[0, 2]).unwrap();
+    let expected_results = get_results(&reference);
+
+    let file_path = get_target_dir().unwrap().join("reference_with_unknown_section.sigref");
+    save_reference(&reference, &file_path);
+    let mut bytes = std::fs::read(&file_path).unwrap();
+    // Empty section with unknown tag after the signature
+    let signature_length = usize::from_ne_bytes(bytes[..8].try_into().unwrap());
+    let mut unknown_section = b"UNKNOWN_".to_vec();
+    unknown_section.extend_from_slice(&0_u64.to_le_bytes());
+    unknown_section.extend_from_slice(&0_u32.to_le_bytes()); // CRC32 of empty bytes
+    let position = 8 + signature_length;
+    bytes.splice(position..position, unknown_section);
+    std::fs::write(&file_path, bytes).unwrap();
+
+    Reference::verify(&file_path).unwrap();
+    let loaded = Reference::load_from(File::open(&file_path).unwrap()).unwrap();
+    assert_eq!(loaded.get_excluded_target_indices(), vec![0, 2]);
+    assert_eq!(get_results(&loaded), expected_results);
+    let mmap_loaded = Reference::load_with_mapped_sequences(&file_path).unwrap();
+    assert_eq!(mmap_loaded.get_metadata(), reference.get_metadata());
+    assert_eq!(get_results(&mmap_loaded), expected_results);
+}
+
+#[test]
+fn test_incompatible_files_are_rejected() {
+    init_logger();
+
+    for encoded_signature in [OLDER_LAYOUT_SIGNATURE, LATER_FORMAT_SIGNATURE, LEGACY_LAYOUT_IN_FORMAT_SIGNATURE] {
+        let file_path = get_target_dir().unwrap().join("reference_incompatible.sigref");
+        let mut bytes = signature_bytes(encoded_signature);
+        bytes.extend_from_slice(&[0; 64]);
+        std::fs::write(&file_path, bytes).unwrap();
+        assert!(matches!(
+            Reference::load_from(File::open(&file_path).unwrap()),
+            Err(ReferenceLoadError::IncompatibleVersion(_)),
+        ));
+        assert!(matches!(
+            Reference::load_with_mapped_sequences(&file_path),
+            Err(ReferenceLoadError::IncompatibleVersion(_)),
+        ));
+        let upgraded_file_path = get_target_dir().unwrap().join("reference_incompatible_upgraded.sigref");
+        assert!(matches!(
+            Reference::upgrade_file(&file_path, &upgraded_file_path),
+            Err(ReferenceLoadError::IncompatibleVersion(_)),
+        ));
+        assert!(!upgraded_file_path.exists());
+    }
+}
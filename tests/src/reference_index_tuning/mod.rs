@@ -0,0 +1,123 @@
+use crate::common::{
+    init_logger,
+    test_data::DataForValidation,
+};
+
+use sigalign_utils::sequence_reader::{
+    fasta::FastaReader,
+    SeqRecord as _,
+};
+use sigalign::{
+    Aligner,
+    Reference,
+    ReferenceBuilder,
+    ReferenceBuildError,
+    BwtBlockSize,
+    algorithms::Local,
+};
+
+const QUERY_COUNT: usize = 30;
+
+#[test]
+fn test_pattern_index_info_follows_builder() {
+    init_logger();
+
+    let (ref_file, _) = DataForValidation::Default.get_data_paths();
+    let default_reference = ReferenceBuilder::new()
+        .add_fasta_file(&ref_file).unwrap()
+        .build().unwrap();
+    let default_info = default_reference.get_pattern_index_info();
+    assert_eq!(default_info.suffix_array_sampling_ratio, 1);
+    assert_eq!(default_info.bwt_block_size, BwtBlockSize::V64);
+    assert!(default_info.size_in_bytes < default_reference.get_estimated_size_in_bytes());
+
+    let tuned_reference = ReferenceBuilder::new()
+        .set_suffix_array_sampling_ratio(4)
+        .set_lookup_table_kmer_size(3)
+        .set_bwt_block_size(BwtBlockSize::V128)
+        .add_fasta_file(&ref_file).unwrap()
+        .build().unwrap();
+    let tuned_info = tuned_reference.get_pattern_index_info();
+    assert_eq!(tuned_info.suffix_array_sampling_ratio, 4);
+    assert_eq!(tuned_info.lookup_table_kmer_size, 3);
+    assert_eq!(tuned_info.bwt_block_size, BwtBlockSize::V128);
+    assert_eq!(tuned_info.num_characters, default_info.num_characters);
+    assert!(tuned_info.size_in_bytes < default_info.size_in_bytes);
+
+    // Byte budget of lookup table
+    let max_bytes_size = 10_000;
+    let budget_info = ReferenceBuilder::new()
+        .set_lookup_table_max_bytes_size(max_bytes_size)
+        .add_fasta_file(&ref_file).unwrap()
+        .build().unwrap()
+        .get_pattern_index_info();
+    let entry_count = (budget_info.num_characters as u64 + 1).pow(budget_info.lookup_table_kmer_size);
+    assert!(entry_count < max_bytes_size);
+    assert!(entry_count * (budget_info.num_characters as u64 + 1) >= max_bytes_size);
+
+    // Safe guard
+    let unguarded_info = ReferenceBuilder::new()
+        .set_safe_guard(false)
+        .add_fasta_file(&ref_file).unwrap()
+        .build().unwrap()
+        .get_pattern_index_info();
+    assert_eq!(unguarded_info.num_characters + 1, default_info.num_characters);
+
+    // Kept after saving and loading
+    let mut buffer = Vec::new();
+    tuned_reference.save_to(&mut buffer).unwrap();
+    let loaded_reference = Reference::load_from(&buffer[..]).unwrap();
+    assert_eq!(loaded_reference.get_pattern_index_info(), tuned_info);
+}
+
+#[test]
+fn test_tuned_reference_gives_same_results() {
+    init_logger();
+
+    let (ref_file, qry_file) = DataForValidation::Default.get_data_paths();
+    let default_reference = ReferenceBuilder::new()
+        .add_fasta_file(&ref_file).unwrap()
+        .build().unwrap();
+    let tuned_reference = ReferenceBuilder::new()
+        .set_suffix_array_sampling_ratio(8)
+        .set_lookup_table_kmer_size(4)
+        .set_bwt_block_size(BwtBlockSize::V128)
+        .add_fasta_file(&ref_file).unwrap()
+        .build().unwrap();
+
+    let mut aligner = Aligner::new(Local::new(4, 6, 2, 50, 0.1).unwrap());
+    let mut fasta_reader = FastaReader::from_path(&qry_file).unwrap();
+    let mut query = Vec::new();
+    for _ in 0..QUERY_COUNT {
+        let Some(mut record) = fasta_reader.next() else { break };
+        query.clear();
+        record.extend_seq_buf(&mut query);
+        let mut expected = aligner.align(&query, &default_reference);
+        let mut result = aligner.align(&query, &tuned_reference);
+        expected.0.sort_by_key(|x| x.index);
+        result.0.sort_by_key(|x| x.index);
+        assert_eq!(format!("{:?}", expected), format!("{:?}", result));
+    }
+}
+
+#[test]
+fn test_lookup_table_kmer_size_over_cap_is_rejected() {
+    init_logger();
+
+    let (ref_file, _) = DataForValidation::Default.get_data_paths();
+    // Zero, over the byte budget, and overflowing the table size
+    for kmer_size in [0, 20, 30, 51, u32::MAX] {
+        let result = ReferenceBuilder::new()
+            .set_lookup_table_kmer_size(kmer_size)
+            .add_fasta_file(&ref_file).unwrap()
+            .build();
+        assert!(matches!(result, Err(ReferenceBuildError::PatternIndexError(_))));
+    }
+    let min_kmer_size_info = ReferenceBuilder::new()
+        .set_lookup_table_kmer_size(1)
+        .add_fasta_file(&ref_file).unwrap()
+        .build().unwrap()
+        .get_pattern_index_info();
+    assert_eq!(min_kmer_size_info.lookup_table_kmer_size, 1);
+
+}
@@ -0,0 +1,175 @@
+use crate::common::{
+    init_logger,
+    test_data::DataForValidation,
+    directory_path::get_target_dir,
+};
+
+use std::fs::File;
+use std::io::BufWriter;
+use std::path::Path;
+use sigalign::{
+    Reference,
+    ReferenceBuilder,
+    ReferenceLoadError,
+};
+
+fn save_reference(reference: &Reference, file_path: &Path) {
+    let writer = BufWriter::new(File::create(file_path).unwrap());
+    reference.save_to_seekable(writer).unwrap();
+}
+
+#[test]
+fn test_metadata_is_recorded_and_saved() {
+    init_logger();
+
+    let (ref_file, _) = DataForValidation::Default.get_data_paths();
+    let reference = ReferenceBuilder::new()
+        .set_uppercase(true)
+        .ignore_base(b'N')
+        .set_suffix_array_sampling_ratio(2)
+        .set_lookup_table_kmer_size(4)
+        .add_fasta_file(&ref_file).unwrap()
+        .build().unwrap();
+
+    let metadata = reference.get_metadata().clone();
+    assert!(metadata.created_at.is_some());
+    let build_configuration = metadata.build_configuration.clone().unwrap();
+    assert!(build_configuration.uppercase);
+    assert_eq!(build_configuration.ignored_bases, b"N".to_vec());
+    assert_eq!(build_configuration.suffix_array_sampling_ratio, 2);
+    assert_eq!(build_configuration.lookup_table_kmer_size, Some(4));
+    assert_eq!(build_configuration.lookup_table_max_bytes_size, None);
+    assert_eq!(build_configuration.bwt_block_size, 64);
+    assert_eq!(metadata.source_files.len(), 1);
+    assert_eq!(metadata.source_files[0].path, ref_file.to_string_lossy());
+    assert_eq!(metadata.source_files[0].size_in_bytes, std::fs::metadata(&ref_file).unwrap().len());
+
+    // Same digest for the same file
+    let other_reference = ReferenceBuilder::new()
+        .add_fasta_file(&ref_file).unwrap()
+        .build().unwrap();
+    assert_eq!(other_reference.get_metadata().source_files, metadata.source_files);
+
+    // Kept after saving and loading
+    let file_path = get_target_dir().unwrap().join("reference_metadata.sigref");
+    save_reference(&reference, &file_path);
+    assert_eq!(Reference::verify(&file_path).unwrap(), metadata);
+    let loaded = Reference::load_from(File::open(&file_path).unwrap()).unwrap();
+    assert_eq!(loaded.get_metadata(), &metadata);
+    let mmap_loaded = Reference::load_with_mapped_sequences(&file_path).unwrap();
+    assert_eq!(mmap_loaded.get_metadata(), &metadata);
+}
+
+#[test]
+fn test_verify_detects_truncated_and_corrupted_files() {
+    init_logger();
+
+    let (ref_file, _) = DataForValidation::Default.get_data_paths();
+    let reference = ReferenceBuilder::new()
+        .add_fasta_file(&ref_file).unwrap()
+        .build().unwrap();
+    let file_path = get_target_dir().unwrap().join("reference_to_verify.sigref");
+    save_reference(&reference, &file_path);
+    Reference::verify(&file_path).unwrap();
+    let bytes = std::fs::read(&file_path).unwrap();
+
+    // Truncated
+    let truncated_file_path = get_target_dir().unwrap().join("reference_truncated.sigref");
+    std::fs::write(&truncated_file_path, &bytes[..bytes.len() - 100]).unwrap();
+    assert!(matches!(
+        Reference::verify(&truncated_file_path),
+        Err(ReferenceLoadError::Truncated { .. }),
+    ));
+    assert!(matches!(
+        Reference::load_from(File::open(&truncated_file_path).unwrap()),
+        Err(ReferenceLoadError::Truncated { .. }),
+    ));
+    assert!(matches!(
+        Reference::load_with_mapped_sequences(&truncated_file_path),
+        Err(ReferenceLoadError::Truncated { .. }),
+    ));
+
+    // Corrupted
+    let corrupted_file_path = get_target_dir().unwrap().join("reference_corrupted.sigref");
+    let mut corrupted_bytes = bytes.clone();
+    let position = corrupted_bytes.len() / 2;
+    corrupted_bytes[position] ^= 0b1000_0000;
+    std::fs::write(&corrupted_file_path, &corrupted_bytes).unwrap();
+    assert!(matches!(
+        Reference::verify(&corrupted_file_path),
+        Err(ReferenceLoadError::ChecksumMismatch { .. }),
+    ));
+    assert!(Reference::load_from(File::open(&corrupted_file_path).unwrap()).is_err());
+
+    // Extra bytes
+    let extended_file_path = get_target_dir().unwrap().join("reference_extended.sigref");
+    let mut extended_bytes = bytes.clone();
+    extended_bytes.extend_from_slice(&[0; 8]);
+    std::fs::write(&extended_file_path, &extended_bytes).unwrap();
+    assert!(matches!(
+        Reference::verify(&extended_file_path),
+        Err(ReferenceLoadError::LengthMismatch { .. }),
+    ));
+    assert!(matches!(
+        Reference::load_with_mapped_sequences(&extended_file_path),
+        Err(ReferenceLoadError::LengthMismatch { .. }),
+    ));
+
+    // Not a reference file
+    let unknown_file_path = get_target_dir().unwrap().join("reference_unknown.sigref");
+    std::fs::write(&unknown_file_path, b"not a reference").unwrap();
+    assert!(matches!(
+        Reference::verify(&unknown_file_path),
+        Err(ReferenceLoadError::UnknownFile),
+    ));
+}
+
+#[test]
+fn test_checksum_after_raw_reference_is_verified() {
+    init_logger();
+
+    let (ref_file, _) = DataForValidation::Default.get_data_paths();
+    let reference = ReferenceBuilder::new()
+        .add_fasta_file(&ref_file).unwrap()
+        .build().unwrap();
+
+    // Written by `save_to` to the writer that cannot seek
+    let mut bytes = Vec::new();
+    reference.save_to(&mut bytes).unwrap();
+    let mut seekable_bytes = std::io::Cursor::new(Vec::new());
+    reference.save_to_seekable(&mut seekable_bytes).unwrap();
+    // Same raw reference with the checksum at the end
+    assert_eq!(bytes.len(), seekable_bytes.get_ref().len() + 12);
+
+    let file_path = get_target_dir().unwrap().join("reference_trailing_digest.sigref");
+    std::fs::write(&file_path, &bytes).unwrap();
+    assert_eq!(&Reference::verify(&file_path).unwrap(), reference.get_metadata());
+    let loaded = Reference::load_from(&bytes[..]).unwrap();
+    let mmap_loaded = Reference::load_with_mapped_sequences(&file_path).unwrap();
+    for target_index in 0..reference.get_num_targets() {
+        assert_eq!(reference.get_sequence(target_index), loaded.get_sequence(target_index));
+        assert_eq!(reference.get_sequence(target_index), mmap_loaded.get_sequence(target_index));
+    }
+
+    // Corrupted
+    let mut corrupted_bytes = bytes.clone();
+    let position = corrupted_bytes.len() / 2;
+    corrupted_bytes[position] ^= 0b1000_0000;
+    std::fs::write(&file_path, &corrupted_bytes).unwrap();
+    assert!(matches!(
+        Reference::verify(&file_path),
+        Err(ReferenceLoadError::ChecksumMismatch { .. }),
+    ));
+    assert!(Reference::load_from(&corrupted_bytes[..]).is_err());
+
+    // Truncated or extended: the checksum at the end is lost
+    for modified_bytes in [
+        bytes[..bytes.len() - 100].to_vec(),
+        [&bytes[..], &[0; 8]].concat(),
+    ] {
+        std::fs::write(&file_path, &modified_bytes).unwrap();
+        assert!(Reference::verify(&file_path).is_err());
+        assert!(Reference::load_from(&modified_bytes[..]).is_err());
+        assert!(Reference::load_with_mapped_sequences(&file_path).is_err());
+    }
+}
@@ -0,0 +1,80 @@
+use crate::common::{
+    init_logger,
+    test_data::DataForValidation,
+    directory_path::get_target_dir,
+};
+
+use std::fs::File;
+use std::io::BufWriter;
+use sigalign_utils::sequence_reader::{
+    fasta::FastaReader,
+    SeqRecord as _,
+};
+use sigalign::{
+    Aligner,
+    Reference,
+    ReferenceBuilder,
+    ReferenceLoadError,
+    algorithms::Local,
+};
+
+const QUERY_COUNT: usize = 30;
+
+#[test]
+fn test_mmap_loaded_reference_is_same() {
+    init_logger();
+
+    let (ref_file, qry_file) = DataForValidation::Default.get_data_paths();
+    let reference = ReferenceBuilder::new()
+        .add_fasta_file(&ref_file).unwrap()
+        .build().unwrap();
+
+    let file_path = get_target_dir().unwrap().join("reference_mmap_load.sigref");
+    {
+        let writer = BufWriter::new(File::create(&file_path).unwrap());
+        reference.save_to(writer).unwrap();
+    }
+    let mmap_reference = Reference::load_with_mapped_sequences(&file_path).unwrap();
+    assert!(mmap_reference.has_mapped_sequences());
+    assert!(!reference.has_mapped_sequences());
+
+    // Same contents
+    assert_eq!(reference.get_num_targets(), mmap_reference.get_num_targets());
+    assert_eq!(reference.get_total_length(), mmap_reference.get_total_length());
+    for target_index in 0..reference.get_num_targets() {
+        assert_eq!(reference.get_sequence(target_index), mmap_reference.get_sequence(target_index));
+        assert_eq!(reference.get_label(target_index), mmap_reference.get_label(target_index));
+    }
+    assert_eq!(reference.get_sequence(reference.get_num_targets()), None);
+
+    // Same results
+    let mut aligner = Aligner::new(Local::new(4, 6, 2, 50, 0.1).unwrap());
+    let mut fasta_reader = FastaReader::from_path(&qry_file).unwrap();
+    let mut query = Vec::new();
+    for _ in 0..QUERY_COUNT {
+        let Some(mut record) = fasta_reader.next() else { break };
+        query.clear();
+        record.extend_seq_buf(&mut query);
+        let mut expected = aligner.align(&query, &reference);
+        let mut result = aligner.align(&query, &mmap_reference);
+        expected.0.sort_by_key(|x| x.index);
+        result.0.sort_by_key(|x| x.index);
+        assert_eq!(format!("{:?}", expected), format!("{:?}", result));
+    }
+
+    // Clone shares the mapping
+    let cloned = mmap_reference.clone();
+    drop(mmap_reference);
+    assert!(cloned.has_mapped_sequences());
+    assert_eq!(reference.get_sequence(0), cloned.get_sequence(0));
+}
+
+#[test]
+fn test_mmap_load_rejects_invalid_file() {
+    init_logger();
+
+    let file_path = get_target_dir().unwrap().join("reference_mmap_load_invalid.sigref");
+    std::fs::write(&file_path, b"not a reference file").unwrap();
+    let result = Reference::load_with_mapped_sequences(&file_path);
+    assert!(matches!(result, Err(ReferenceLoadError::UnknownFile)));
+}
@@ -0,0 +1,116 @@
+use crate::common::{
+    init_logger,
+    test_data::DataForValidation,
+    directory_path::get_target_dir,
+};
+
+use std::fs::File;
+use std::io::BufWriter;
+use sigalign_utils::sequence_reader::{
+    fasta::FastaReader,
+    SeqRecord as _,
+};
+use sigalign::{
+    Aligner,
+    Reference,
+    ReferenceBuilder,
+    algorithms::Local,
+    results::QueryAlignment,
+};
+
+const QUERY_COUNT: usize = 30;
+
+fn get_queries() -> Vec<Vec<u8>> {
+    let (_, qry_file) = DataForValidation::Default.get_data_paths();
+    let mut fasta_reader = FastaReader::from_path(&qry_file).unwrap();
+    let mut queries = Vec::new();
+    for _ in 0..QUERY_COUNT {
+        let Some(mut record) = fasta_reader.next() else { break };
+        let mut query = Vec::new();
+        record.extend_seq_buf(&mut query);
+        queries.push(query);
+    }
+    queries
+}
+
+fn align_all(reference: &Reference, queries: &[Vec<u8>]) -> Vec<QueryAlignment> {
+    let mut aligner = Aligner::new(Local::new(4, 6, 2, 50, 0.1).unwrap());
+    queries.iter().map(|query| {
+        let mut result = aligner.align(query, reference);
+        result.0.sort_by_key(|x| x.index);
+        result
+    }).collect()
+}
+
+#[test]
+fn test_excluded_targets_are_not_aligned() {
+    init_logger();
+
+    let (ref_file, _) = DataForValidation::Default.get_data_paths();
+    let mut reference = ReferenceBuilder::new()
+        .add_fasta_file(&ref_file).unwrap()
+        .build().unwrap();
+    let num_targets = reference.get_num_targets();
+    let queries = get_queries();
+    let original_results = align_all(&reference, &queries);
+
+    // Exclude every other target that has been aligned
+    let mut aligned_targets: Vec<u32> = original_results.iter().flat_map(|result| {
+        result.0.iter().map(|x| x.index)
+    }).collect();
+    aligned_targets.sort_unstable();
+    aligned_targets.dedup();
+    let to_exclude: Vec<u32> = aligned_targets.iter().copied().step_by(2).collect();
+    assert!(!to_exclude.is_empty());
+
+    reference.exclude_targets(&to_exclude).unwrap();
+    assert_eq!(reference.get_excluded_target_indices(), to_exclude);
+    assert!(reference.is_excluded(to_exclude[0]));
+    assert_eq!(reference.get_num_targets(), num_targets);
+    assert!(reference.exclude_targets(&[num_targets]).is_err());
+
+    let expected_results: Vec<String> = original_results.iter().map(|result| {
+        let mut result = result.clone();
+        result.0.retain(|x| !to_exclude.contains(&x.index));
+        format!("{:?}", result)
+    }).collect();
+    let results: Vec<String> = align_all(&reference, &queries).iter().map(|result| {
+        format!("{:?}", result)
+    }).collect();
+    assert_eq!(expected_results, results);
+
+    // Subset leaves out the excluded targets
+    let subset = reference.get_target_subset_by_indices(&aligned_targets).unwrap();
+    assert_eq!(
+        subset.get_sorted_target_indices().len(),
+        aligned_targets.len() - to_exclude.len(),
+    );
+
+    // Kept after saving and loading
+    let file_path = get_target_dir().unwrap().join("reference_target_exclusion.sigref");
+    {
+        let writer = BufWriter::new(File::create(&file_path).unwrap());
+        reference.save_to(writer).unwrap();
+    }
+    let loaded = Reference::load_from(File::open(&file_path).unwrap()).unwrap();
+    assert_eq!(loaded.get_excluded_target_indices(), to_exclude);
+    let mmap_loaded = Reference::load_with_mapped_sequences(&file_path).unwrap();
+    assert_eq!(mmap_loaded.get_excluded_target_indices(), to_exclude);
+    let results: Vec<String> = align_all(&mmap_loaded, &queries).iter().map(|result| {
+        format!("{:?}", result)
+    }).collect();
+    assert_eq!(expected_results, results);
+
+    // Include again
+    reference.include_targets(&to_exclude[..1]).unwrap();
+    assert_eq!(reference.get_excluded_target_indices(), to_exclude[1..]);
+    reference.include_all_targets();
+    assert!(reference.get_excluded_target_indices().is_empty());
+    let results: Vec<String> = align_all(&reference, &queries).iter().map(|result| {
+        format!("{:?}", result)
+    }).collect();
+    let expected_results: Vec<String> = original_results.iter().map(|result| {
+        format!("{:?}", result)
+    }).collect();
+    assert_eq!(expected_results, results);
+}
@@ -0,0 +1,218 @@
+use crate::common::{
+    init_logger,
+    test_data::DataForValidation,
+};
+
+use log::info;
+use sigalign_utils::sequence_reader::{
+    fasta::FastaReader,
+    SeqRecord as _,
+};
+use sigalign::{
+    Aligner,
+    Reference,
+    ReferenceBuilder,
+    Strand,
+    algorithms::{Local, SubstitutionMatrix},
+    results::{SamWriter, SamCigarStyle},
+};
+
+#[test]
+fn test_sam_records_are_consistent_with_reference() {
+    init_logger();
+
+    let (ref_file, qry_file) = DataForValidation::Default.get_data_paths();
+    let reference = ReferenceBuilder::new()
+        .add_fasta_file(&ref_file).unwrap()
+        .build().unwrap();
+    let mut aligner = Aligner::new(Local::new(4, 6, 2, 50, 0.1).unwrap());
+
+    for cigar_style in [SamCigarStyle::Extended, SamCigarStyle::Match] {
+        let mut sam_writer = SamWriter::new(Vec::new(), &reference);
+        sam_writer.set_cigar_style(cigar_style);
+        sam_writer.write_header().unwrap();
+
+        let mut fasta_reader = FastaReader::from_path(&qry_file).unwrap();
+        let mut query_buffer = Vec::new();
+        let mut query_count = 0;
+        for _ in 0..50 {
+            let Some(mut record) = fasta_reader.next() else { break };
+            query_buffer.clear();
+            record.extend_seq_buf(&mut query_buffer);
+            let query_name = format!("query_{}", query_count);
+
+            let result = aligner.align_stranded(&query_buffer, &reference, Strand::Both);
+            let labeled_result = reference.label_stranded_query_alignment(result);
+            sam_writer.write_labeled_stranded_query_alignment(
+                &query_name, &query_buffer, None, &labeled_result,
+            ).unwrap();
+            query_count += 1;
+        }
+
+        let sam = String::from_utf8(sam_writer.into_inner()).unwrap();
+        let record_count = validate_sam(&sam, &reference, cigar_style, query_count);
+        info!("{} SAM records are validated ({:?})", record_count, cigar_style);
+    }
+}
+
+#[test]
+fn test_sam_record_of_known_alignment() {
+    init_logger();
+
+    let reference = ReferenceBuilder::new()
+        .add_target("target_1", b"TTAGTTGTGCCGCAGCGAAGTAGTGCTTGAAATATGCGACCCCTAAGTAGGAGCGTATGCGCCCAGTAACCAATGCCTGTTGAGATGCCAGACGCGTAACCAAAACATAGAAACCATCAATAGACAGGTCATAATCGGTC")
+        .build().unwrap();
+    let mut aligner = Aligner::new(Local::new(4, 6, 2, 20, 0.2).unwrap());
+
+    // One substitution (T->G) and one deleted base (T)
+    let query = b"TTTCGCAGCGAAGGAGTGCTTGAAATATGCGACCCCAAGTAGGAGCGTATGCGCCCAGTAACGG";
+    let quality = vec![b'I'; query.len()];
+    let result = aligner.align(query, &reference);
+    assert_eq!(result.count_alignments(), 1);
+
+    let mut sam_writer = SamWriter::new(Vec::new(), &reference);
+    sam_writer.write_header().unwrap();
+    sam_writer.write_query_alignment("query", query, Some(&quality), &result).unwrap();
+    let sam = String::from_utf8(sam_writer.into_inner()).unwrap();
+    let lines: Vec<&str> = sam.lines().collect();
+
+    assert_eq!(lines[0], "@HD\tVN:1.6\tSO:unsorted");
+    assert_eq!(lines[1], "@SQ\tSN:target_1\tLN:140");
+    assert!(lines[2].starts_with("@PG\tID:sigalign\tPN:sigalign\tVN:"));
+    let fields: Vec<&str> = lines[3].split('\t').collect();
+    assert_eq!(&fields[..9], &["query", "0", "target_1", "11", "255", "3S10=1X22=1D26=2S", "*", "0", "0"]);
+    assert_eq!(fields[9].as_bytes(), query);
+    assert_eq!(fields[10].as_bytes(), &quality[..]);
+    assert_eq!(&fields[11..], &["NM:i:2", "MD:Z:10T22^T26", "AS:i:-12"]);
+
+    // Unmapped
+    let mut sam_writer = SamWriter::new(Vec::new(), &reference);
+    sam_writer.write_query_alignment("unmapped", b"ACGT", None, &aligner.align(b"ACGT", &reference)).unwrap();
+    let sam = String::from_utf8(sam_writer.into_inner()).unwrap();
+    assert_eq!(sam, "unmapped\t4\t*\t0\t0\t*\t*\t0\t0\tACGT\t*\n");
+
+    // Quality with the different length
+    let mut sam_writer = SamWriter::new(Vec::new(), &reference);
+    assert!(sam_writer.write_query_alignment("query", query, Some(b"II"), &result).is_err());
+}
+
+#[test]
+fn test_ambiguity_code_matched_to_base_is_counted_as_mismatch() {
+    init_logger();
+
+    let reference = ReferenceBuilder::new()
+        .add_target("target_1", b"TTAGTTGTGCCGCAGCGAAGTAGTGCTTGAAATATGCGACCCCTAAGTAGGAGCGTATGCGCCCAGTAACCAATGCCTGTTGAGATGCCAGACGCGTAACCAAAACATAGAAACCATCAATAGACAGGTCATAATCGGTC")
+        .build().unwrap();
+    let substitution_matrix = SubstitutionMatrix::iupac(4, 0).unwrap();
+    let mut aligner = Aligner::new(
+        Local::with_substitution_matrix(substitution_matrix, 6, 2, 20, 0.2).unwrap()
+    );
+
+    // `S` (G or C) at the position of `G`
+    let query = b"TAGTGCTTGAAATATGCGACCCCTAAGTAGSAGCGTATGCGCCCAGTAACCAATGCCTGT";
+    let result = aligner.align(query, &reference);
+    assert_eq!(result.count_alignments(), 1);
+    assert_eq!(result.0[0].alignments[0].penalty, 0);
+
+    let mut sam_writer = SamWriter::new(Vec::new(), &reference);
+    sam_writer.set_cigar_style(SamCigarStyle::Match);
+    sam_writer.write_query_alignment("query", query, None, &result).unwrap();
+    let sam = String::from_utf8(sam_writer.into_inner()).unwrap();
+    let fields: Vec<&str> = sam.trim_end().split('\t').collect();
+    assert_eq!(&fields[3..6], &["21", "255", "60M"]);
+    assert_eq!(&fields[11..], &["NM:i:1", "MD:Z:30G29", "AS:i:0"]);
+}
+
+/// Returns the number of alignment records.
+fn validate_sam(
+    sam: &str,
+    reference: &Reference,
+    cigar_style: SamCigarStyle,
+    query_count: usize,
+) -> usize {
+    let mut lines = sam.lines();
+    assert_eq!(lines.next().unwrap(), "@HD\tVN:1.6\tSO:unsorted");
+    let mut target_indices_by_label = std::collections::HashMap::new();
+    for target_index in 0..reference.get_num_targets() {
+        let line = lines.next().unwrap();
+        let label = reference.get_label(target_index).unwrap();
+        let length = reference.get_sequence(target_index).unwrap().len();
+        assert_eq!(line, format!("@SQ\tSN:{}\tLN:{}", label, length));
+        target_indices_by_label.insert(label, target_index);
+    }
+    assert!(lines.next().unwrap().starts_with("@PG\t"));
+
+    let mut primary_count = 0;
+    let mut record_count = 0;
+    for line in lines {
+        let fields: Vec<&str> = line.split('\t').collect();
+        let flag: u16 = fields[1].parse().unwrap();
+        if flag & 0x4 != 0 {
+            assert_eq!(fields.len(), 11);
+            continue;
+        }
+        assert_eq!(fields.len(), 14);
+        if flag & 0x900 == 0 {
+            primary_count += 1;
+        }
+        record_count += 1;
+
+        let target_index = target_indices_by_label[fields[2]];
+        let target = reference.get_sequence(target_index).unwrap();
+        let seq = fields[9].as_bytes();
+
+        // Walk CIGAR to recompute NM and MD
+        let mut query_position = 0;
+        let mut target_position = fields[3].parse::<usize>().unwrap() - 1;
+        let mut edit_distance = 0;
+        let mut md = String::new();
+        let mut match_count = 0;
+        let mut count = 0;
+        for c in fields[5].chars() {
+            if let Some(digit) = c.to_digit(10) {
+                count = count * 10 + digit as usize;
+                continue;
+            }
+            match c {
+                'S' => query_position += count,
+                '=' | 'X' | 'M' => {
+                    assert_ne!(c, if cigar_style == SamCigarStyle::Extended { 'M' } else { '=' });
+                    for _ in 0..count {
+                        let is_match = seq[query_position] == target[target_position];
+                        if c == '=' { assert!(is_match) }
+                        if c == 'X' { assert!(!is_match) }
+                        if is_match {
+                            match_count += 1;
+                        } else {
+                            edit_distance += 1;
+                            md.push_str(&format!("{}{}", match_count, target[target_position] as char));
+                            match_count = 0;
+                        }
+                        query_position += 1;
+                        target_position += 1;
+                    }
+                },
+                'I' => {
+                    edit_distance += count;
+                    query_position += count;
+                },
+                'D' => {
+                    edit_distance += count;
+                    md.push_str(&format!("{}^", match_count));
+                    target[target_position..target_position+count].iter().for_each(|&b| md.push(b as char));
+                    match_count = 0;
+                    target_position += count;
+                },
+                _ => panic!("Unexpected CIGAR operation: {}", c),
+            }
+            count = 0;
+        }
+        md.push_str(&match_count.to_string());
+        assert_eq!(query_position, seq.len());
+        assert_eq!(fields[11], format!("NM:i:{}", edit_distance));
+        assert_eq!(fields[12], format!("MD:Z:{}", md));
+        assert!(fields[13].starts_with("AS:i:"));
+    }
+    assert!(primary_count <= query_count);
+    record_count
+}
@@ -0,0 +1,149 @@
+use crate::common::{
+    init_logger,
+    test_data::DataForValidation,
+    directory_path::get_target_dir,
+};
+
+use std::io::Write;
+use flate2::{write::GzEncoder, Compression};
+use sigalign_utils::sequence_reader::{
+    fasta::FastaReader,
+    SeqRecord as _,
+    IdRecord as _,
+};
+use sigalign::{
+    Aligner,
+    ReferenceBuilder,
+    Strand,
+    algorithms::Local,
+    results::LabeledQueryAlignment,
+};
+
+const QUERY_COUNT: usize = 30;
+
+#[test]
+fn test_align_fasta_fastq_and_reader() {
+    init_logger();
+
+    let (ref_file, qry_file) = DataForValidation::Default.get_data_paths();
+    let reference = ReferenceBuilder::new()
+        .add_fasta_file(&ref_file).unwrap()
+        .build().unwrap();
+    let mut aligner = Aligner::new(Local::new(4, 6, 2, 50, 0.1).unwrap());
+
+    // Expected results
+    let mut records = Vec::new();
+    let mut fasta_reader = FastaReader::from_path(&qry_file).unwrap();
+    for _ in 0..QUERY_COUNT {
+        let Some(mut record) = fasta_reader.next() else { break };
+        let mut id = String::new();
+        record.extend_id_string(&mut id).unwrap();
+        let mut query = Vec::new();
+        record.extend_seq_buf(&mut query);
+        records.push((id, query));
+    }
+    let expected: Vec<(String, LabeledQueryAlignment)> = records.iter().map(|(id, query)| {
+        let result = aligner.align(query, &reference);
+        (id.clone(), reference.label_query_alignment(result))
+    }).collect();
+
+    // Make inputs
+    let mut fasta_bytes = Vec::new();
+    let mut fastq_bytes = Vec::new();
+    for (id, query) in &records {
+        writeln!(fasta_bytes, ">{} description\n{}", id, String::from_utf8_lossy(query)).unwrap();
+        writeln!(fastq_bytes, "@{}\n{}\n+\n{}", id, String::from_utf8_lossy(query), "I".repeat(query.len())).unwrap();
+    }
+    let gzip_fasta_bytes = {
+        // Two members like `bgzip`
+        let (first, second) = fasta_bytes.split_at(fasta_bytes.len() / 2);
+        let second_start = second.iter().position(|&b| b == b'>').unwrap();
+        let (first, second) = (
+            [first, &second[..second_start]].concat(),
+            second[second_start..].to_vec(),
+        );
+        let mut bytes = Vec::new();
+        for member in [first, second] {
+            let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+            encoder.write_all(&member).unwrap();
+            bytes.extend(encoder.finish().unwrap());
+        }
+        bytes
+    };
+    let tmp_dir = get_target_dir().unwrap().join("tmp_for_sequence_file_alignment");
+    std::fs::create_dir_all(&tmp_dir).unwrap();
+    let fastq_gz_file = tmp_dir.join("query.fq.gz");
+    {
+        let mut encoder = GzEncoder::new(std::fs::File::create(&fastq_gz_file).unwrap(), Compression::default());
+        encoder.write_all(&fastq_bytes).unwrap();
+        encoder.finish().unwrap();
+    }
+
+    // Compare
+    let results: Vec<_> = aligner.align_fasta(&qry_file, &reference).unwrap().take(QUERY_COUNT).collect::<Result<_, _>>().unwrap();
+    assert_same_results(&expected, &results);
+    let results: Vec<_> = aligner.align_fastq(&fastq_gz_file, &reference).unwrap().collect::<Result<_, _>>().unwrap();
+    assert_same_results(&expected, &results);
+    for bytes in [&fasta_bytes, &fastq_bytes, &gzip_fasta_bytes] {
+        let results: Vec<_> = aligner.align_reader(&bytes[..], &reference).unwrap().collect::<Result<_, _>>().unwrap();
+        assert_same_results(&expected, &results);
+    }
+
+    // Stranded
+    let expected_stranded: Vec<String> = records.iter().map(|(id, query)| {
+        let result = aligner.align_stranded(query, &reference, Strand::Both);
+        format!("{} {:?}", id, reference.label_stranded_query_alignment(result))
+    }).collect();
+    let results: Vec<String> = aligner.align_reader_stranded(&fastq_bytes[..], &reference, Strand::Both).unwrap().map(|result| {
+        let (id, result) = result.unwrap();
+        format!("{} {:?}", id, result)
+    }).collect();
+    assert_eq!(expected_stranded, results);
+    let results = aligner.align_fasta_stranded(&qry_file, &reference, Strand::Both).unwrap().take(QUERY_COUNT).count();
+    assert_eq!(results, expected_stranded.len());
+
+    // Invalid inputs
+    assert!(aligner.align_reader(&b"ACGT\n"[..], &reference).is_err());
+    assert!(aligner.align_fasta(tmp_dir.join("not_existing.fa"), &reference).is_err());
+    assert_eq!(aligner.align_reader(&b""[..], &reference).unwrap().count(), 0);
+}
+
+fn assert_same_results(
+    expected: &[(String, LabeledQueryAlignment)],
+    results: &[(String, LabeledQueryAlignment)],
+) {
+    assert_eq!(expected.len(), results.len());
+    for ((expected_id, expected_result), (id, result)) in expected.iter().zip(results.iter()) {
+        assert_eq!(expected_id, id);
+        let mut expected_result = expected_result.0.clone();
+        let mut result = result.0.clone();
+        expected_result.sort_by_key(|x| x.index);
+        result.sort_by_key(|x| x.index);
+        assert_eq!(expected_result.len(), result.len());
+        for (exp, res) in expected_result.iter().zip(result.iter()) {
+            assert_eq!(exp.label, res.label);
+            assert_eq!(exp.alignments, res.alignments);
+        }
+    }
+}
+
+#[test]
+fn test_error_of_records_is_returned() {
+    init_logger();
+
+    let reference = ReferenceBuilder::new()
+        .add_target("target", b"ACGTACGTACGTACGTACGTACGTACGTACGTACGTACGTACGTACGTACGTACGT")
+        .build().unwrap();
+    let mut aligner = Aligner::new(Local::new(4, 6, 2, 50, 0.1).unwrap());
+
+    // The second record has no separator line (`+`)
+    let fastq_bytes = b"@query_1\nACGTACGT\n+\nIIIIIIII\n@query_2\nACGTACGT\nIIIIIIII\n";
+    let results: Vec<_> = aligner.align_reader(&fastq_bytes[..], &reference).unwrap().collect();
+    assert_eq!(results.len(), 2);
+    assert_eq!(results[0].as_ref().unwrap().0, "query_1");
+    assert!(results[1].is_err());
+
+    let results: Vec<_> = aligner.align_reader_stranded(&fastq_bytes[..], &reference, Strand::Both).unwrap().collect();
+    assert_eq!(results.len(), 2);
+    assert!(results[1].is_err());
+}
@@ -0,0 +1,131 @@
+use crate::common::{
+    init_logger,
+    test_data::DataForValidation,
+    directory_path::get_target_dir,
+    random_text_and_pattern::gen_rand_text,
+};
+
+use sigalign_utils::sequence_reader::{
+    fasta::FastaReader,
+    SeqRecord as _,
+};
+use sigalign::{
+    Aligner,
+    ReferenceBuilder,
+    ShardedReference,
+    ShardedReferenceBuilder,
+    ShardLoading,
+    algorithms::{Local, LocalWithLimit},
+    results::LabeledQueryAlignment,
+};
+
+const QUERY_COUNT: usize = 30;
+
+fn sorted_debug_string(mut result: LabeledQueryAlignment) -> String {
+    result.0.sort_by_key(|x| x.index);
+    format!("{:?}", result)
+}
+
+#[test]
+fn test_sharded_reference_gives_same_results() {
+    init_logger();
+
+    let (ref_file, qry_file) = DataForValidation::Default.get_data_paths();
+    let reference = ReferenceBuilder::new()
+        .add_fasta_file(&ref_file).unwrap()
+        .build().unwrap();
+    let total_length = reference.get_total_length();
+
+    let manifest_file = get_target_dir().unwrap().join("sharded_reference_works.manifest");
+    let sharded_reference = ShardedReferenceBuilder::new()
+        .set_max_shard_length((total_length / 4) as u32)
+        .set_shard_loading(ShardLoading::Resident)
+        .build_from_fasta_file(&ref_file, &manifest_file).unwrap();
+    assert!(sharded_reference.get_num_shards() >= 4);
+    assert_eq!(sharded_reference.get_num_targets(), reference.get_num_targets());
+    assert_eq!(sharded_reference.get_total_length(), total_length);
+    let mut next_start = 0;
+    for shard_index in 0..sharded_reference.get_num_shards() {
+        let range = sharded_reference.get_target_range_of_shard(shard_index).unwrap();
+        assert_eq!(range.start, next_start);
+        next_start = range.end;
+    }
+    assert_eq!(next_start, reference.get_num_targets());
+
+    let lazy_sharded_reference = ShardedReference::load_from_manifest(
+        &manifest_file, ShardLoading::Lazy,
+    ).unwrap();
+    let resident_sharded_reference = ShardedReference::load_from_manifest(
+        &manifest_file, ShardLoading::Resident,
+    ).unwrap();
+    assert_eq!(lazy_sharded_reference.get_num_shards(), sharded_reference.get_num_shards());
+
+    let mut aligner = Aligner::new(Local::new(4, 6, 2, 50, 0.1).unwrap());
+    let mut queries = Vec::new();
+    let mut fasta_reader = FastaReader::from_path(&qry_file).unwrap();
+    for _ in 0..QUERY_COUNT {
+        let Some(mut record) = fasta_reader.next() else { break };
+        let mut query = Vec::new();
+        record.extend_seq_buf(&mut query);
+        queries.push(query);
+    }
+
+    let batch_results = aligner.align_sharded_batch(&queries, &lazy_sharded_reference).unwrap();
+    for (query, batch_result) in queries.iter().zip(batch_results) {
+        let expected = sorted_debug_string(
+            reference.label_query_alignment(aligner.align(query, &reference))
+        );
+        for sharded in [&sharded_reference, &lazy_sharded_reference, &resident_sharded_reference] {
+            let result = aligner.align_sharded(query, sharded).unwrap();
+            assert_eq!(expected, sorted_debug_string(result));
+        }
+        assert_eq!(expected, sorted_debug_string(batch_result));
+    }
+}
+
+#[test]
+fn test_limit_is_applied_to_merged_result() {
+    init_logger();
+
+    // The same sequence in every shard
+    let num_shards = 4;
+    let sequence = gen_rand_text(b"ACGT", 300, 300);
+    let mut fasta = Vec::new();
+    for index in 0..num_shards {
+        fasta.extend_from_slice(format!(">target_{}\n", index).as_bytes());
+        fasta.extend_from_slice(&sequence);
+        fasta.push(b'\n');
+    }
+    let fasta_file = get_target_dir().unwrap().join("sharded_reference_limit.fa");
+    std::fs::write(&fasta_file, &fasta).unwrap();
+    let manifest_file = get_target_dir().unwrap().join("sharded_reference_limit.manifest");
+    let sharded_reference = ShardedReferenceBuilder::new()
+        .set_max_shard_length(sequence.len() as u32)
+        .set_shard_loading(ShardLoading::Resident)
+        .build_from_fasta_file(&fasta_file, &manifest_file).unwrap();
+    assert_eq!(sharded_reference.get_num_shards(), num_shards);
+
+    let limit = 2;
+    let mut aligner = Aligner::new(LocalWithLimit::new(4, 6, 2, 50, 0.1, limit).unwrap());
+    let count = |result: &LabeledQueryAlignment| -> usize {
+        result.0.iter().map(|x| x.alignments.len()).sum()
+    };
+    let result = aligner.align_sharded(&sequence, &sharded_reference).unwrap();
+    assert_eq!(count(&result), limit as usize);
+    assert!(result.0.iter().all(|x| !x.alignments.is_empty()));
+    let batch_results = aligner.align_sharded_batch(&[&sequence], &sharded_reference).unwrap();
+    assert_eq!(format!("{:?}", result), format!("{:?}", batch_results[0]));
+
+    let mut unlimited_aligner = Aligner::new(Local::new(4, 6, 2, 50, 0.1).unwrap());
+    let result = unlimited_aligner.align_sharded(&sequence, &sharded_reference).unwrap();
+    assert_eq!(count(&result), num_shards);
+}
+
+#[test]
+fn test_invalid_manifest_is_rejected() {
+    init_logger();
+
+    let manifest_file = get_target_dir().unwrap().join("sharded_reference_invalid.manifest");
+    std::fs::write(&manifest_file, "not a manifest\n").unwrap();
+    assert!(ShardedReference::load_from_manifest(&manifest_file, ShardLoading::Lazy).is_err());
+}
@@ -0,0 +1,153 @@
+use crate::common::{
+    init_logger,
+    test_data::DataForValidation,
+};
+
+use log::info;
+use sigalign_utils::{
+    sequence_reader::{
+        fasta::FastaReader,
+        SeqRecord as _,
+    },
+    sequence_manipulation::reverse_complementary::reverse_complement_of_dna_sequence,
+};
+use sigalign::{
+    Aligner,
+    Strand,
+    ReferenceBuilder,
+    algorithms::Local,
+    results::{QueryAlignment, TargetAlignment},
+};
+
+#[test]
+fn test_forward_strand_is_equal_to_default_alignment() {
+    init_logger();
+
+    let (ref_file, qry_file) = DataForValidation::Default.get_data_paths();
+    let reference = ReferenceBuilder::new()
+        .add_fasta_file(&ref_file).unwrap()
+        .build().unwrap();
+
+    let mut aligner = Aligner::new(Local::new(4, 6, 2, 50, 0.1).unwrap());
+    assert_eq!(Strand::default(), Strand::Forward);
+
+    let mut fasta_reader = FastaReader::from_path(&qry_file).unwrap();
+    let mut query_buffer = Vec::new();
+    for _ in 0..50 {
+        let Some(mut record) = fasta_reader.next() else { break };
+        query_buffer.clear();
+        record.extend_seq_buf(&mut query_buffer);
+
+        let default_results = aligner.align(&query_buffer, &reference);
+        let stranded_results = aligner.align_stranded(&query_buffer, &reference, Strand::Forward);
+        assert!(stranded_results.reverse.0.is_empty());
+        assert_eq!(
+            sorted_alignments(default_results),
+            sorted_alignments(stranded_results.forward),
+        );
+    }
+}
+
+#[test]
+fn test_reverse_strand_is_mapped_to_forward_coordinates() {
+    init_logger();
+
+    let (ref_file, qry_file) = DataForValidation::Default.get_data_paths();
+    let reference = ReferenceBuilder::new()
+        .add_fasta_file(&ref_file).unwrap()
+        .build().unwrap();
+
+    let mut aligner = Aligner::new(Local::new(4, 6, 2, 50, 0.1).unwrap());
+
+    let mut fasta_reader = FastaReader::from_path(&qry_file).unwrap();
+    let mut query_buffer = Vec::new();
+    let mut reverse_alignment_count = 0;
+    for _ in 0..50 {
+        let Some(mut record) = fasta_reader.next() else { break };
+        query_buffer.clear();
+        record.extend_seq_buf(&mut query_buffer);
+        let query_length = query_buffer.len() as u32;
+
+        // Alignments of the reverse complement are the reverse strand of the original query.
+        let reverse_complement = reverse_complement_of_dna_sequence(&query_buffer);
+        let stranded_results = aligner.align_stranded(&query_buffer, &reference, Strand::Both);
+        let stranded_results_of_rc = aligner.align_stranded(&reverse_complement, &reference, Strand::Both);
+
+        let mut expected = stranded_results_of_rc.forward;
+        expected.0.iter_mut().for_each(|tgt_aln| {
+            tgt_aln.alignments.iter_mut().for_each(|aln| {
+                let (start, end) = aln.position.query;
+                aln.position.query = (query_length - end, query_length - start);
+            })
+        });
+        reverse_alignment_count += stranded_results.reverse.count_alignments();
+        stranded_results.reverse.0.iter().for_each(|tgt_aln| {
+            tgt_aln.alignments.iter().for_each(|aln| {
+                assert!(aln.position.query.1 <= query_length);
+            })
+        });
+        assert_eq!(
+            sorted_alignments(expected),
+            sorted_alignments(stranded_results.reverse),
+        );
+    }
+    info!("Number of alignments in reverse strand: {}", reverse_alignment_count);
+}
+
+fn sorted_alignments(query_alignment: QueryAlignment) -> Vec<(u32, sigalign::results::Alignment)> {
+    let mut alignments: Vec<_> = query_alignment.0.into_iter().flat_map(|tgt_aln| {
+        let index = tgt_aln.index;
+        tgt_aln.alignments.into_iter().map(move |aln| (index, aln))
+    }).collect();
+    alignments.sort_by(|a, b| {
+        a.0.cmp(&b.0)
+            .then(a.1.position.target.cmp(&b.1.position.target))
+            .then(a.1.position.query.cmp(&b.1.position.query))
+    });
+    alignments
+}
+
+#[test]
+fn test_stranded_target_alignments_keep_strand() {
+    init_logger();
+
+    let (ref_file, qry_file) = DataForValidation::Default.get_data_paths();
+    let reference = ReferenceBuilder::new()
+        .add_fasta_file(&ref_file).unwrap()
+        .build().unwrap();
+
+    let mut aligner = Aligner::new(Local::new(4, 6, 2, 50, 0.1).unwrap());
+
+    let mut fasta_reader = FastaReader::from_path(&qry_file).unwrap();
+    let mut query_buffer = Vec::new();
+    for _ in 0..20 {
+        let Some(mut record) = fasta_reader.next() else { break };
+        query_buffer.clear();
+        record.extend_seq_buf(&mut query_buffer);
+
+        let stranded_results = aligner.align_stranded(&query_buffer, &reference, Strand::Both);
+        let alignment_count = stranded_results.count_alignments();
+        let (forward, reverse) = (
+            sorted_alignments(stranded_results.forward.clone()),
+            sorted_alignments(stranded_results.reverse.clone()),
+        );
+
+        let target_alignments = stranded_results.into_stranded_target_alignments();
+        assert!(target_alignments.windows(2).all(|x| x[0].index < x[1].index));
+        assert_eq!(
+            target_alignments.iter().map(|tgt_aln| tgt_aln.alignments.len()).sum::<usize>(),
+            alignment_count,
+        );
+        for strand in [Strand::Forward, Strand::Reverse] {
+            let query_alignment = QueryAlignment(target_alignments.iter().map(|tgt_aln| TargetAlignment {
+                index: tgt_aln.index,
+                alignments: tgt_aln.alignments.iter()
+                    .filter(|stranded_aln| stranded_aln.strand == strand)
+                    .map(|stranded_aln| stranded_aln.alignment.clone())
+                    .collect(),
+            }).collect());
+            let expected = if strand == Strand::Forward { &forward } else { &reverse };
+            assert_eq!(&sorted_alignments(query_alignment), expected);
+        }
+    }
+}
@@ -0,0 +1,201 @@
+use crate::common::{
+    init_logger,
+    random_text_and_pattern::gen_rand_text_with_rng,
+    test_data::DataForValidation,
+    dynamic_programming_matrix::{
+        dp_local_with_substitution_matrix_to_target,
+        dp_semi_global_with_substitution_matrix_to_target,
+    },
+    substitution_matrix_validation::assert_alignment_is_valid,
+};
+
+use rand::{Rng, SeedableRng, rngs::StdRng};
+use sigalign_utils::sequence_reader::{
+    fasta::FastaReader,
+    SeqRecord as _,
+};
+use sigalign::{
+    Aligner,
+    Reference,
+    ReferenceBuilder,
+    algorithms::{Algorithm, Local, SemiGlobal, SubstitutionMatrix},
+    results::{Alignment, QueryAlignment},
+};
+
+const QUERY_COUNT: usize = 20;
+const NUCLEOTIDES: &[u8] = b"ACGT";
+const RANDOM_QUERY_COUNT: usize = 6;
+const SEED: u64 = 21;
+
+fn get_reference() -> Reference {
+    let (ref_file, _) = DataForValidation::Default.get_data_paths();
+    ReferenceBuilder::new()
+        .add_fasta_file(&ref_file).unwrap()
+        .build().unwrap()
+}
+
+fn get_queries() -> Vec<Vec<u8>> {
+    let (_, qry_file) = DataForValidation::Default.get_data_paths();
+    let mut fasta_reader = FastaReader::from_path(&qry_file).unwrap();
+    let mut queries = Vec::new();
+    while let Some(mut record) = fasta_reader.next() {
+        if queries.len() == QUERY_COUNT {
+            break
+        }
+        let mut query = Vec::new();
+        record.extend_seq_buf(&mut query);
+        queries.push(query);
+    }
+    queries
+}
+
+fn get_results<A: Algorithm>(algorithm: A, reference: &Reference, queries: &[Vec<u8>]) -> Vec<String> {
+    let mut aligner = Aligner::new(algorithm);
+    queries.iter().map(|query| {
+        let mut result = aligner.align(query, reference);
+        result.0.sort_by_key(|x| x.index);
+        format!("{:?}", result)
+    }).collect()
+}
+
+#[test]
+fn test_uniform_substitution_matrix_gives_same_results() {
+    init_logger();
+
+    let reference = get_reference();
+    let queries = get_queries();
+    for (px, po, pe, minl, maxp) in [(4, 6, 2, 50, 0.1), (3, 5, 2, 100, 0.05)] {
+        let substitution_matrix = SubstitutionMatrix::new(px).unwrap();
+        assert_eq!(
+            get_results(Local::with_substitution_matrix(substitution_matrix.clone(), po, pe, minl, maxp).unwrap(), &reference, &queries),
+            get_results(Local::new(px, po, pe, minl, maxp).unwrap(), &reference, &queries),
+        );
+        let substitution_matrix = SubstitutionMatrix::dna_transition_transversion(px, px).unwrap();
+        assert_eq!(
+            get_results(SemiGlobal::with_substitution_matrix(substitution_matrix, po, pe, minl, maxp).unwrap(), &reference, &queries),
+            get_results(SemiGlobal::new(px, po, pe, minl, maxp).unwrap(), &reference, &queries),
+        );
+    }
+}
+
+#[test]
+fn test_results_follow_substitution_matrix() {
+    init_logger();
+
+    let reference = get_reference();
+    let queries = get_queries();
+    let (po, pe, minl, maxp) = (6, 2, 50, 0.1);
+    let substitution_matrix = SubstitutionMatrix::dna_transition_transversion(2, 5).unwrap();
+
+    let mut local_aligner = Aligner::new(
+        Local::with_substitution_matrix(substitution_matrix.clone(), po, pe, minl, maxp).unwrap()
+    );
+    let mut semi_global_aligner = Aligner::new(
+        SemiGlobal::with_substitution_matrix(substitution_matrix.clone(), po, pe, minl, maxp).unwrap()
+    );
+    let mut alignment_count = 0;
+    for query in queries.iter() {
+        for result in [
+            local_aligner.align(query, &reference),
+            semi_global_aligner.align(query, &reference),
+        ] {
+            for target_alignment in result.0 {
+                let target = reference.get_sequence(target_alignment.index).unwrap();
+                for alignment in target_alignment.alignments {
+                    assert_alignment_is_valid(&alignment, query, &target, &substitution_matrix, (po, pe), minl, maxp);
+                    alignment_count += 1;
+                }
+            }
+        }
+    }
+    assert!(alignment_count > 0);
+}
+
+
+// Sequence with the substitutions (transitions are more frequent) and a few indels
+fn gen_variant(rng: &mut StdRng, sequence: &[u8]) -> Vec<u8> {
+    let mut variant: Vec<u8> = sequence.iter().map(|&base| {
+        if rng.gen_bool(0.03) {
+            match base {
+                b'A' => b'G',
+                b'G' => b'A',
+                b'C' => b'T',
+                _ => b'C',
+            }
+        } else if rng.gen_bool(0.01) {
+            NUCLEOTIDES[rng.gen_range(0..NUCLEOTIDES.len())]
+        } else {
+            base
+        }
+    }).collect();
+    for _ in 0..rng.gen_range(0..=2) {
+        let position = rng.gen_range(0..variant.len());
+        if rng.gen_bool(0.5) {
+            variant.remove(position);
+        } else {
+            variant.insert(position, NUCLEOTIDES[rng.gen_range(0..NUCLEOTIDES.len())]);
+        }
+    }
+    variant
+}
+
+#[test]
+fn test_all_alignments_of_dpm_are_found() {
+    init_logger();
+
+    let mut rng = StdRng::seed_from_u64(SEED);
+    let (po, pe, minl, maxp) = (6, 2, 50, 0.1);
+    let substitution_matrix = SubstitutionMatrix::dna_transition_transversion(2, 5).unwrap();
+    let mut local_aligner = Aligner::new(
+        Local::with_substitution_matrix(substitution_matrix.clone(), po, pe, minl, maxp).unwrap()
+    );
+    let mut semi_global_aligner = Aligner::new(
+        SemiGlobal::with_substitution_matrix(substitution_matrix.clone(), po, pe, minl, maxp).unwrap()
+    );
+
+    let mut dpm_alignment_count = 0;
+    for _ in 0..RANDOM_QUERY_COUNT {
+        // Query is a variant of the region in the middle of the target
+        let region = gen_rand_text_with_rng(&mut rng, NUCLEOTIDES, 120, 120);
+        let query = gen_variant(&mut rng, &region);
+        let mut target = gen_rand_text_with_rng(&mut rng, NUCLEOTIDES, 30, 30);
+        target.extend_from_slice(&region);
+        target.extend(gen_rand_text_with_rng(&mut rng, NUCLEOTIDES, 30, 30));
+        let reference = ReferenceBuilder::new().add_target("target", &target).build().unwrap();
+
+        let local_alignments = get_alignments(local_aligner.align(&query, &reference));
+        let dpm_alignments = dp_local_with_substitution_matrix_to_target(&query, &target, &substitution_matrix, po, pe, minl, maxp);
+        assert_all_dpm_alignments_are_found(&local_alignments, &dpm_alignments);
+        dpm_alignment_count += dpm_alignments.len();
+
+        let semi_global_alignments = get_alignments(semi_global_aligner.align(&query, &reference));
+        let dpm_alignments = dp_semi_global_with_substitution_matrix_to_target(&query, &target, &substitution_matrix, po, pe, minl, maxp);
+        assert_all_dpm_alignments_are_found(&semi_global_alignments, &dpm_alignments);
+        dpm_alignment_count += dpm_alignments.len();
+    }
+    assert!(dpm_alignment_count > 0);
+}
+
+fn get_alignments(result: QueryAlignment) -> Vec<Alignment> {
+    result.0.into_iter().flat_map(|target_alignment| target_alignment.alignments).collect()
+}
+
+// The alignment of DPM is found as it is,
+// or the overlapping alignment is more optimal (longer, or less penalty with the same length).
+fn assert_all_dpm_alignments_are_found(alignments: &[Alignment], dpm_alignments: &[Alignment]) {
+    let is_overlapped = |range1: (u32, u32), range2: (u32, u32)| range1.0 < range2.1 && range2.0 < range1.1;
+    dpm_alignments.iter().for_each(|dpm_alignment| {
+        assert!(
+            alignments.iter().any(|alignment| {
+                (
+                    alignment.position == dpm_alignment.position && alignment.penalty == dpm_alignment.penalty
+                ) || (
+                    is_overlapped(alignment.position.query, dpm_alignment.position.query)
+                    && is_overlapped(alignment.position.target, dpm_alignment.position.target)
+                    && (alignment.length, dpm_alignment.penalty) > (dpm_alignment.length, alignment.penalty)
+                )
+            }),
+            "Alignment of DPM is not found: {:?}\nAlignments: {:?}", dpm_alignment, alignments,
+        );
+    });
+}
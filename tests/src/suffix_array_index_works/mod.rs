@@ -0,0 +1,113 @@
+use crate::common::{
+    init_logger,
+    test_data::DataForValidation,
+    pattern_index_option::get_dynamic_lfi_option,
+    random_text_and_pattern::gen_rand_text,
+};
+
+use sigalign_utils::sequence_reader::{
+    fasta::FastaReader,
+    SeqRecord as _,
+};
+use sigalign_core::{
+    aligner::{AlignmentRegulator, local::LocalAligner},
+    reference::{
+        Reference as RawReference,
+        PatternIndex,
+        SequenceStorage,
+        extensions::{Serialize, EstimateSize},
+    },
+};
+use sigalign_impl::{
+    pattern_index::{
+        dynamic_lfi::DynamicLfi,
+        suffix_array::{SuffixArrayIndex, SuffixArrayIndexOption},
+    },
+    sequence_storage::in_memory::InMemoryStorage,
+};
+
+const QUERY_COUNT: usize = 30;
+
+#[test]
+fn test_suffix_array_index_locates_same_positions() {
+    init_logger();
+
+    // Random text with long repeats
+    let mut text = gen_rand_text(b"ACGT", 100_000, 100_000);
+    let repeat = text[..5_000].to_vec();
+    text.extend_from_slice(&repeat);
+    text.resize(text.len() + 3_000, b'N');
+    text.extend_from_slice(&repeat);
+
+    let dynamic_lfi = DynamicLfi::new(text.clone(), get_dynamic_lfi_option(1)).unwrap();
+    let single_thread_index = SuffixArrayIndex::new(
+        text.clone(), SuffixArrayIndexOption::new(1),
+    ).unwrap();
+    let multi_thread_index = SuffixArrayIndex::new(
+        text.clone(), SuffixArrayIndexOption::new(4),
+    ).unwrap();
+    assert_eq!(single_thread_index, multi_thread_index);
+
+    for pattern_size in [1, 5, 10, 20, 50, 200] {
+        for start in (0..text.len() - pattern_size).step_by(997) {
+            let pattern = &text[start..start + pattern_size];
+            assert_eq!(
+                dynamic_lfi.get_sorted_positions(pattern),
+                multi_thread_index.get_sorted_positions(pattern),
+            );
+        }
+        // Not existing pattern
+        let pattern = vec![b'T'; pattern_size + 20];
+        assert_eq!(
+            dynamic_lfi.get_sorted_positions(&pattern),
+            multi_thread_index.get_sorted_positions(&pattern),
+        );
+    }
+    // Pattern at the end of sequence
+    let pattern = &text[text.len() - 30..];
+    assert_eq!(
+        dynamic_lfi.get_sorted_positions(pattern),
+        multi_thread_index.get_sorted_positions(pattern),
+    );
+
+    // Serialization
+    let mut buffer = Vec::new();
+    multi_thread_index.save_to(&mut buffer).unwrap();
+    assert_eq!(buffer.len(), multi_thread_index.serialized_size());
+    let loaded = SuffixArrayIndex::load_from(&buffer[..]).unwrap();
+    assert_eq!(multi_thread_index, loaded);
+}
+
+#[test]
+fn test_alignment_with_suffix_array_index() {
+    init_logger();
+
+    let (ref_file, qry_file) = DataForValidation::Default.get_data_paths();
+    let mut in_memory_storage = InMemoryStorage::new();
+    in_memory_storage.add_fasta(std::fs::File::open(ref_file).unwrap()).unwrap();
+
+    let lfi_reference: RawReference<DynamicLfi, InMemoryStorage> = RawReference::new(
+        in_memory_storage.clone(), get_dynamic_lfi_option(1),
+    ).unwrap();
+    let sa_reference: RawReference<SuffixArrayIndex, InMemoryStorage> = RawReference::new(
+        in_memory_storage, SuffixArrayIndexOption::default(),
+    ).unwrap();
+
+    let mut aligner = LocalAligner::new(AlignmentRegulator::new(4, 6, 2, 50, 0.1).unwrap());
+    let target_indices: Vec<u32> = (0..lfi_reference.num_targets()).collect();
+    let mut lfi_buffer = lfi_reference.get_sequence_storage().get_buffer();
+    let mut sa_buffer = sa_reference.get_sequence_storage().get_buffer();
+
+    let mut fasta_reader = FastaReader::from_path(&qry_file).unwrap();
+    let mut query = Vec::new();
+    for _ in 0..QUERY_COUNT {
+        let Some(mut record) = fasta_reader.next() else { break };
+        query.clear();
+        record.extend_seq_buf(&mut query);
+        let mut expected = aligner.align(&query, &lfi_reference, &mut lfi_buffer, &target_indices);
+        let mut result = aligner.align(&query, &sa_reference, &mut sa_buffer, &target_indices);
+        expected.0.sort_by_key(|x| x.index);
+        result.0.sort_by_key(|x| x.index);
+        assert_eq!(format!("{:?}", expected), format!("{:?}", result));
+    }
+}
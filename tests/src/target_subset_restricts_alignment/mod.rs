@@ -0,0 +1,85 @@
+use crate::common::{
+    init_logger,
+    test_data::DataForValidation,
+};
+
+use log::info;
+use sigalign_utils::sequence_reader::{
+    fasta::FastaReader,
+    SeqRecord as _,
+};
+use sigalign::{
+    Aligner,
+    ReferenceBuilder,
+    TargetSubsetError,
+    algorithms::Local,
+};
+
+#[test]
+fn test_target_subset_is_sorted_and_deduplicated() {
+    init_logger();
+
+    let (ref_file, _) = DataForValidation::Default.get_data_paths();
+    let reference = ReferenceBuilder::new()
+        .add_fasta_file(&ref_file).unwrap()
+        .build().unwrap();
+    let num_targets = reference.get_num_targets();
+    assert!(num_targets >= 3);
+
+    // By indices
+    let subset = reference.get_target_subset_by_indices(&[2, 0, 2, 1, 0]).unwrap();
+    assert_eq!(subset.get_sorted_target_indices(), &[0, 1, 2]);
+    assert!(subset.contains(1));
+    assert!(!subset.contains(3));
+
+    let err = reference.get_target_subset_by_indices(&[0, num_targets]).unwrap_err();
+    assert!(matches!(err, TargetSubsetError::IndexOutOfRange { .. }));
+
+    // By labels
+    let labels: Vec<String> = [2, 0, 2].iter().map(|&i| reference.get_label(i).unwrap()).collect();
+    let subset = reference.get_target_subset_by_labels(&labels).unwrap();
+    assert_eq!(subset.get_sorted_target_indices(), &[0, 2]);
+
+    let err = reference.get_target_subset_by_labels(&["not_existing_label"]).unwrap_err();
+    assert!(matches!(err, TargetSubsetError::UnknownLabel(_)));
+}
+
+#[test]
+fn test_alignment_to_target_subset_is_part_of_full_alignment() {
+    init_logger();
+
+    let (ref_file, qry_file) = DataForValidation::Default.get_data_paths();
+    let reference = ReferenceBuilder::new()
+        .add_fasta_file(&ref_file).unwrap()
+        .build().unwrap();
+    let odd_indices: Vec<u32> = (0..reference.get_num_targets()).filter(|x| x % 2 == 1).collect();
+    let subset = reference.get_target_subset_by_indices(&odd_indices).unwrap();
+
+    let mut aligner = Aligner::new(Local::new(4, 6, 2, 50, 0.1).unwrap());
+
+    let mut fasta_reader = FastaReader::from_path(&qry_file).unwrap();
+    let mut query_buffer = Vec::new();
+    let mut query_count = 0;
+    while let Some(mut record) = fasta_reader.next() {
+        if query_count == 100 {
+            break;
+        }
+        query_buffer.clear();
+        record.extend_seq_buf(&mut query_buffer);
+
+        let mut full_results = aligner.align(&query_buffer, &reference).0;
+        full_results.retain(|x| subset.contains(x.index));
+        full_results.sort_by_key(|x| x.index);
+
+        let mut subset_results = aligner.align_to_subset(&query_buffer, &reference, &subset).0;
+        subset_results.sort_by_key(|x| x.index);
+
+        assert_eq!(full_results.len(), subset_results.len());
+        for (full, sub) in full_results.iter().zip(subset_results.iter()) {
+            assert_eq!(full.index, sub.index);
+            assert_eq!(full.alignments, sub.alignments);
+        }
+        query_count += 1;
+    }
+    info!("{} queries are aligned to target subset", query_count);
+}
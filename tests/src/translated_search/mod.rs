@@ -0,0 +1,198 @@
+use crate::common::{
+    init_logger,
+    random_text_and_pattern::gen_rand_text_with_rng,
+};
+
+use rand::{Rng, SeedableRng, rngs::StdRng};
+use sigalign_utils::sequence_manipulation::{
+    reverse_complementary::reverse_complement_of_dna_sequence,
+    translation::translate_dna_sequence,
+};
+use sigalign::{
+    Aligner,
+    GeneticCode,
+    Reference,
+    ReferenceBuilder,
+    algorithms::{SemiGlobal, SubstitutionMatrix},
+    results::{AlignmentOperation, TranslatedAlignment, TranslatedQueryAlignment},
+};
+
+const AMINO_ACIDS: &[u8] = b"ACDEFGHIKLMNPQRSTVWY";
+const NUCLEOTIDES: &[u8] = b"ACGT";
+const TARGET_COUNT: usize = 10;
+const QUERY_COUNT: usize = 30;
+const SEED: u64 = 24;
+const PARAMS: (u32, u32, u32, f32) = (11, 1, 30, 0.3);
+
+
+fn gen_random_nucleotides(rng: &mut StdRng, length: usize) -> Vec<u8> {
+    (0..length).map(|_| NUCLEOTIDES[rng.gen_range(0..NUCLEOTIDES.len())]).collect()
+}
+
+// Pick a random codon for each amino acid
+fn back_translate(rng: &mut StdRng, protein: &[u8], genetic_code: GeneticCode) -> Vec<u8> {
+    let codons_of_amino_acid = |amino_acid: u8| -> Vec<[u8; 3]> {
+        NUCLEOTIDES.iter().flat_map(|&first| NUCLEOTIDES.iter().flat_map(move |&second| {
+            NUCLEOTIDES.iter().map(move |&third| [first, second, third])
+        })).filter(|codon| genetic_code.translate_codon(codon) == amino_acid).collect()
+    };
+    protein.iter().flat_map(|&amino_acid| {
+        let codons = codons_of_amino_acid(amino_acid);
+        codons[rng.gen_range(0..codons.len())]
+    }).collect()
+}
+
+fn build_protein_reference(targets: &[Vec<u8>]) -> Reference {
+    targets.iter().enumerate().fold(ReferenceBuilder::new_protein(), |builder, (index, target)| {
+        builder.add_target(&format!("protein_{}", index), target)
+    }).build().unwrap()
+}
+
+fn get_aligner() -> Aligner<SemiGlobal> {
+    let (po, pe, minl, maxp) = PARAMS;
+    Aligner::new(
+        SemiGlobal::with_substitution_matrix(SubstitutionMatrix::blosum62(), po, pe, minl, maxp).unwrap()
+    )
+}
+
+fn find_alignment(
+    result: &TranslatedQueryAlignment,
+    target_index: u32,
+    frame: i8,
+    query_position: (u32, u32),
+    target_position: (u32, u32),
+) -> Option<&TranslatedAlignment> {
+    result.0.iter()
+        .find(|target_alignment| target_alignment.index == target_index)?
+        .alignments.iter()
+        .find(|translated_alignment| {
+            translated_alignment.frame == frame
+            && translated_alignment.alignment.position.query == query_position
+            && translated_alignment.alignment.position.target == target_position
+        })
+}
+
+#[test]
+fn test_genetic_code_translates_codons() {
+    let standard = GeneticCode::default();
+    assert_eq!(standard, GeneticCode::Standard);
+    assert_eq!(standard.translate_codon(b"ATG"), b'M');
+    assert_eq!(standard.translate_codon(b"aug"), b'M');
+    assert_eq!(standard.translate_codon(b"TGA"), b'*');
+    assert_eq!(standard.translate_codon(b"AGA"), b'R');
+    assert_eq!(standard.translate_codon(b"ANG"), b'X');
+
+    let vertebrate_mitochondrial = GeneticCode::from_ncbi_table_id(2).unwrap();
+    assert_eq!(vertebrate_mitochondrial, GeneticCode::VertebrateMitochondrial);
+    assert_eq!(vertebrate_mitochondrial.translate_codon(b"TGA"), b'W');
+    assert_eq!(vertebrate_mitochondrial.translate_codon(b"AGA"), b'*');
+    assert_eq!(vertebrate_mitochondrial.translate_codon(b"ATA"), b'M');
+
+    for table_id in [1, 2, 3, 4, 5, 6, 11] {
+        assert_eq!(GeneticCode::from_ncbi_table_id(table_id).unwrap().ncbi_table_id(), table_id);
+    }
+    assert_eq!(GeneticCode::from_ncbi_table_id(7), None);
+
+    assert_eq!(translate_dna_sequence(b"ATGTTTTAAGC", standard), b"MF*".to_vec());
+}
+
+#[test]
+fn test_translated_query_is_aligned_in_correct_frame() {
+    init_logger();
+
+    let mut rng = StdRng::seed_from_u64(SEED);
+    let targets: Vec<Vec<u8>> = (0..TARGET_COUNT).map(|_| {
+        let length = rng.gen_range(200..400);
+        gen_rand_text_with_rng(&mut rng, AMINO_ACIDS, length, length)
+    }).collect();
+    let reference = build_protein_reference(&targets);
+    let mut aligner = get_aligner();
+
+    for query_index in 0..QUERY_COUNT {
+        let target_index = rng.gen_range(0..targets.len());
+        let target = &targets[target_index];
+        let length = rng.gen_range(60..100);
+        let start = rng.gen_range(0..target.len() - length);
+        let fragment = &target[start..start + length];
+
+        // Frame offset is the length of the leading bases (shorter than a codon)
+        let offset = rng.gen_range(0..3);
+        let mut query = gen_random_nucleotides(&mut rng, offset);
+        query.extend(back_translate(&mut rng, fragment, GeneticCode::Standard));
+        let trailing_length = rng.gen_range(0..3);
+        query.extend(gen_random_nucleotides(&mut rng, trailing_length));
+        let coding_region = (offset as u32, (offset + length * 3) as u32);
+        let target_position = (start as u32, (start + length) as u32);
+
+        // Half of the queries are the reverse complement
+        let is_reverse = query_index % 2 == 1;
+        let (query, frame, query_position) = if is_reverse {
+            let query_length = query.len() as u32;
+            (
+                reverse_complement_of_dna_sequence(&query),
+                -(offset as i8 + 1),
+                (query_length - coding_region.1, query_length - coding_region.0),
+            )
+        } else {
+            (query, offset as i8 + 1, coding_region)
+        };
+
+        let result = aligner.align_translated(&query, &reference);
+        let translated_alignment = find_alignment(
+            &result, target_index as u32, frame, query_position, target_position,
+        ).unwrap_or_else(|| panic!(
+            "Alignment of frame {} is not found at query {:?} and target {:?}: {:?}",
+            frame, query_position, target_position, result,
+        ));
+        assert_eq!(translated_alignment.alignment.penalty, 0);
+        assert_eq!(translated_alignment.alignment.length, length as u32);
+        assert_eq!(translated_alignment.alignment.operations.len(), 1);
+        assert_eq!(translated_alignment.alignment.operations[0].operation, AlignmentOperation::Match);
+
+        // Translation of the reported query region is the aligned target region
+        let (query_start, query_end) = (query_position.0 as usize, query_position.1 as usize);
+        let query_region = if is_reverse {
+            reverse_complement_of_dna_sequence(&query[query_start..query_end])
+        } else {
+            query[query_start..query_end].to_vec()
+        };
+        assert_eq!(translate_dna_sequence(&query_region, GeneticCode::Standard), fragment);
+
+        // Lowercase query gives the same frames
+        let lowercase_result = aligner.align_translated(&query.to_ascii_lowercase(), &reference);
+        assert_eq!(format!("{:?}", lowercase_result), format!("{:?}", result));
+    }
+}
+
+#[test]
+fn test_genetic_code_of_aligner_is_used_for_translation() {
+    init_logger();
+
+    let mut rng = StdRng::seed_from_u64(SEED);
+    let mut target = gen_rand_text_with_rng(&mut rng, AMINO_ACIDS, 120, 120);
+    // Tryptophan is encoded by `TGA` in the vertebrate mitochondrial code
+    target.iter_mut().step_by(10).for_each(|amino_acid| *amino_acid = b'W');
+    let reference = build_protein_reference(&[target.clone()]);
+    let mut query = back_translate(&mut rng, &target, GeneticCode::VertebrateMitochondrial);
+    query.chunks_exact_mut(3).zip(target.iter()).for_each(|(codon, &amino_acid)| {
+        if amino_acid == b'W' {
+            codon.copy_from_slice(b"TGA");
+        }
+    });
+    let query_position = (0, query.len() as u32);
+    let target_position = (0, target.len() as u32);
+
+    let mut aligner = get_aligner();
+    assert_eq!(aligner.get_genetic_code(), GeneticCode::Standard);
+    let result = aligner.align_translated(&query, &reference);
+    assert!(
+        find_alignment(&result, 0, 1, query_position, target_position)
+            .map(|translated_alignment| translated_alignment.alignment.penalty != 0)
+            .unwrap_or(true)
+    );
+
+    aligner.set_genetic_code(GeneticCode::VertebrateMitochondrial);
+    let result = aligner.align_translated(&query, &reference);
+    let translated_alignment = find_alignment(&result, 0, 1, query_position, target_position).unwrap();
+    assert_eq!(translated_alignment.alignment.penalty, 0);
+}
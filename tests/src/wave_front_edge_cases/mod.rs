@@ -0,0 +1,76 @@
+use crate::common::{
+    init_logger,
+    substitution_matrix_validation::assert_alignment_is_valid,
+};
+
+use sigalign::{
+    Aligner,
+    ReferenceBuilder,
+    algorithms::{Algorithm, Local, SemiGlobal, SubstitutionMatrix},
+    results::{AlignmentOperation, AlignmentOperations, AlignmentPosition},
+};
+
+// (mismatch, gap-open, gap-extend, minimum length, maximum penalty per length)
+type Regulator = (u32, u32, u32, u32, f32);
+
+fn assert_results_are_valid<A: Algorithm>(
+    aligner: &mut Aligner<A>,
+    query: &[u8],
+    target: &[u8],
+    (px, po, pe, minl, maxp): Regulator,
+) {
+    let reference = ReferenceBuilder::new().add_target("target", target).build().unwrap();
+    let result = aligner.align(query, &reference);
+    assert!(!result.0.is_empty());
+    result.0.iter().flat_map(|target_alignment| target_alignment.alignments.iter()).for_each(|alignment| {
+        assert_alignment_is_valid(
+            alignment, query, target, &SubstitutionMatrix::new(px).unwrap(),
+            (po, pe), minl, maxp,
+        );
+    });
+}
+
+// The operations of the left extension can start with a gap.
+//  - Previously, the separator in the operations buffer was a deletion,
+//    and the leading gap of the left extension was merged into it (and lost).
+#[test]
+fn test_leading_gap_of_extension_is_kept() {
+    init_logger();
+
+    let query = b"CGAAAACGGCCGGTCAAGTTGAGACGTATTTCAG";
+    let target = b"CGACGGCCGGTCAAGTTGAGACGTATTTCAG";
+    let regulator = (6, 2, 1, 20, 0.3);
+    let (px, po, pe, minl, maxp) = regulator;
+
+    let mut semi_global = Aligner::new(SemiGlobal::new(px, po, pe, minl, maxp).unwrap());
+    assert_results_are_valid(&mut semi_global, query, target, regulator);
+    let mut local = Aligner::new(Local::new(px, po, pe, minl, maxp).unwrap());
+    assert_results_are_valid(&mut local, query, target, regulator);
+}
+
+// The substitution is not extended from the empty component of the previous wave front score.
+//  - Previously, the empty component (FR of 0) was extended to the component with FR of 1,
+//    and the bogus component hid the longer alignment.
+#[test]
+fn test_substitution_is_not_extended_from_empty_component() {
+    init_logger();
+
+    let query = b"TTAAATCATGATAACGTTCCGGGAGAAGGGG";
+    let target = b"TTAACTCATGATAACGTTCCGGAGGCCGAGAAGGCG";
+    let regulator = (2, 3, 1, 20, 0.3);
+    let (px, po, pe, minl, maxp) = regulator;
+
+    let mut local = Aligner::new(Local::new(px, po, pe, minl, maxp).unwrap());
+    assert_results_are_valid(&mut local, query, target, regulator);
+    let reference = ReferenceBuilder::new().add_target("target", target).build().unwrap();
+    let alignment = &local.align(query, &reference).0[0].alignments[0];
+    assert_eq!(alignment.position, AlignmentPosition { query: (0, 29), target: (0, 34) });
+    assert_eq!(alignment.penalty, 10);
+    assert_eq!(alignment.operations, vec![
+        AlignmentOperations { operation: AlignmentOperation::Match, count: 4 },
+        AlignmentOperations { operation: AlignmentOperation::Subst, count: 1 },
+        AlignmentOperations { operation: AlignmentOperation::Match, count: 17 },
+        AlignmentOperations { operation: AlignmentOperation::Deletion, count: 5 },
+        AlignmentOperations { operation: AlignmentOperation::Match, count: 7 },
+    ]);
+}